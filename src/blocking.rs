@@ -0,0 +1,86 @@
+use crate::error::{SResult, TwtScrapeError::Io};
+use crate::id::UserId;
+use crate::scrape::{Scraper, ScraperBuilder};
+use crate::tweet::Tweet;
+use crate::user::User;
+use crate::usertweets::UserTweetsAndReplies;
+use std::future::Future;
+use std::sync::OnceLock;
+use tokio::runtime::{Builder, Runtime};
+
+// sync wrappers around the async api for callers without a runtime of their own. every call
+// blocks the thread on one runtime shared by the whole process, which also drives the scraper's
+// connections between calls. calling these from inside an async context panics, use the async
+// versions there.
+
+static RUNTIME: OnceLock<std::io::Result<Runtime>> = OnceLock::new();
+
+fn block_on<F: Future>(future: F) -> SResult<F::Output> {
+    let runtime = RUNTIME.get_or_init(|| {
+        Builder::new_multi_thread()
+            .enable_all()
+            .thread_name("twtscrape-blocking")
+            .build()
+    });
+    match runtime {
+        Ok(runtime) => Ok(runtime.block_on(future)),
+        // the error stays in the `OnceLock`, every call gets a copy of it
+        Err(why) => Err(Io(std::io::Error::new(why.kind(), why.to_string()))),
+    }
+}
+
+impl ScraperBuilder {
+    pub fn finish_blocking(self) -> SResult<Scraper> {
+        block_on(self.finish())?
+    }
+}
+
+impl User {
+    pub fn new_blocking(scraper: &Scraper, handle: impl AsRef<str>) -> SResult<Self> {
+        block_on(User::new(scraper, handle))?
+    }
+
+    pub fn by_id_blocking(scraper: &Scraper, id: UserId) -> SResult<Self> {
+        block_on(User::by_id(scraper, id))?
+    }
+}
+
+impl Tweet {
+    pub fn get_blocking(scraper: &Scraper, tweet_id: &str) -> SResult<Tweet> {
+        block_on(Tweet::get(scraper, tweet_id))?
+    }
+
+    pub fn parse_thread_blocking(
+        scraper: &Scraper,
+        tweet_id: &str,
+        max_quote_depth: Option<usize>,
+    ) -> SResult<(Vec<Tweet>, Vec<User>)> {
+        block_on(Tweet::parse_thread(scraper, tweet_id, max_quote_depth))?
+    }
+}
+
+impl UserTweetsAndReplies {
+    pub fn scroll_user_timeline_blocking(
+        scraper: &Scraper,
+        user_handle: String,
+        max_tweets: Option<u32>,
+    ) -> SResult<Self> {
+        block_on(UserTweetsAndReplies::scroll_user_timeline(
+            scraper,
+            user_handle,
+            max_tweets,
+        ))?
+    }
+}
+
+#[test]
+fn blocking_without_a_runtime() {
+    let scraper = ScraperBuilder::new()
+        .with_dry_run(vec![
+            r#"{"errors":[{"message":"Could not authenticate you.","code":32}]}"#.to_string(),
+        ])
+        .finish_blocking()
+        .unwrap();
+    assert!(User::by_id_blocking(&scraper, UserId(783214)).is_err());
+    assert_eq!(scraper.stop_dry_run().len(), 1);
+}
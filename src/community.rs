@@ -0,0 +1,374 @@
+use crate::error::SResult;
+use crate::error::TwtScrapeError::{CommunityUnavailable, TwitterBadRestId};
+#[cfg(feature = "scrape")]
+use crate::graphql::GraphQlRequest;
+#[cfg(feature = "scrape")]
+use crate::paginate::{HasCursor, Pager};
+#[cfg(feature = "scrape")]
+use crate::scrape::Scraper;
+use crate::tweet::{EntryVariant, FilterCursorTweetRequest, Instruction, Tweet, TweetResults};
+use crate::user::{Error, TwtUsrResult, User};
+use ahash::{HashSet, HashSetExt};
+use rkyv::Archive;
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "scrape")]
+use tracing::warn;
+
+#[cfg(feature = "scrape")]
+pub fn twitter_request_url_community(id: u64) -> String {
+    GraphQlRequest::new("lUBKrilodgg9Nikaw3cIiA", "CommunityQuery")
+        .variable("communityId", id.to_string())
+        .variable("withDmMuting", false)
+        .variable("withSafetyModeUserFields", false)
+        .feature("responsive_web_graphql_timeline_navigation_enabled", true)
+        .build()
+}
+
+#[cfg(feature = "scrape")]
+#[test]
+fn community_url() {
+    assert_eq!(twitter_request_url_community(1), "https://twitter.com/i/api/graphql/lUBKrilodgg9Nikaw3cIiA/CommunityQuery?variables=%7B%22communityId%22%3A%221%22%2C%22withDmMuting%22%3Afalse%2C%22withSafetyModeUserFields%22%3Afalse%7D&features=%7B%22responsive_web_graphql_timeline_navigation_enabled%22%3Atrue%7D");
+}
+
+#[cfg(feature = "scrape")]
+pub fn twitter_request_url_community_tweets(id: u64, cursor: Option<impl AsRef<str>>) -> String {
+    match cursor {
+        Some(cursor) => {
+            let crsr = urlencoding::encode(cursor.as_ref());
+            format!("https://twitter.com/i/api/graphql/7B2AdxSuC-Er8qUr3Plm_w/CommunityTweetsTimeline?variables=%7B%22communityId%22%3A%22{id}%22%2C%22count%22%3A20%2C%22cursor%22%3A%22{crsr}%22%2C%22withCommunity%22%3Atrue%7D&features=%7B%22responsive_web_twitter_blue_verified_badge_is_enabled%22%3Atrue%2C%22verified_phone_label_enabled%22%3Afalse%2C%22responsive_web_graphql_timeline_navigation_enabled%22%3Atrue%2C%22tweetypie_unmention_optimization_enabled%22%3Atrue%2C%22vibe_api_enabled%22%3Atrue%2C%22responsive_web_edit_tweet_api_enabled%22%3Atrue%2C%22graphql_is_translatable_rweb_tweet_is_translatable_enabled%22%3Atrue%2C%22standardized_nudges_misinfo%22%3Atrue%2C%22interactive_text_enabled%22%3Atrue%2C%22responsive_web_enhance_cards_enabled%22%3Atrue%7D")
+        }
+        None => {
+            format!("https://twitter.com/i/api/graphql/7B2AdxSuC-Er8qUr3Plm_w/CommunityTweetsTimeline?variables=%7B%22communityId%22%3A%22{id}%22%2C%22count%22%3A20%2C%22withCommunity%22%3Atrue%7D&features=%7B%22responsive_web_twitter_blue_verified_badge_is_enabled%22%3Atrue%2C%22verified_phone_label_enabled%22%3Afalse%2C%22responsive_web_graphql_timeline_navigation_enabled%22%3Atrue%2C%22tweetypie_unmention_optimization_enabled%22%3Atrue%2C%22vibe_api_enabled%22%3Atrue%2C%22responsive_web_edit_tweet_api_enabled%22%3Atrue%2C%22graphql_is_translatable_rweb_tweet_is_translatable_enabled%22%3Atrue%2C%22standardized_nudges_misinfo%22%3Atrue%2C%22interactive_text_enabled%22%3Atrue%2C%22responsive_web_enhance_cards_enabled%22%3Atrue%7D")
+        }
+    }
+}
+
+#[derive(
+    Clone,
+    Debug,
+    Hash,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+    Archive,
+    rkyv::Serialize,
+    rkyv::Deserialize,
+)]
+pub struct Community {
+    pub id: u64,
+    pub tweets: Vec<Tweet>,
+    pub users: Vec<User>,
+}
+
+#[derive(
+    Clone,
+    Debug,
+    Hash,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+    Archive,
+    rkyv::Serialize,
+    rkyv::Deserialize,
+)]
+pub struct CommunityInfo {
+    pub id: u64,
+    pub name: String,
+    pub description: Option<String>,
+    pub member_count: u64,
+    pub rules: Vec<CommunityRule>,
+}
+
+#[derive(
+    Clone,
+    Debug,
+    Hash,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+    Archive,
+    rkyv::Serialize,
+    rkyv::Deserialize,
+)]
+pub struct CommunityRule {
+    pub name: String,
+    pub description: Option<String>,
+}
+
+#[cfg(feature = "scrape")]
+impl Community {
+    #[tracing::instrument]
+    pub async fn info(scraper: &Scraper, community_id: u64) -> SResult<CommunityInfo> {
+        let request = scraper
+            .api_req::<CommunityInfoRequest>(
+                scraper.make_get_req(twitter_request_url_community(community_id)),
+            )
+            .await?;
+
+        for warning in crate::user::collect_warnings(&request.errors)? {
+            warn!(code = %warning.code, message = warning.message, "Community returned with errors");
+        }
+
+        let community = match request.data.community_results.result {
+            CommunityResult::Community(community) => community,
+            CommunityResult::CommunityUnavailable => {
+                return Err(CommunityUnavailable(community_id))
+            }
+        };
+
+        if community.rest_id.is_empty() || community.rest_id == "0" {
+            return Err(TwitterBadRestId("Community RestID", community.rest_id));
+        }
+
+        Ok(CommunityInfo {
+            id: community
+                .rest_id
+                .parse::<u64>()
+                .map_err(|why| TwitterBadRestId("Community RestID", why.to_string()))?,
+            name: community.name,
+            description: crate::as_option!(community.description, ""),
+            member_count: community.member_count,
+            rules: community
+                .rules
+                .into_iter()
+                .map(|rule| CommunityRule {
+                    name: rule.name,
+                    description: rule.description,
+                })
+                .collect(),
+        })
+    }
+
+    // tweets or authors that fail to parse are skipped. on cancellation the tweets of the pages
+    // fetched so far are returned
+    #[tracing::instrument]
+    pub async fn tweets(scraper: &Scraper, community_id: u64) -> SResult<Self> {
+        let mut tweets = Vec::with_capacity(20);
+        let mut users = Vec::with_capacity(20);
+        let mut already_parsed_users = HashSet::with_capacity(20);
+
+        let mut pager = Pager::new(scraper, FilterCursorTweetRequest::Bottom, None, |cursor| {
+            twitter_request_url_community_tweets(community_id, cursor)
+        });
+        while let Some(request) = pager.next_page::<CommunityTweetsRequest>().await? {
+            for inst in request.into_instructions() {
+                if let Instruction::TimelineAddEntries(add) = inst {
+                    for entry in add.entries {
+                        if let EntryVariant::Tweet(twt) = entry.content {
+                            let tweet = match Tweet::new_from_entry(&twt.item_content.tweet_results)
+                            {
+                                Ok(tweet) => tweet,
+                                Err(why) => {
+                                    warn!(error = %why, "Failed to parse community tweet. Skipping...");
+                                    continue;
+                                }
+                            };
+                            if tweet.id == 0 {
+                                continue;
+                            }
+                            tweets.push(tweet);
+
+                            if let TweetResults::Ok(trr) = twt.item_content.tweet_results {
+                                if let TwtUsrResult::User(user) = &trr.core.user_results.result {
+                                    if !already_parsed_users.insert(user.rest_id.clone()) {
+                                        continue;
+                                    }
+                                }
+                                match User::from_result(scraper, trr.core.user_results.result).await
+                                {
+                                    Ok(user) => users.push(user),
+                                    Err(why) => {
+                                        warn!(error = %why, "Failed to get data. Skipping...")
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        tweets.shrink_to_fit();
+        users.shrink_to_fit();
+
+        Ok(Community {
+            id: community_id,
+            tweets,
+            users,
+        })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub(crate) struct CommunityInfoRequest {
+    #[serde(default)]
+    pub errors: Vec<Error>,
+    pub data: CommunityInfoData,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub(crate) struct CommunityInfoData {
+    #[serde(rename = "communityResults")]
+    pub community_results: CommunityResults,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub(crate) struct CommunityResults {
+    pub result: CommunityResult,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "__typename")]
+pub(crate) enum CommunityResult {
+    Community(CommunityData),
+    CommunityUnavailable,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub(crate) struct CommunityData {
+    pub rest_id: String,
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    pub member_count: u64,
+    #[serde(default)]
+    pub rules: Vec<CommunityRuleData>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub(crate) struct CommunityRuleData {
+    pub name: String,
+    pub description: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub(crate) struct CommunityTweetsRequest {
+    #[serde(default)]
+    pub errors: Vec<Error>,
+    pub data: CommunityTweetsData,
+}
+
+#[cfg(feature = "scrape")]
+impl CommunityTweetsRequest {
+    pub(crate) fn filter_cursor(&self, filter: FilterCursorTweetRequest) -> Option<&str> {
+        self.data
+            .community_results
+            .result
+            .ranked_community_timeline
+            .timeline
+            .instructions
+            .iter()
+            .find_map(|inst| inst.filter_cursor(filter))
+    }
+
+    pub(crate) fn into_instructions(self) -> Vec<Instruction> {
+        self.data
+            .community_results
+            .result
+            .ranked_community_timeline
+            .timeline
+            .instructions
+    }
+}
+
+#[cfg(feature = "scrape")]
+crate::impl_filter_json!(CommunityTweetsRequest);
+
+#[cfg(feature = "scrape")]
+impl HasCursor for CommunityTweetsRequest {
+    fn filter_cursor(&self, filter: FilterCursorTweetRequest) -> Option<&str> {
+        CommunityTweetsRequest::filter_cursor(self, filter)
+    }
+
+    fn entry_count(&self) -> usize {
+        self.data
+            .community_results
+            .result
+            .ranked_community_timeline
+            .timeline
+            .instructions
+            .iter()
+            .map(Instruction::entry_count)
+            .sum()
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub(crate) struct CommunityTweetsData {
+    #[serde(rename = "communityResults")]
+    pub community_results: CommunityTimelineResults,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub(crate) struct CommunityTimelineResults {
+    pub result: CommunityTimelineResult,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub(crate) struct CommunityTimelineResult {
+    pub ranked_community_timeline: CommunityTimeline,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub(crate) struct CommunityTimeline {
+    pub timeline: CommunityInstructions,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub(crate) struct CommunityInstructions {
+    pub instructions: Vec<Instruction>,
+}
+
+#[cfg(all(test, feature = "scrape"))]
+fn community_page(entries: Vec<serde_json::Value>) -> String {
+    serde_json::json!({
+        "data": {"communityResults": {"result": {"ranked_community_timeline": {"timeline": {
+            "instructions": [{"type": "TimelineAddEntries", "entries": entries}],
+        }}}}},
+    })
+    .to_string()
+}
+
+#[cfg(feature = "scrape")]
+#[test]
+fn unavailable_community() {
+    use crate::scrape::dry_run;
+
+    let canned = serde_json::json!({
+        "data": {"communityResults": {"result": {"__typename": "CommunityUnavailable"}}},
+    });
+
+    dry_run(vec![canned.to_string()], |scraper| async move {
+        assert!(matches!(
+            Community::info(&scraper, 7).await,
+            Err(CommunityUnavailable(7))
+        ));
+    });
+}
+
+#[cfg(feature = "scrape")]
+#[test]
+fn broken_community_tweets_are_skipped() {
+    use crate::scrape::dry_run;
+    use crate::tweet::{tweet_entry_fixture, tweet_results_fixture};
+
+    let good = 1700000000000000001;
+    let broken = 1700000000000000002;
+    let canned = vec![community_page(vec![
+        tweet_entry_fixture(good, tweet_results_fixture(good, 12, serde_json::json!({}))),
+        tweet_entry_fixture(
+            broken,
+            tweet_results_fixture(broken, 13, serde_json::json!({"created_at": "yesterday"})),
+        ),
+    ])];
+
+    dry_run(canned, |scraper| async move {
+        let community = Community::tweets(&scraper, 7).await.unwrap();
+        assert_eq!(community.tweets.len(), 1);
+        assert_eq!(community.tweets[0].id, good);
+        assert_eq!(community.users.len(), 1);
+    });
+}
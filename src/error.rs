@@ -1,3 +1,6 @@
+use rkyv::Archive;
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
 use std::num::ParseIntError;
 use thiserror::Error;
 
@@ -20,7 +23,7 @@ pub enum TwtScrapeError {
     #[error("Failed to build client: {0:?}")]
     ClientBuildError(reqwest::Error),
     #[error("Twitter JSON Error: Code {0}, {1}")]
-    TwitterJSONError(i32, String),
+    TwitterJSONError(TwitterErrorCode, String),
     #[error("Bad Rest ID (type {0}): {1}")]
     TwitterBadRestId(&'static str, String),
     #[error(
@@ -29,10 +32,44 @@ pub enum TwtScrapeError {
     TwitterBadTimeParse(String),
     #[error("The User's JSON as returned by Twitter was not AvailableUser.")]
     UserResultError,
+    // `reason` is twitter's own, e.g. "Suspended"
+    #[error("User Unavailable ({reason}): {message}")]
+    UserUnavailable { reason: String, message: String },
+    #[error("Tweet {id} Unavailable ({reason:?})")]
+    TweetUnavailable {
+        id: crate::id::TweetId,
+        reason: crate::tweet::TombstoneReason,
+    },
+    #[error("Community {0} Unavailable")]
+    CommunityUnavailable(u64),
     #[error("This Timeline Entry `{0}` Not Supported")]
     BadTimelineEntry(String),
     #[error("Schema Error for {0}: {1}")]
-    BadJSONSchema(&'static str, String)
+    BadJSONSchema(&'static str, String),
+    #[error("Tweet {0} is not translatable")]
+    NotTranslatable(crate::id::TweetId),
+    #[error("Invalid Archive: {0}")]
+    InvalidArchive(String),
+    // twitter turned the guest token or account down, see `Scraper::validate`
+    #[error("Invalid Credentials: {0}")]
+    InvalidCredentials(String),
+    #[error("{0} requires an authenticated scraper (auth_token cookie)")]
+    NotAuthenticated(&'static str),
+    #[error("Invalid Handle: {0}")]
+    InvalidHandle(String),
+    #[error("Dry run has no canned response left for {0}")]
+    DryRunExhausted(String),
+    #[error("Raw requests can't be answered during a dry run")]
+    DryRunRawRequest,
+    #[error("Not Found ({0})")]
+    NotFound(u16),
+    // `reset_at` is `None` when twitter's 429 didn't say when the window resets
+    #[error("Rate limited{}", .reset_at.map(|at| format!(" until {at}")).unwrap_or_default())]
+    RateLimited {
+        reset_at: Option<chrono::DateTime<chrono::Utc>>,
+    },
+    #[error("IO Error: {0}")]
+    Io(std::io::Error),
 }
 
 impl From<ParseIntError> for TwtScrapeError {
@@ -41,4 +78,130 @@ impl From<ParseIntError> for TwtScrapeError {
     }
 }
 
+#[derive(
+    Copy,
+    Clone,
+    Debug,
+    Hash,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+    Archive,
+    rkyv::Serialize,
+    rkyv::Deserialize,
+)]
+#[cfg_attr(feature = "validation", archive(check_bytes))]
+pub enum TwitterErrorCode {
+    BadAuthentication,
+    PageNotFound,
+    Ignore,
+    UserSuspended,
+    AccountSuspended,
+    RateLimitExceeded,
+    OverCapacity,
+    InternalError,
+    AlreadyFavorited,
+    NotAuthorized,
+    AccountLocked,
+    Unknown(i32),
+}
+
+impl TwitterErrorCode {
+    pub const fn code(&self) -> i32 {
+        match self {
+            TwitterErrorCode::BadAuthentication => 32,
+            TwitterErrorCode::PageNotFound => 34,
+            TwitterErrorCode::Ignore => 37,
+            TwitterErrorCode::UserSuspended => 63,
+            TwitterErrorCode::AccountSuspended => 64,
+            TwitterErrorCode::RateLimitExceeded => 88,
+            TwitterErrorCode::OverCapacity => 130,
+            TwitterErrorCode::InternalError => 131,
+            TwitterErrorCode::AlreadyFavorited => 139,
+            TwitterErrorCode::NotAuthorized => 179,
+            TwitterErrorCode::AccountLocked => 326,
+            TwitterErrorCode::Unknown(code) => *code,
+        }
+    }
+
+    // twitter being overloaded, asking again a little later usually works
+    pub const fn is_retriable(&self) -> bool {
+        matches!(
+            self,
+            TwitterErrorCode::OverCapacity | TwitterErrorCode::InternalError
+        )
+    }
+
+    // anything else can show up next to a perfectly usable `data`
+    pub const fn is_fatal(&self) -> bool {
+        matches!(
+            self,
+            TwitterErrorCode::BadAuthentication
+                | TwitterErrorCode::RateLimitExceeded
+                | TwitterErrorCode::AccountLocked
+        )
+    }
+}
+
+impl From<i32> for TwitterErrorCode {
+    fn from(value: i32) -> Self {
+        match value {
+            32 => TwitterErrorCode::BadAuthentication,
+            34 => TwitterErrorCode::PageNotFound,
+            37 => TwitterErrorCode::Ignore,
+            63 => TwitterErrorCode::UserSuspended,
+            64 => TwitterErrorCode::AccountSuspended,
+            88 => TwitterErrorCode::RateLimitExceeded,
+            130 => TwitterErrorCode::OverCapacity,
+            131 => TwitterErrorCode::InternalError,
+            139 => TwitterErrorCode::AlreadyFavorited,
+            179 => TwitterErrorCode::NotAuthorized,
+            326 => TwitterErrorCode::AccountLocked,
+            code => TwitterErrorCode::Unknown(code),
+        }
+    }
+}
+
+impl Display for TwitterErrorCode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TwitterErrorCode::Unknown(code) => write!(f, "{code}"),
+            known => write!(f, "{} ({known:?})", known.code()),
+        }
+    }
+}
+
 pub type SResult<T> = Result<T, TwtScrapeError>;
+
+// what a scrape that keeps going past failures collected, and what failed along the way. every
+// failure says what it was for, e.g. `tweet 1655555555555555555` or `page <cursor>`.
+#[derive(Debug)]
+pub struct ScrapeOutcome<T> {
+    pub data: T,
+    pub errors: Vec<(String, TwtScrapeError)>,
+}
+
+impl<T> ScrapeOutcome<T> {
+    pub fn is_complete(&self) -> bool {
+        self.errors.is_empty()
+    }
+}
+
+#[derive(
+    Clone,
+    Debug,
+    Hash,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+    Archive,
+    rkyv::Serialize,
+    rkyv::Deserialize,
+)]
+#[cfg_attr(feature = "validation", archive(check_bytes))]
+pub struct TwitterWarning {
+    pub code: TwitterErrorCode,
+    pub message: String,
+}
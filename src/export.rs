@@ -0,0 +1,307 @@
+#[cfg(feature = "scrape")]
+use crate::error::{SResult, TwtScrapeError::Io};
+#[cfg(feature = "scrape")]
+use crate::scrape::Scraper;
+use crate::tweet::{Tweet, TweetType};
+use crate::user::User;
+use crate::usertweets::UserTweetsAndReplies;
+#[cfg(feature = "scrape")]
+use crate::usertweets::{UserTimeline, UserTweetAndRepliesRequest};
+#[cfg(feature = "scrape")]
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "scrape")]
+use std::io::Write;
+#[cfg(feature = "scrape")]
+use tracing::warn;
+
+// bumped whenever a field is renamed or removed, added fields don't bump it
+pub const EXPORT_SCHEMA_VERSION: u32 = 1;
+
+// a stable shape for archives, independent of how twitter's json or our own types change.
+// users and tweets are sorted by id so two exports of the same data are byte for byte equal.
+// ids are strings, they don't fit in the doubles most json tooling (jq, javascript) uses.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExportFormat {
+    pub schema_version: u32,
+    pub users: Vec<ExportUser>,
+    pub tweets: Vec<ExportTweet>,
+    pub next_cursor: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExportUser {
+    pub id: String,
+    pub handle: String,
+    pub display_name: String,
+    pub bio: String,
+    pub location: Option<String>,
+    pub website: Option<String>,
+    // rfc 3339, `None` if twitter's date didn't parse
+    pub joined: Option<String>,
+    pub avatar_url: String,
+    pub banner_url: String,
+    pub tweets: u32,
+    pub following: u32,
+    pub followers: u32,
+    pub likes: u32,
+    pub media_tweets: u32,
+    pub normal_followers: u32,
+    pub listed: u32,
+    pub verified: bool,
+    pub blue_verified: bool,
+    pub protected: bool,
+    pub sensitive: bool,
+    pub pinned_tweet_id: Option<String>,
+    pub withheld_in_countries: Vec<String>,
+}
+
+impl From<&User> for ExportUser {
+    fn from(user: &User) -> Self {
+        ExportUser {
+            id: user.id.to_string(),
+            handle: user.name.handle.clone(),
+            display_name: user.name.display.clone(),
+            bio: user.bio.clone(),
+            location: user.additional_info.location.clone(),
+            website: user.additional_info.website.clone(),
+            joined: user
+                .additional_info
+                .joined
+                .map(|joined| joined.to_rfc3339()),
+            avatar_url: user.avatar.url.clone(),
+            banner_url: user.avatar.banner.clone(),
+            tweets: user.profile_stats.tweets,
+            following: user.profile_stats.following,
+            followers: user.profile_stats.followers,
+            likes: user.profile_stats.likes,
+            media_tweets: user.profile_stats.media_tweets,
+            normal_followers: user.profile_stats.normal_followers,
+            listed: user.profile_stats.listed,
+            verified: user.profile_stats.verified,
+            blue_verified: user.profile_stats.blue_verified,
+            protected: user.is_protected,
+            sensitive: user.is_sensitive,
+            pinned_tweet_id: user.pinned_tweet_id.map(|id| id.to_string()),
+            withheld_in_countries: user.withheld_in_countries.clone(),
+        }
+    }
+}
+
+// tombstones only carry `id`, `conversation_id` and `tombstone`
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExportTweet {
+    pub id: String,
+    pub conversation_id: String,
+    pub author_id: Option<String>,
+    // rfc 3339
+    pub created: Option<String>,
+    pub text: Option<String>,
+    // twitter's text explaining why the tweet is gone
+    pub tombstone: Option<String>,
+    pub replying_to: Option<String>,
+    pub quoting: Option<String>,
+    pub retweeting: Option<String>,
+    pub edit_ids: Vec<String>,
+    pub media_urls: Vec<String>,
+    pub likes: u32,
+    pub retweets: u32,
+    pub replies: u32,
+    pub quote_tweets: u32,
+    pub withheld_in_countries: Vec<String>,
+    pub promoted: bool,
+}
+
+impl ExportTweet {
+    pub fn new(tweet: &Tweet, promoted: bool) -> Self {
+        let mut export = ExportTweet {
+            id: tweet.id.to_string(),
+            conversation_id: tweet.conversation_id.to_string(),
+            author_id: tweet.author_id().map(|id| id.to_string()),
+            created: None,
+            text: None,
+            tombstone: None,
+            replying_to: None,
+            quoting: None,
+            retweeting: None,
+            edit_ids: vec![],
+            media_urls: vec![],
+            likes: 0,
+            retweets: 0,
+            replies: 0,
+            quote_tweets: 0,
+            withheld_in_countries: tweet.withheld_in_countries().to_vec(),
+            promoted,
+        };
+        match &tweet.tweet_type {
+            TweetType::Tombstone(text) => export.tombstone = Some(text.clone()),
+            TweetType::Tweet(data) => {
+                export.created = Some(data.created.to_rfc3339());
+                export.text = Some(data.text.clone());
+                export.replying_to = data.reply_info.replying_to.map(|id| id.to_string());
+                export.quoting = data.reply_info.quoting.map(|id| id.to_string());
+                export.retweeting = data.reply_info.retweeting.map(|id| id.to_string());
+                export.edit_ids = data.edit_ids.iter().map(ToString::to_string).collect();
+                export.media_urls = data
+                    .entry
+                    .media
+                    .iter()
+                    .map(|media| media.media_url_https.clone())
+                    .collect();
+                export.likes = data.stats.likes;
+                export.retweets = data.stats.retweets;
+                export.replies = data.stats.replies;
+                export.quote_tweets = data.stats.quote_tweets;
+            }
+        }
+        export
+    }
+}
+
+impl UserTweetsAndReplies {
+    pub fn to_export(&self) -> ExportFormat {
+        let mut users = self.users.iter().collect::<Vec<_>>();
+        users.sort_by_key(|user| user.id);
+        let mut tweets = self.tweets.iter().collect::<Vec<_>>();
+        tweets.sort_by_key(|tweet| tweet.id);
+
+        ExportFormat {
+            schema_version: EXPORT_SCHEMA_VERSION,
+            users: users.into_iter().map(ExportUser::from).collect(),
+            tweets: tweets
+                .into_iter()
+                .map(|tweet| ExportTweet::new(tweet, self.is_promoted(tweet)))
+                .collect(),
+            next_cursor: self.next_cursor.clone(),
+        }
+    }
+
+    #[cfg(feature = "scrape")]
+    pub fn to_export_json(&self) -> serde_json::Value {
+        // plain structs with string keys, this can't fail
+        serde_json::to_value(self.to_export()).unwrap_or_default()
+    }
+}
+
+// writes every tweet on the user's timeline as one `ExportTweet` per line while the pages come
+// in, only the current page is ever held in memory. threads are not crawled, so other people's
+// replies are left out, and a tweet showing up on two pages is written twice. returns the number
+// of lines written.
+#[cfg(feature = "scrape")]
+#[tracing::instrument(skip(writer))]
+pub async fn write_ndjson<W: Write>(
+    scraper: &Scraper,
+    user_handle: &str,
+    mut writer: W,
+) -> SResult<u64> {
+    let user = User::new(scraper, user_handle).await?;
+
+    let mut written = 0;
+    let pages = UserTweetAndRepliesRequest::scroll_stream(
+        scraper,
+        UserTimeline::TweetsAndReplies,
+        user.id,
+        None,
+    );
+    futures::pin_mut!(pages);
+    while let Some(page) = pages.next().await {
+        written += write_ndjson_page(&page?, &mut writer)?;
+    }
+
+    writer.flush().map_err(Io)?;
+    Ok(written)
+}
+
+#[cfg(feature = "scrape")]
+fn write_ndjson_page<W: Write>(page: &UserTweetAndRepliesRequest, writer: &mut W) -> SResult<u64> {
+    let mut written = 0;
+    for (results, promoted) in page.tweet_results() {
+        if results.rest_id_or_skip().is_none() {
+            continue;
+        }
+        match Tweet::new_from_entry(results) {
+            Ok(tweet) => {
+                write_ndjson_record(writer, &ExportTweet::new(&tweet, promoted))?;
+                written += 1;
+            }
+            Err(why) => warn!(error = %why, "Failed to parse tweet. Skipping..."),
+        }
+    }
+    Ok(written)
+}
+
+#[cfg(feature = "scrape")]
+fn write_ndjson_record<W: Write>(writer: &mut W, tweet: &ExportTweet) -> SResult<()> {
+    serde_json::to_writer(&mut *writer, tweet).map_err(|why| Io(why.into()))?;
+    writer.write_all(b"\n").map_err(Io)
+}
+
+#[cfg(feature = "scrape")]
+#[test]
+fn ndjson_record_per_line() {
+    use crate::id::TweetId;
+
+    let mut out = Vec::new();
+    for id in [1700000000000000001_u64, 1700000000000000002] {
+        let tweet = Tweet {
+            id: TweetId(id),
+            conversation_id: TweetId(id),
+            posted_id: None,
+            tweet_type: TweetType::Tombstone("This Tweet was deleted.".to_string()),
+        };
+        write_ndjson_record(&mut out, &ExportTweet::new(&tweet, false)).unwrap();
+    }
+    let out = String::from_utf8(out).unwrap();
+    let lines = out.lines().collect::<Vec<_>>();
+    assert_eq!(lines.len(), 2);
+    let second = serde_json::from_str::<ExportTweet>(lines[1]).unwrap();
+    assert_eq!(second.id, "1700000000000000002");
+    assert!(out.ends_with('\n'));
+}
+
+#[cfg(feature = "scrape")]
+#[test]
+fn export_is_sorted_and_versioned() {
+    use crate::id::TweetId;
+    use ahash::{HashSet, HashSetExt};
+
+    let mut tweets = HashSet::new();
+    for id in [
+        1700000000000000003_u64,
+        1700000000000000001,
+        1700000000000000002,
+    ] {
+        tweets.insert(Tweet {
+            id: TweetId(id),
+            conversation_id: TweetId(id),
+            posted_id: None,
+            tweet_type: TweetType::Tombstone("This Tweet was deleted.".to_string()),
+        });
+    }
+    let timeline = UserTweetsAndReplies {
+        users: HashSet::new(),
+        tweets,
+        warnings: vec![],
+        promoted: vec![TweetId(1700000000000000002)],
+        next_cursor: None,
+    };
+
+    let json = timeline.to_export_json();
+    assert_eq!(json["schema_version"], EXPORT_SCHEMA_VERSION);
+    let ids = json["tweets"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|tweet| tweet["id"].as_str().unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        ids,
+        vec![
+            "1700000000000000001",
+            "1700000000000000002",
+            "1700000000000000003"
+        ]
+    );
+    assert_eq!(json["tweets"][1]["promoted"], true);
+    assert_eq!(json["tweets"][0]["tombstone"], "This Tweet was deleted.");
+}
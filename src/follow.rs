@@ -1,13 +1,20 @@
-use crate::error::SResult;
+use crate::error::{SResult, TwitterErrorCode, TwitterWarning, TwtScrapeError};
+#[cfg(feature = "scrape")]
+use crate::id::UserId;
+#[cfg(feature = "scrape")]
+use crate::paginate::{paginate, HasCursor, Pager};
+#[cfg(feature = "scrape")]
 use crate::scrape::Scraper;
-use crate::tweet::{Cursor, FilterCursorTweetRequest, TimelineTerminateTimeline, UserResults};
-use crate::user::{Error, User};
-use crate::usertweets::TimelineAddEntry;
-use crate::TwitterIdType;
+use crate::tweet::{FilterCursorTweetRequest, TimelineTerminateTimeline, UserResults};
+#[cfg(feature = "scrape")]
+use crate::user::collect_warnings;
+use crate::user::{Error, TwtUsrResult, User};
+use crate::{SetAsVec, TwitterIdType};
+use ahash::{HashSet, HashSetExt};
 use rkyv::Archive;
 use serde::{Deserialize, Serialize};
 use std::collections::VecDeque;
-use std::fmt::Display;
+use std::fmt::{Debug, Display};
 use tracing::warn;
 
 #[cfg(feature = "scrape")]
@@ -19,7 +26,7 @@ pub fn twitter_following_request(
     match following {
         FollowType::Following => match cursor {
             Some(cursor) => {
-                let crsr = urlencoding::encode(cursor);
+                let crsr = urlencoding::encode(cursor.as_ref());
                 format!("https://twitter.com/i/api/graphql/9rGM7YNDYuiqd0Cb0ZwLJw/Following?variables=%7B%22userId%22%3A%22{id}%22%2C%22count%22%3A20%2C%22cursor%22%3A%22{crsr}%22%2C%22includePromotedContent%22%3Afalse%2C%22withSuperFollowsUserFields%22%3Atrue%2C%22withDownvotePerspective%22%3Afalse%2C%22withReactionsMetadata%22%3Afalse%2C%22withReactionsPerspective%22%3Afalse%2C%22withSuperFollowsTweetFields%22%3Atrue%7D&features=%7B%22responsive_web_twitter_blue_verified_badge_is_enabled%22%3Atrue%2C%22verified_phone_label_enabled%22%3Afalse%2C%22responsive_web_graphql_timeline_navigation_enabled%22%3Atrue%2C%22unified_cards_ad_metadata_container_dynamic_card_content_query_enabled%22%3Atrue%2C%22tweetypie_unmention_optimization_enabled%22%3Atrue%2C%22responsive_web_uc_gql_enabled%22%3Atrue%2C%22vibe_api_enabled%22%3Atrue%2C%22responsive_web_edit_tweet_api_enabled%22%3Atrue%2C%22graphql_is_translatable_rweb_tweet_is_translatable_enabled%22%3Atrue%2C%22standardized_nudges_misinfo%22%3Atrue%2C%22tweet_with_visibility_results_prefer_gql_limited_actions_policy_enabled%22%3Afalse%2C%22interactive_text_enabled%22%3Atrue%2C%22responsive_web_text_conversations_enabled%22%3Afalse%2C%22responsive_web_enhance_cards_enabled%22%3Atrue%7D")
             }
             None => {
@@ -28,7 +35,7 @@ pub fn twitter_following_request(
         },
         FollowType::Followers => match cursor {
             Some(cursor) => {
-                let crsr = urlencoding::encode(cursor);
+                let crsr = urlencoding::encode(cursor.as_ref());
                 format!("https://twitter.com/i/api/graphql/_gXC5CopoM8fIgawvyGpIg/Followers?variables=%7B%22userId%22%3A%22{id}%22%2C%22count%22%3A20%2C%22cursor%22%3A%22{crsr}%22%2C%22includePromotedContent%22%3Afalse%2C%22withSuperFollowsUserFields%22%3Atrue%2C%22withDownvotePerspective%22%3Afalse%2C%22withReactionsMetadata%22%3Afalse%2C%22withReactionsPerspective%22%3Afalse%2C%22withSuperFollowsTweetFields%22%3Atrue%7D&features=%7B%22responsive_web_twitter_blue_verified_badge_is_enabled%22%3Atrue%2C%22verified_phone_label_enabled%22%3Afalse%2C%22responsive_web_graphql_timeline_navigation_enabled%22%3Atrue%2C%22unified_cards_ad_metadata_container_dynamic_card_content_query_enabled%22%3Atrue%2C%22tweetypie_unmention_optimization_enabled%22%3Atrue%2C%22responsive_web_uc_gql_enabled%22%3Atrue%2C%22vibe_api_enabled%22%3Atrue%2C%22responsive_web_edit_tweet_api_enabled%22%3Atrue%2C%22graphql_is_translatable_rweb_tweet_is_translatable_enabled%22%3Atrue%2C%22standardized_nudges_misinfo%22%3Atrue%2C%22tweet_with_visibility_results_prefer_gql_limited_actions_policy_enabled%22%3Afalse%2C%22interactive_text_enabled%22%3Atrue%2C%22responsive_web_text_conversations_enabled%22%3Afalse%2C%22responsive_web_enhance_cards_enabled%22%3Atrue%7D")
             }
             None => {
@@ -57,12 +64,21 @@ pub struct Follows {
 
 #[cfg(feature = "scrape")]
 impl Follows {
+    // on cancellation the users of the pages fetched so far are returned
     #[tracing::instrument]
-    pub async fn get_user_follow(scraper: &Scraper, id: u64, ftype: FollowType) -> SResult<Self> {
+    pub async fn get_user_follow(
+        scraper: &Scraper,
+        id: UserId,
+        ftype: FollowType,
+    ) -> SResult<Self> {
         let mut follow_page_requests = Vec::with_capacity(50);
 
         let first_request = scraper
-            .api_req::<FollowReq>(scraper.make_get_req(twitter_following_request(id, ftype, None)))
+            .api_req::<FollowReq>(scraper.make_get_req(twitter_following_request(
+                id,
+                ftype,
+                None::<&str>,
+            )))
             .await?;
         // find the cursor
         let first_cursor = first_request.filter_cursor(FilterCursorTweetRequest::Bottom);
@@ -75,30 +91,13 @@ impl Follows {
         let mut users = Vec::with_capacity(1000);
 
         for req in follow_page_requests {
-            if let Rslt::User(tl) = req.data.result {
-                for inst in tl.timeline.instructions {
-                    if let Instruction::TimelineAddEntries(tl_add) = inst {
-                        for entry in tl_add.entries {
-                            if let Entry::User(usr) = entry {
-                                match User::from_result(
-                                    scraper,
-                                    usr.content.item_content.result.result,
-                                )
-                                .await
-                                {
-                                    Ok(us) => {
-                                        users.push(us);
-                                    }
-                                    Err(why) => {
-                                        warn!(
-                                            error = why,
-                                            user_id = id,
-                                            "Failed to get data. Skipping..."
-                                        )
-                                    }
-                                }
-                            }
-                        }
+            for result in req.into_user_results() {
+                match User::from_result(scraper, result).await {
+                    Ok(us) => {
+                        users.push(us);
+                    }
+                    Err(why) => {
+                        warn!(error = %why, user_id = %id, "Failed to get data. Skipping...")
                     }
                 }
             }
@@ -110,11 +109,181 @@ impl Follows {
     }
 }
 
+// twitter stops handing out pages somewhere around here anyway
+pub const FOLLOW_SCROLL_CAP: usize = 3200;
+
+#[derive(
+    Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Archive, rkyv::Serialize, rkyv::Deserialize,
+)]
+pub struct Followers {
+    #[with(SetAsVec)]
+    pub users: HashSet<User>,
+    // where scrolling stopped when it hit `FOLLOW_SCROLL_CAP` or was cancelled, `None` once the
+    // whole list was read
+    pub cursor: Option<String>,
+}
+
+#[cfg(feature = "scrape")]
+impl Followers {
+    // at most `FOLLOW_SCROLL_CAP` users. on cancellation the users read so far are returned
+    #[tracing::instrument]
+    pub async fn scroll(scraper: &Scraper, user_handle: impl AsRef<str> + Debug) -> SResult<Self> {
+        let user = User::new(scraper, user_handle.as_ref()).await?;
+        let capacity = user.profile_stats.followers as usize;
+        let (users, cursor) = scroll_follows(
+            scraper,
+            user.id,
+            FollowType::Followers,
+            capacity,
+            FOLLOW_SCROLL_CAP,
+        )
+        .await?;
+        Ok(Followers { users, cursor })
+    }
+}
+
+#[derive(
+    Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Archive, rkyv::Serialize, rkyv::Deserialize,
+)]
+pub struct Following {
+    #[with(SetAsVec)]
+    pub users: HashSet<User>,
+    // same as `Followers::cursor`
+    pub cursor: Option<String>,
+}
+
+#[cfg(feature = "scrape")]
+impl Following {
+    // at most `FOLLOW_SCROLL_CAP` users. a protected account we aren't allowed to see comes back
+    // empty instead of erroring
+    #[tracing::instrument]
+    pub async fn scroll(scraper: &Scraper, user_handle: impl AsRef<str> + Debug) -> SResult<Self> {
+        let user = User::new(scraper, user_handle.as_ref()).await?;
+        let capacity = user.profile_stats.following as usize;
+        match scroll_follows(
+            scraper,
+            user.id,
+            FollowType::Following,
+            capacity,
+            FOLLOW_SCROLL_CAP,
+        )
+        .await
+        {
+            Ok((users, cursor)) => Ok(Following { users, cursor }),
+            Err(TwtScrapeError::TwitterJSONError(TwitterErrorCode::NotAuthorized, _))
+                if user.is_protected =>
+            {
+                Ok(Following {
+                    users: HashSet::new(),
+                    cursor: None,
+                })
+            }
+            Err(why) => Err(why),
+        }
+    }
+}
+
+// parses every page as it comes in instead of holding on to them like `FollowReq::scroll`.
+// stops after the page that brought it to `max_users`.
+#[cfg(feature = "scrape")]
+async fn scroll_follows(
+    scraper: &Scraper,
+    id: UserId,
+    ftype: FollowType,
+    capacity: usize,
+    max_users: usize,
+) -> SResult<(HashSet<User>, Option<String>)> {
+    let mut users = HashSet::with_capacity(capacity.min(max_users));
+    let mut pager = Pager::new(scraper, FilterCursorTweetRequest::Bottom, None, |cursor| {
+        twitter_following_request(id, ftype, cursor)
+    });
+
+    // a page of only broken or already seen users isn't the end
+    while let Some(request) = pager.next_page::<FollowReq>().await? {
+        for result in request.into_user_results() {
+            match User::from_result(scraper, result).await {
+                Ok(user) => {
+                    users.insert(user);
+                }
+                Err(why) => warn!(error = %why, user_id = %id, "Failed to get data. Skipping..."),
+            }
+        }
+
+        if users.len() >= max_users {
+            break;
+        }
+    }
+
+    Ok((users, pager.cursor().map(ToString::to_string)))
+}
+
+/// Accounts followed by both `handle_a` and `handle_b`.
+/// Both following lists are fetched concurrently, each up to `max_following` users (at most
+/// `FOLLOW_SCROLL_CAP`, the default). Mutuals past that point are missed.
+#[cfg(feature = "scrape")]
+#[tracing::instrument]
+pub async fn mutuals(
+    scraper: &Scraper,
+    handle_a: impl AsRef<str> + Debug,
+    handle_b: impl AsRef<str> + Debug,
+    max_following: Option<usize>,
+) -> SResult<HashSet<User>> {
+    let max_following = max_following
+        .unwrap_or(FOLLOW_SCROLL_CAP)
+        .min(FOLLOW_SCROLL_CAP);
+
+    let (user_a, user_b) = tokio::try_join!(
+        User::new(scraper, handle_a.as_ref()),
+        User::new(scraper, handle_b.as_ref())
+    )?;
+
+    // nothing to intersect, save the requests
+    if user_a.profile_stats.following == 0 || user_b.profile_stats.following == 0 {
+        return Ok(HashSet::new());
+    }
+
+    let following = |user: &User| {
+        scroll_follows(
+            scraper,
+            user.id,
+            FollowType::Following,
+            user.profile_stats.following as usize,
+            max_following,
+        )
+    };
+
+    if user_a.id == user_b.id {
+        let (follows, _) = following(&user_a).await?;
+        return Ok(follows);
+    }
+
+    let ((follows_a, _), (follows_b, _)) =
+        tokio::try_join!(following(&user_a), following(&user_b))?;
+
+    // intersect on id, the same account can differ slightly between pages
+    let (smaller, larger) = if follows_a.len() <= follows_b.len() {
+        (follows_a, follows_b)
+    } else {
+        (follows_b, follows_a)
+    };
+    let larger_ids = larger
+        .iter()
+        .map(|user| user.id)
+        .collect::<HashSet<UserId>>();
+
+    Ok(smaller
+        .into_iter()
+        .filter(|user| larger_ids.contains(&user.id))
+        .collect())
+}
+
 #[derive(
     Copy,
     Clone,
     Debug,
+    Hash,
     PartialEq,
+    Eq,
     PartialOrd,
     Serialize,
     Deserialize,
@@ -140,83 +309,94 @@ pub enum FollowType {
     rkyv::Deserialize,
 )]
 pub struct FollowReq {
-    pub errors: Vec<Error>,
-    pub data: Data,
+    #[serde(default)]
+    pub(crate) errors: Vec<Error>,
+    pub(crate) data: Data,
 }
 
 #[cfg(feature = "scrape")]
 impl FollowReq {
+    pub(crate) fn json_request_filter_errors(&self) -> SResult<Vec<TwitterWarning>> {
+        collect_warnings(&self.errors)
+    }
+
+    fn entries(&self) -> impl Iterator<Item = &Entry> {
+        let instructions = match &self.data.user.result {
+            Rslt::User(tl) => tl.timeline.timeline.instructions.as_slice(),
+            Rslt::UserUnavailable => &[],
+        };
+        instructions.iter().flat_map(|inst| match inst {
+            Instruction::TimelineAddEntries(add) => add.entries.as_slice(),
+            _ => &[],
+        })
+    }
+
     pub(crate) fn filter_cursor(&self, filter: FilterCursorTweetRequest) -> Option<&str> {
-        if let Rslt::User(tl) = &self.data.result {
-            for inst in &tl.timeline.instructions {
-                if let Instruction::TimelineAddEntries(tl_add) = inst {
-                    for entry in &tl_add.entries {
-                        if let Entry::Cursor(crsr) = entry {
-                            match filter {
-                                FilterCursorTweetRequest::Top => {
-                                    if crsr.entry_id.starts_with("cursor-top") {
-                                        return Some(crsr.content.item_content.value.as_str());
-                                    }
-                                }
-                                FilterCursorTweetRequest::Bottom => {
-                                    if crsr.entry_id.starts_with("cursor-bottom")
-                                        || crsr.entry_id.starts_with("cursor-showmorethreads")
-                                    {
-                                        return Some(crsr.content.item_content.value.as_str());
-                                    }
-                                }
-                            }
-                        }
-                    }
-                }
+        let mut cursors = self.entries().filter_map(|entry| match entry {
+            Entry::Cursor(crsr) => Some(crsr),
+            Entry::User(_) => None,
+        });
+        let cursor = match filter {
+            FilterCursorTweetRequest::Top => cursors.find(|c| c.content.cursor_type == "Top"),
+            FilterCursorTweetRequest::Bottom => {
+                cursors.filter(|c| c.content.cursor_type == "Bottom").last()
             }
-        }
+        };
+        cursor.map(|c| c.content.value.as_str())
+    }
 
-        None
+    pub(crate) fn into_user_results(self) -> Vec<TwtUsrResult> {
+        let instructions = match self.data.user.result {
+            Rslt::User(tl) => tl.timeline.timeline.instructions,
+            Rslt::UserUnavailable => vec![],
+        };
+        instructions
+            .into_iter()
+            .flat_map(|inst| match inst {
+                Instruction::TimelineAddEntries(add) => add.entries,
+                _ => vec![],
+            })
+            .filter_map(|entry| match entry {
+                Entry::User(usr) => Some(usr.content.item_content.user_results.result),
+                Entry::Cursor(_) => None,
+            })
+            .collect()
     }
 
     #[tracing::instrument]
     pub(crate) async fn scroll(
         scraper: &Scraper,
-        id: u64,
+        id: UserId,
         ftype: FollowType,
         first_cursor: &str,
     ) -> SResult<VecDeque<Self>> {
-        let mut requests = VecDeque::with_capacity(5);
-
-        let mut cursor_counter = first_cursor.to_string();
-        let mut break_on_next = false;
-        loop {
-            let scrolled_up_request = scraper
-                .api_req::<FollowReq>(scraper.make_get_req(twitter_following_request(
-                    id,
-                    ftype,
-                    Some(&cursor_counter),
-                )))
-                .await?;
-
-            scrolled_up_request.json_request_filter_errors()?;
-
-            requests.push_front(scrolled_up_request);
-            if break_on_next {
-                break;
-            }
-
-            match scrolled_up_request.filter_cursor(FilterCursorTweetRequest::Bottom) {
-                Some(bottom) => {
-                    cursor_counter = bottom.to_string();
-                }
-                None => break_on_next = true,
-            }
-        }
-
-        Ok(requests)
+        paginate(
+            scraper,
+            FilterCursorTweetRequest::Bottom,
+            first_cursor.to_string(),
+            None,
+            |cursor| twitter_following_request(id, ftype, Some(cursor)),
+        )
+        .await
     }
 }
 
 #[cfg(feature = "scrape")]
 crate::impl_filter_json!(FollowReq);
 
+#[cfg(feature = "scrape")]
+impl HasCursor for FollowReq {
+    fn filter_cursor(&self, filter: FilterCursorTweetRequest) -> Option<&str> {
+        FollowReq::filter_cursor(self, filter)
+    }
+
+    fn entry_count(&self) -> usize {
+        self.entries()
+            .filter(|entry| matches!(entry, Entry::User(_)))
+            .count()
+    }
+}
+
 #[derive(
     Clone,
     Debug,
@@ -230,6 +410,22 @@ crate::impl_filter_json!(FollowReq);
     rkyv::Deserialize,
 )]
 pub(crate) struct Data {
+    pub user: FollowUser,
+}
+
+#[derive(
+    Clone,
+    Debug,
+    Hash,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+    Archive,
+    rkyv::Serialize,
+    rkyv::Deserialize,
+)]
+pub(crate) struct FollowUser {
     pub result: Rslt,
 }
 
@@ -247,8 +443,9 @@ pub(crate) struct Data {
 )]
 #[serde(tag = "__typename")]
 pub(crate) enum Rslt {
-    #[serde(flatten)]
     User(Timeline),
+    // suspended or deactivated
+    UserUnavailable,
 }
 
 #[derive(
@@ -264,6 +461,22 @@ pub(crate) enum Rslt {
     rkyv::Deserialize,
 )]
 pub(crate) struct Timeline {
+    pub timeline: TimelineWrapper,
+}
+
+#[derive(
+    Clone,
+    Debug,
+    Hash,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+    Archive,
+    rkyv::Serialize,
+    rkyv::Deserialize,
+)]
+pub(crate) struct TimelineWrapper {
     pub timeline: InnerTimeline,
 }
 
@@ -298,10 +511,8 @@ pub(crate) struct InnerTimeline {
 #[serde(tag = "type")]
 pub(crate) enum Instruction {
     TimelineClearCache,
-    #[serde(flatten)]
     TimelineTerminateTimeline(TimelineTerminateTimeline),
-    #[serde(flatten)]
-    TimelineAddEntries(TimelineAddEntry),
+    TimelineAddEntries(TimelineAddEntries),
 }
 
 #[derive(
@@ -320,6 +531,7 @@ pub(crate) struct TimelineAddEntries {
     pub entries: Vec<Entry>,
 }
 
+// user entries are the ones with a user result in them, the rest are cursors
 #[derive(
     Clone,
     Debug,
@@ -332,9 +544,10 @@ pub(crate) struct TimelineAddEntries {
     rkyv::Serialize,
     rkyv::Deserialize,
 )]
+#[serde(untagged)]
 pub(crate) enum Entry {
     User(Usr),
-    Cursor(Cursor),
+    Cursor(FollowCursor),
 }
 
 #[derive(
@@ -365,8 +578,8 @@ pub(crate) struct Usr {
     rkyv::Serialize,
     rkyv::Deserialize,
 )]
+#[serde(rename_all = "camelCase")]
 pub(crate) struct Content {
-    #[serde(rename = "itemContent")]
     pub item_content: ItemContent,
 }
 
@@ -383,5 +596,140 @@ pub(crate) struct Content {
     rkyv::Deserialize,
 )]
 pub(crate) struct ItemContent {
-    pub result: UserResults,
+    pub user_results: UserResults,
+}
+
+// unlike tweet timelines the cursor sits right in the entry content
+#[derive(
+    Clone,
+    Debug,
+    Hash,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+    Archive,
+    rkyv::Serialize,
+    rkyv::Deserialize,
+)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct FollowCursor {
+    pub entry_id: String,
+    pub content: FollowCursorContent,
+}
+
+#[derive(
+    Clone,
+    Debug,
+    Hash,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+    Archive,
+    rkyv::Serialize,
+    rkyv::Deserialize,
+)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct FollowCursorContent {
+    pub value: String,
+    pub cursor_type: String,
+}
+
+#[cfg(feature = "scrape")]
+#[test]
+fn following_fixture() {
+    let req = serde_json::from_str::<FollowReq>(
+        r#"{"data":{"user":{"result":{"__typename":"User","timeline":{"timeline":{"instructions":[{"type":"TimelineClearCache"},{"type":"TimelineTerminateTimeline","direction":"Top"},{"type":"TimelineAddEntries","entries":[{"entryId":"user-783214","sortIndex":"1684972338541182976","content":{"entryType":"TimelineTimelineItem","__typename":"TimelineTimelineItem","itemContent":{"itemType":"TimelineUser","__typename":"TimelineUser","user_results":{"result":{"__typename":"User","id":"VXNlcjo3ODMyMTQ=","rest_id":"783214","has_nft_avatar":false,"is_blue_verified":true,"legacy":{"created_at":"Tue Feb 20 14:35:54 +0000 2007","default_profile":false,"default_profile_image":false,"description":"what's happening?!","entities":{"description":{"urls":[]}},"fast_followers_count":0,"favourites_count":6393,"followers_count":66047734,"friends_count":0,"has_custom_timelines":true,"is_translator":false,"listed_count":87426,"location":"everywhere","media_count":2980,"name":"X","normal_followers_count":66047734,"pinned_tweet_ids_str":[],"possibly_sensitive":false,"profile_banner_url":"https://pbs.twimg.com/profile_banners/783214/1690175171","profile_image_url_https":"https://pbs.twimg.com/profile_images/1683899100922511378/5lY42eHs_normal.jpg","profile_interstitial_type":"","protected":false,"screen_name":"X","statuses_count":15043,"translator_type":"regular","url":"https://t.co/bGXvqFEGJJ","verified":false,"withheld_in_countries":[]},"professional":{"rest_id":"1503055759638159366","professional_type":"Creator","category":[{"id":958,"name":"Entrepreneur","icon_name":"IconBriefcaseStroke"}]},"super_follow_eligible":false,"is_profile_translatable":false}},"userDisplayType":"User"},"clientEventInfo":{"component":"FollowingSgs","element":"user"}}},{"entryId":"cursor-bottom-1773391394532089876","sortIndex":"1773391394532089857","content":{"entryType":"TimelineTimelineCursor","__typename":"TimelineTimelineCursor","value":"1773391394532089876|1684972338541182974","cursorType":"Bottom"}},{"entryId":"cursor-top-1684972338541182977","sortIndex":"1684972338541182977","content":{"entryType":"TimelineTimelineCursor","__typename":"TimelineTimelineCursor","value":"-1|1684972338541182977","cursorType":"Top"}}]}]}}}}}}"#,
+    )
+    .unwrap();
+    assert!(req.json_request_filter_errors().unwrap().is_empty());
+    assert_eq!(
+        req.filter_cursor(FilterCursorTweetRequest::Bottom),
+        Some("1773391394532089876|1684972338541182974")
+    );
+    let users = req.into_user_results();
+    assert_eq!(users.len(), 1);
+    match &users[0] {
+        TwtUsrResult::User(user) => assert_eq!(user.rest_id, "783214"),
+        TwtUsrResult::UserUnavailable(_) => panic!("expected an available user"),
+    }
+}
+
+#[cfg(feature = "scrape")]
+#[test]
+fn unparsable_page_keeps_scrolling() {
+    use crate::paginate::follow_page;
+    use crate::scrape::dry_run;
+
+    // every user on the first two pages is unavailable, only the third page ends the list
+    let canned = vec![
+        follow_page(
+            r#"{"entryId":"cursor-bottom-1","content":{"value":"second","cursorType":"Bottom"}}"#,
+        ),
+        follow_page(
+            r#"{"entryId":"cursor-bottom-2","content":{"value":"third","cursorType":"Bottom"}}"#,
+        ),
+        r#"{"data":{"user":{"result":{"__typename":"User","timeline":{"timeline":{"instructions":[{"type":"TimelineAddEntries","entries":[{"entryId":"cursor-bottom-3","content":{"value":"fourth","cursorType":"Bottom"}}]}]}}}}}}"#.to_string(),
+    ];
+
+    dry_run(canned, |scraper| async move {
+        let (users, cursor) = scroll_follows(
+            &scraper,
+            UserId(1),
+            FollowType::Followers,
+            20,
+            FOLLOW_SCROLL_CAP,
+        )
+        .await
+        .unwrap();
+        assert!(users.is_empty());
+        assert_eq!(cursor, None);
+        assert_eq!(scraper.stop_dry_run().len(), 3);
+    });
+}
+
+#[cfg(feature = "scrape")]
+#[test]
+fn mutuals_stop_at_max_following() {
+    use crate::scrape::dry_run;
+    use crate::user::user_results_fixture;
+
+    let user = |id: u64| {
+        let mut result = user_results_fixture(id);
+        result["legacy"]["friends_count"] = 4.into();
+        serde_json::json!({"data": {"user": {"result": result}}}).to_string()
+    };
+    // two accounts and a cursor to more of them, which `max_following` never gets to
+    let page = |ids: [u64; 2]| {
+        let mut entries = ids
+            .iter()
+            .map(|id| {
+                serde_json::json!({
+                    "entryId": format!("user-{id}"),
+                    "content": {"itemContent": {"user_results": {"result": user_results_fixture(*id)}}},
+                })
+            })
+            .collect::<Vec<_>>();
+        entries.push(serde_json::json!({
+            "entryId": "cursor-bottom-1",
+            "content": {"value": "second", "cursorType": "Bottom"},
+        }));
+        serde_json::json!({
+            "data": {"user": {"result": {"__typename": "User", "timeline": {"timeline": {
+                "instructions": [{"type": "TimelineAddEntries", "entries": entries}],
+            }}}}},
+        })
+        .to_string()
+    };
+    let canned = vec![user(1), user(2), page([10, 11]), page([11, 12])];
+
+    dry_run(canned, |scraper| async move {
+        let mutuals = mutuals(&scraper, "user1", "user2", Some(2)).await.unwrap();
+        assert_eq!(
+            mutuals.iter().map(|user| user.id).collect::<Vec<_>>(),
+            vec![UserId(11)]
+        );
+        assert_eq!(scraper.stop_dry_run().len(), 4);
+    });
 }
@@ -0,0 +1,70 @@
+use crate::scrape::TWITTER_URL;
+use serde_json::{Map, Value};
+
+// builds `/i/api/graphql/{query id}/{operation}` urls so new endpoints don't need a hand encoded
+// json blob. keys come out sorted, twitter doesn't care about their order.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct GraphQlRequest {
+    query_id: String,
+    operation_name: String,
+    variables: Map<String, Value>,
+    features: Map<String, Value>,
+}
+
+impl GraphQlRequest {
+    pub fn new(query_id: impl Into<String>, operation_name: impl Into<String>) -> Self {
+        GraphQlRequest {
+            query_id: query_id.into(),
+            operation_name: operation_name.into(),
+            ..Default::default()
+        }
+    }
+
+    // twitter wants ids as strings, pass `id.to_string()` rather than the number
+    pub fn variable(mut self, key: impl Into<String>, value: impl Into<Value>) -> Self {
+        self.variables.insert(key.into(), value.into());
+        self
+    }
+
+    pub fn feature(mut self, key: impl Into<String>, enabled: bool) -> Self {
+        self.features.insert(key.into(), Value::Bool(enabled));
+        self
+    }
+
+    pub fn features<'a>(mut self, features: impl IntoIterator<Item = (&'a str, bool)>) -> Self {
+        for (key, enabled) in features {
+            self.features.insert(key.to_string(), Value::Bool(enabled));
+        }
+        self
+    }
+
+    pub fn build(&self) -> String {
+        let variables = Value::Object(self.variables.clone()).to_string();
+        let mut url = format!(
+            "{TWITTER_URL}/i/api/graphql/{}/{}?variables={}",
+            self.query_id,
+            self.operation_name,
+            urlencoding::encode(&variables)
+        );
+        if !self.features.is_empty() {
+            let features = Value::Object(self.features.clone()).to_string();
+            url.push_str("&features=");
+            url.push_str(&urlencoding::encode(&features));
+        }
+        url
+    }
+}
+
+#[test]
+fn build_graphql_url() {
+    let url = GraphQlRequest::new("abc", "UserTweets")
+        .variable("userId", "12")
+        .variable("count", 20)
+        .feature("vibe_api_enabled", true)
+        .build();
+    assert_eq!(url, "https://twitter.com/i/api/graphql/abc/UserTweets?variables=%7B%22count%22%3A20%2C%22userId%22%3A%2212%22%7D&features=%7B%22vibe_api_enabled%22%3Atrue%7D");
+    assert_eq!(
+        GraphQlRequest::new("abc", "Viewer").build(),
+        "https://twitter.com/i/api/graphql/abc/Viewer?variables=%7B%7D"
+    );
+}
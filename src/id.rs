@@ -0,0 +1,117 @@
+use crate::error::{SResult, TwtScrapeError};
+use crate::TwitterIdType;
+use chrono::{DateTime, TimeZone, Utc};
+use rkyv::Archive;
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+// twitter ids are snowflakes sent as strings. these keep a tweet id from being passed where a
+// user id goes. serialized as the bare number, same as before they were wrapped.
+macro_rules! snowflake_id {
+    ($name:ident) => {
+        #[derive(
+            Copy,
+            Clone,
+            Debug,
+            Default,
+            Hash,
+            PartialEq,
+            Eq,
+            PartialOrd,
+            Ord,
+            Serialize,
+            Deserialize,
+            Archive,
+            rkyv::Serialize,
+            rkyv::Deserialize,
+        )]
+        #[cfg_attr(feature = "validation", archive(check_bytes))]
+        #[archive_attr(derive(Debug, PartialEq, Eq))]
+        #[serde(transparent)]
+        pub struct $name(pub u64);
+
+        impl $name {
+            pub fn get(self) -> u64 {
+                self.0
+            }
+        }
+
+        impl Display for $name {
+            fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+                self.0.fmt(f)
+            }
+        }
+
+        impl FromStr for $name {
+            type Err = TwtScrapeError;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                s.trim()
+                    .parse::<u64>()
+                    .map($name)
+                    .map_err(|why| TwtScrapeError::IdParseError(format!("{s:?}: {why}")))
+            }
+        }
+
+        impl From<u64> for $name {
+            fn from(id: u64) -> Self {
+                $name(id)
+            }
+        }
+
+        impl From<$name> for u64 {
+            fn from(id: $name) -> Self {
+                id.0
+            }
+        }
+
+        impl PartialEq<u64> for $name {
+            fn eq(&self, other: &u64) -> bool {
+                self.0 == *other
+            }
+        }
+
+        impl TwitterIdType for $name {
+            fn to_u64(&self) -> SResult<u64> {
+                Ok(self.0)
+            }
+        }
+    };
+}
+
+snowflake_id!(TweetId);
+snowflake_id!(UserId);
+
+// 2010-11-04T01:42:54.657Z, snowflake timestamps count from here
+pub const TWITTER_EPOCH_MS: i64 = 1288834974657;
+
+impl TweetId {
+    // when the tweet was posted, to the millisecond, straight from the top 42 bits of the id.
+    // tombstones keep their id so this works for them too. ids from before snowflakes (late
+    // 2010) are plain counters and all land on the epoch.
+    pub fn timestamp(self) -> DateTime<Utc> {
+        let millis = (self.0 >> 22) as i64 + TWITTER_EPOCH_MS;
+        Utc.timestamp_millis_opt(millis)
+            .single()
+            .unwrap_or(DateTime::<Utc>::MIN_UTC)
+    }
+}
+
+#[test]
+fn ids_round_trip() {
+    let id = "1700000000000000001".parse::<TweetId>().unwrap();
+    assert_eq!(id, TweetId(1700000000000000001));
+    assert_eq!(id.to_string(), "1700000000000000001");
+    assert!("@jack".parse::<UserId>().is_err());
+    assert!("".parse::<UserId>().is_err());
+}
+
+#[test]
+fn tweet_id_timestamp() {
+    // created_at "2019-12-31T19:26:16.000Z", the api only hands out whole seconds
+    let posted = TweetId(1212092628029698048).timestamp();
+    assert_eq!(posted.timestamp_millis(), 1577820376771);
+    assert_eq!(posted.to_rfc3339(), "2019-12-31T19:26:16.771+00:00");
+    assert_eq!(TweetId(20).timestamp().timestamp_millis(), TWITTER_EPOCH_MS);
+}
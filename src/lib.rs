@@ -1,15 +1,34 @@
 use crate::error::{SResult, TwtScrapeError};
+use rkyv::ser::{ScratchSpace, Serializer};
+use rkyv::vec::{ArchivedVec, VecResolver};
+use rkyv::with::{ArchiveWith, DeserializeWith, SerializeWith};
+use rkyv::{Archive, Fallible};
+use std::collections::HashSet;
 use std::fmt::Display;
+use std::hash::{BuildHasher, Hash};
 
+#[cfg(feature = "blocking")]
+pub mod blocking;
+pub mod community;
 pub mod error;
+pub mod export;
 pub mod follow;
+#[cfg(feature = "scrape")]
+pub mod graphql;
+pub mod id;
+pub mod likes;
+pub mod list;
+pub mod mentions;
 pub mod moderated_tweets;
 #[cfg(feature = "scrape")]
+mod paginate;
+#[cfg(feature = "scrape")]
 pub mod scrape;
 pub mod search;
 pub mod timeline;
 pub mod tweet;
 pub mod user;
+pub mod usermedia;
 pub mod usertweets;
 
 pub trait TwitterIdType: Display {
@@ -47,9 +66,35 @@ macro_rules! as_option {
     };
 }
 
+// path segments of a twitter.com or x.com url, query string and fragment dropped
+pub(crate) fn twitter_url_segments(url: &str) -> Option<Vec<&str>> {
+    let url = url.trim();
+    let rest = url
+        .strip_prefix("https://")
+        .or_else(|| url.strip_prefix("http://"))
+        .unwrap_or(url);
+    let rest = rest.split(['?', '#']).next()?;
+    let mut parts = rest.split('/');
+    let host = parts.next()?;
+    let known_host = [
+        "twitter.com",
+        "www.twitter.com",
+        "mobile.twitter.com",
+        "x.com",
+        "www.x.com",
+        "mobile.x.com",
+    ]
+    .iter()
+    .any(|known| host.eq_ignore_ascii_case(known));
+    if !known_host {
+        return None;
+    }
+    Some(parts.filter(|part| !part.is_empty()).collect())
+}
+
 #[cfg(feature = "scrape")]
 pub trait FilterJSON {
-    fn filter_json_err(&self) -> SResult<()>;
+    fn filter_json_err(&self) -> SResult<Vec<crate::error::TwitterWarning>>;
 }
 
 #[cfg(feature = "scrape")]
@@ -57,17 +102,65 @@ pub trait FilterJSON {
 macro_rules! impl_filter_json {
     ($to:ty) => {
         impl $crate::FilterJSON for $to {
-            fn filter_json_err(&self) -> SResult<()> {
-                if let Some(why) = self.errors.first() {
-                    if why.code != 37 {
-                        return Err($crate::error::TwtScrapeError::TwitterJSONError(
-                            why.code,
-                            why.message.clone(),
-                        ));
-                    }
-                }
-                Ok(())
+            fn filter_json_err(
+                &self,
+            ) -> $crate::error::SResult<Vec<$crate::error::TwitterWarning>> {
+                $crate::user::collect_warnings(&self.errors)
             }
         }
     };
 }
+
+// archives a hash set as a plain vector. our archived types hold chrono's archived `DateTime`,
+// which isn't `Hash`, so they can't live in an archived hash set.
+pub(crate) struct SetAsVec;
+
+impl<T: Archive, S> ArchiveWith<HashSet<T, S>> for SetAsVec {
+    type Archived = ArchivedVec<T::Archived>;
+    type Resolver = VecResolver;
+
+    // sound for the same reason `Vec`'s own `resolve` is: `serialize_with` wrote exactly
+    // `field.len()` elements and handed back their resolver, and rkyv calls this with `out`
+    // pointing at the field's slot in the archive, `pos` being that slot's position.
+    // nothing here touches memory itself, `resolve_from_len` only fills in the relative pointer.
+    unsafe fn resolve_with(
+        field: &HashSet<T, S>,
+        pos: usize,
+        resolver: Self::Resolver,
+        out: *mut Self::Archived,
+    ) {
+        ArchivedVec::resolve_from_len(field.len(), pos, resolver, out);
+    }
+}
+
+impl<T, S, R> SerializeWith<HashSet<T, S>, R> for SetAsVec
+where
+    T: rkyv::Serialize<R>,
+    R: ScratchSpace + Serializer + ?Sized,
+{
+    fn serialize_with(
+        field: &HashSet<T, S>,
+        serializer: &mut R,
+    ) -> Result<Self::Resolver, R::Error> {
+        ArchivedVec::<T::Archived>::serialize_from_iter::<T, _, _, _>(field.iter(), serializer)
+    }
+}
+
+impl<T, S, D> DeserializeWith<ArchivedVec<T::Archived>, HashSet<T, S>, D> for SetAsVec
+where
+    T: Archive + Hash + Eq,
+    T::Archived: rkyv::Deserialize<T, D>,
+    S: BuildHasher + Default,
+    D: Fallible + ?Sized,
+{
+    fn deserialize_with(
+        field: &ArchivedVec<T::Archived>,
+        deserializer: &mut D,
+    ) -> Result<HashSet<T, S>, D::Error> {
+        let mut set = HashSet::with_capacity_and_hasher(field.len(), S::default());
+        for item in field.iter() {
+            set.insert(rkyv::Deserialize::deserialize(item, deserializer)?);
+        }
+        Ok(set)
+    }
+}
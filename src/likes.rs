@@ -0,0 +1,164 @@
+use crate::error::SResult;
+#[cfg(feature = "scrape")]
+use crate::graphql::GraphQlRequest;
+#[cfg(feature = "scrape")]
+use crate::id::UserId;
+#[cfg(feature = "scrape")]
+use crate::paginate::Pager;
+#[cfg(feature = "scrape")]
+use crate::scrape::{ScrapeOptions, Scraper};
+use crate::tweet::Tweet;
+#[cfg(feature = "scrape")]
+use crate::tweet::{FilterCursorTweetRequest, TweetResults};
+#[cfg(feature = "scrape")]
+use crate::user::TwtUsrResult;
+use crate::user::User;
+#[cfg(feature = "scrape")]
+use crate::usertweets::{Entry, Instruction, UserTweetAndRepliesRequest};
+use crate::SetAsVec;
+use ahash::{HashSet, HashSetExt};
+use rkyv::Archive;
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "scrape")]
+use tracing::warn;
+
+#[cfg(feature = "scrape")]
+pub fn twitter_request_url_likes(
+    id: UserId,
+    cursor: Option<impl AsRef<str>>,
+    options: &ScrapeOptions,
+) -> String {
+    let request = GraphQlRequest::new("eSSNbhECHHWWALkkQq-YTA", "Likes")
+        .variable("userId", id.to_string())
+        .variable("count", 20)
+        .variable("includePromotedContent", options.include_promoted_content)
+        .variable("withClientEventToken", false)
+        .variable("withBirdwatchNotes", false)
+        .variable("withVoice", true)
+        .variable("withV2Timeline", true)
+        .features([
+            (
+                "responsive_web_twitter_blue_verified_badge_is_enabled",
+                true,
+            ),
+            ("verified_phone_label_enabled", false),
+            ("responsive_web_graphql_timeline_navigation_enabled", true),
+            ("tweetypie_unmention_optimization_enabled", true),
+            ("vibe_api_enabled", true),
+            ("responsive_web_edit_tweet_api_enabled", true),
+            (
+                "graphql_is_translatable_rweb_tweet_is_translatable_enabled",
+                true,
+            ),
+            ("standardized_nudges_misinfo", true),
+            (
+                "tweet_with_visibility_results_prefer_gql_limited_actions_policy_enabled",
+                false,
+            ),
+            ("interactive_text_enabled", true),
+            ("responsive_web_text_conversations_enabled", false),
+            ("responsive_web_enhance_cards_enabled", true),
+        ]);
+    match cursor {
+        Some(cursor) => request.variable("cursor", cursor.as_ref()).build(),
+        None => request.build(),
+    }
+}
+
+#[derive(
+    Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Archive, rkyv::Serialize, rkyv::Deserialize,
+)]
+pub struct Likes {
+    #[with(SetAsVec)]
+    pub tweets: HashSet<Tweet>,
+    // authors of the liked tweets
+    #[with(SetAsVec)]
+    pub users: HashSet<User>,
+}
+
+#[cfg(feature = "scrape")]
+impl Likes {
+    // twitter only shows likes to the account itself, or for accounts with public likes. anything
+    // else comes back as an empty timeline, which ends up as empty sets rather than an error.
+    // deleted and withheld tweets are skipped. on cancellation the pages fetched so far are
+    // returned.
+    #[tracing::instrument]
+    pub async fn scroll(scraper: &Scraper, user_id: UserId) -> SResult<Self> {
+        let mut tweets = HashSet::with_capacity(20);
+        let mut users = HashSet::with_capacity(20);
+        let mut seen_users = HashSet::with_capacity(20);
+
+        let mut pager = Pager::new(scraper, FilterCursorTweetRequest::Bottom, None, |cursor| {
+            twitter_request_url_likes(user_id, cursor, scraper.options())
+        });
+        while let Some(request) = pager.next_page::<UserTweetAndRepliesRequest>().await? {
+            for inst in request.data.user.result.timeline_v2.timeline.instructions {
+                let add = match inst {
+                    Instruction::TimelineAddEntries(add) => add,
+                    _ => continue,
+                };
+                for entry in add.entries {
+                    let results = match entry {
+                        Entry::Tweet(tweet) => tweet.item_content.tweet_results,
+                        _ => continue,
+                    };
+                    if results.rest_id_or_skip().is_none() {
+                        continue;
+                    }
+                    match Tweet::new_from_entry(&results) {
+                        Ok(tweet) => {
+                            tweets.insert(tweet);
+                        }
+                        Err(why) => {
+                            warn!(error = %why, "Failed to parse liked tweet. Skipping...");
+                            continue;
+                        }
+                    }
+
+                    let author = match results {
+                        TweetResults::Ok(trr) => trr.core.user_results.result,
+                        _ => continue,
+                    };
+                    if let TwtUsrResult::User(usr) = &author {
+                        if !seen_users.insert(usr.rest_id.clone()) {
+                            continue;
+                        }
+                    }
+                    match User::from_result(scraper, author).await {
+                        Ok(user) => {
+                            users.insert(user);
+                        }
+                        Err(why) => warn!(error = %why, "Failed to get data. Skipping..."),
+                    }
+                }
+            }
+        }
+
+        Ok(Likes { tweets, users })
+    }
+}
+
+#[cfg(feature = "scrape")]
+#[test]
+fn broken_likes_are_skipped() {
+    use crate::scrape::dry_run;
+    use crate::tweet::{tweet_entry_fixture, tweet_results_fixture};
+    use crate::usertweets::user_timeline_page;
+
+    let good = 1700000000000000001;
+    let broken = 1700000000000000002;
+    let canned = vec![user_timeline_page(vec![
+        tweet_entry_fixture(good, tweet_results_fixture(good, 12, serde_json::json!({}))),
+        tweet_entry_fixture(
+            broken,
+            tweet_results_fixture(broken, 13, serde_json::json!({"created_at": "yesterday"})),
+        ),
+    ])];
+
+    dry_run(canned, |scraper| async move {
+        let likes = Likes::scroll(&scraper, UserId(12)).await.unwrap();
+        assert_eq!(likes.tweets.len(), 1);
+        assert!(likes.tweets.iter().all(|tweet| tweet.id == good));
+        assert_eq!(likes.users.len(), 1);
+    });
+}
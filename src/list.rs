@@ -0,0 +1,361 @@
+use crate::error::SResult;
+#[cfg(feature = "scrape")]
+use crate::follow::{self, TimelineWrapper};
+#[cfg(feature = "scrape")]
+use crate::graphql::GraphQlRequest;
+#[cfg(feature = "scrape")]
+use crate::paginate::{HasCursor, Pager};
+#[cfg(feature = "scrape")]
+use crate::scrape::{ScrapeOptions, Scraper};
+use crate::tweet::Tweet;
+#[cfg(feature = "scrape")]
+use crate::tweet::{FilterCursorTweetRequest, TweetResults};
+use crate::user::User;
+#[cfg(feature = "scrape")]
+use crate::user::{Error, TwtUsrResult};
+#[cfg(feature = "scrape")]
+use crate::usertweets::{bottom_cursor, instruction_tweet_results, TimelineV2};
+use crate::SetAsVec;
+use ahash::{HashSet, HashSetExt};
+use rkyv::Archive;
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "scrape")]
+use tracing::warn;
+
+#[cfg(feature = "scrape")]
+pub fn twitter_request_url_list_tweets(
+    list_id: u64,
+    cursor: Option<impl AsRef<str>>,
+    options: &ScrapeOptions,
+) -> String {
+    let request = GraphQlRequest::new("2Vjeyo_L0nizAUhHe3fKyA", "ListLatestTweetsTimeline")
+        .variable("listId", list_id.to_string())
+        .variable("count", 20)
+        .variable("includePromotedContent", options.include_promoted_content)
+        .variable("withVoice", true)
+        .features([
+            (
+                "responsive_web_twitter_blue_verified_badge_is_enabled",
+                true,
+            ),
+            ("verified_phone_label_enabled", false),
+            ("responsive_web_graphql_timeline_navigation_enabled", true),
+            ("tweetypie_unmention_optimization_enabled", true),
+            ("vibe_api_enabled", true),
+            ("responsive_web_edit_tweet_api_enabled", true),
+            (
+                "graphql_is_translatable_rweb_tweet_is_translatable_enabled",
+                true,
+            ),
+            ("standardized_nudges_misinfo", true),
+            (
+                "tweet_with_visibility_results_prefer_gql_limited_actions_policy_enabled",
+                false,
+            ),
+            ("interactive_text_enabled", true),
+            ("responsive_web_text_conversations_enabled", false),
+            ("responsive_web_enhance_cards_enabled", true),
+        ]);
+    match cursor {
+        Some(cursor) => request.variable("cursor", cursor.as_ref()).build(),
+        None => request.build(),
+    }
+}
+
+#[cfg(feature = "scrape")]
+pub fn twitter_request_url_list_members(list_id: u64, cursor: Option<impl AsRef<str>>) -> String {
+    let request = GraphQlRequest::new("BQp2IEYkgxuSxqbTAr1e1g", "ListMembers")
+        .variable("listId", list_id.to_string())
+        .variable("count", 20)
+        .variable("withSafetyModeUserFields", true)
+        .features([
+            (
+                "responsive_web_twitter_blue_verified_badge_is_enabled",
+                true,
+            ),
+            ("verified_phone_label_enabled", false),
+            ("responsive_web_graphql_timeline_navigation_enabled", true),
+        ]);
+    match cursor {
+        Some(cursor) => request.variable("cursor", cursor.as_ref()).build(),
+        None => request.build(),
+    }
+}
+
+#[derive(
+    Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Archive, rkyv::Serialize, rkyv::Deserialize,
+)]
+pub struct List {
+    #[with(SetAsVec)]
+    pub tweets: HashSet<Tweet>,
+    // authors of the tweets, not the list's members
+    #[with(SetAsVec)]
+    pub users: HashSet<User>,
+}
+
+#[cfg(feature = "scrape")]
+impl List {
+    // the list's latest tweets, deleted and withheld tweets are skipped. private lists come back
+    // as an error from twitter unless the scraper's account can see them. on cancellation the
+    // pages fetched so far are returned.
+    #[tracing::instrument]
+    pub async fn scroll(scraper: &Scraper, list_id: u64) -> SResult<Self> {
+        let mut tweets = HashSet::with_capacity(20);
+        let mut users = HashSet::with_capacity(20);
+        let mut seen_users = HashSet::with_capacity(20);
+
+        let mut pager = Pager::new(scraper, FilterCursorTweetRequest::Bottom, None, |cursor| {
+            twitter_request_url_list_tweets(list_id, cursor, scraper.options())
+        });
+        while let Some(request) = pager.next_page::<ListTweetsRequest>().await? {
+            let instructions = &request.data.list.tweets_timeline.timeline.instructions;
+            for (results, _) in instruction_tweet_results(instructions) {
+                if results.rest_id_or_skip().is_none() {
+                    continue;
+                }
+                match Tweet::new_from_entry(results) {
+                    Ok(tweet) => {
+                        tweets.insert(tweet);
+                    }
+                    Err(why) => {
+                        warn!(error = %why, "Failed to parse list tweet. Skipping...");
+                        continue;
+                    }
+                }
+
+                let author = match results {
+                    TweetResults::Ok(trr) => trr.core.user_results.result.clone(),
+                    _ => continue,
+                };
+                if let TwtUsrResult::User(usr) = &author {
+                    if !seen_users.insert(usr.rest_id.clone()) {
+                        continue;
+                    }
+                }
+                match User::from_result(scraper, author).await {
+                    Ok(user) => {
+                        users.insert(user);
+                    }
+                    Err(why) => warn!(error = %why, "Failed to get data. Skipping..."),
+                }
+            }
+        }
+
+        Ok(List { tweets, users })
+    }
+
+    // every account on the list. on cancellation the members fetched so far are returned.
+    #[tracing::instrument]
+    pub async fn members(scraper: &Scraper, list_id: u64) -> SResult<HashSet<User>> {
+        let mut members = HashSet::with_capacity(20);
+
+        let mut pager = Pager::new(scraper, FilterCursorTweetRequest::Bottom, None, |cursor| {
+            twitter_request_url_list_members(list_id, cursor)
+        });
+        while let Some(request) = pager.next_page::<ListMembersRequest>().await? {
+            for result in request.into_user_results() {
+                match User::from_result(scraper, result).await {
+                    Ok(user) => {
+                        members.insert(user);
+                    }
+                    Err(why) => warn!(error = %why, "Failed to get data. Skipping..."),
+                }
+            }
+        }
+
+        Ok(members)
+    }
+}
+
+#[cfg(feature = "scrape")]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub(crate) struct ListTweetsRequest {
+    #[serde(default)]
+    pub errors: Vec<Error>,
+    pub data: ListTweetsData,
+}
+
+#[cfg(feature = "scrape")]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub(crate) struct ListTweetsData {
+    pub list: ListTweetsTimeline,
+}
+
+#[cfg(feature = "scrape")]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub(crate) struct ListTweetsTimeline {
+    pub tweets_timeline: TimelineV2,
+}
+
+#[cfg(feature = "scrape")]
+crate::impl_filter_json!(ListTweetsRequest);
+
+#[cfg(feature = "scrape")]
+impl HasCursor for ListTweetsRequest {
+    // list timelines only scroll down
+    fn filter_cursor(&self, filter: FilterCursorTweetRequest) -> Option<&str> {
+        match filter {
+            FilterCursorTweetRequest::Top => None,
+            FilterCursorTweetRequest::Bottom => {
+                bottom_cursor(&self.data.list.tweets_timeline.timeline.instructions)
+            }
+        }
+    }
+
+    fn entry_count(&self) -> usize {
+        instruction_tweet_results(&self.data.list.tweets_timeline.timeline.instructions).count()
+    }
+}
+
+// members come in the same entries as followers and following
+#[cfg(feature = "scrape")]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub(crate) struct ListMembersRequest {
+    #[serde(default)]
+    pub errors: Vec<Error>,
+    pub data: ListMembersData,
+}
+
+#[cfg(feature = "scrape")]
+impl ListMembersRequest {
+    fn entries(&self) -> impl Iterator<Item = &follow::Entry> {
+        self.data
+            .list
+            .members_timeline
+            .timeline
+            .instructions
+            .iter()
+            .flat_map(|inst| match inst {
+                follow::Instruction::TimelineAddEntries(add) => add.entries.as_slice(),
+                _ => &[],
+            })
+    }
+
+    pub(crate) fn filter_cursor(&self, filter: FilterCursorTweetRequest) -> Option<&str> {
+        let mut cursors = self.entries().filter_map(|entry| match entry {
+            follow::Entry::Cursor(crsr) => Some(crsr),
+            follow::Entry::User(_) => None,
+        });
+        let cursor = match filter {
+            FilterCursorTweetRequest::Top => cursors.find(|c| c.content.cursor_type == "Top"),
+            FilterCursorTweetRequest::Bottom => {
+                cursors.filter(|c| c.content.cursor_type == "Bottom").last()
+            }
+        };
+        cursor.map(|c| c.content.value.as_str())
+    }
+
+    pub(crate) fn into_user_results(self) -> Vec<TwtUsrResult> {
+        self.data
+            .list
+            .members_timeline
+            .timeline
+            .instructions
+            .into_iter()
+            .flat_map(|inst| match inst {
+                follow::Instruction::TimelineAddEntries(add) => add.entries,
+                _ => vec![],
+            })
+            .filter_map(|entry| match entry {
+                follow::Entry::User(usr) => Some(usr.content.item_content.user_results.result),
+                follow::Entry::Cursor(_) => None,
+            })
+            .collect()
+    }
+}
+
+#[cfg(feature = "scrape")]
+crate::impl_filter_json!(ListMembersRequest);
+
+#[cfg(feature = "scrape")]
+impl HasCursor for ListMembersRequest {
+    fn filter_cursor(&self, filter: FilterCursorTweetRequest) -> Option<&str> {
+        ListMembersRequest::filter_cursor(self, filter)
+    }
+
+    fn entry_count(&self) -> usize {
+        self.entries()
+            .filter(|entry| matches!(entry, follow::Entry::User(_)))
+            .count()
+    }
+}
+
+#[cfg(feature = "scrape")]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub(crate) struct ListMembersData {
+    pub list: ListMembersTimeline,
+}
+
+#[cfg(feature = "scrape")]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub(crate) struct ListMembersTimeline {
+    pub members_timeline: TimelineWrapper,
+}
+
+#[cfg(feature = "scrape")]
+#[test]
+fn list_members_page() {
+    let page = serde_json::json!({
+        "data": {"list": {"members_timeline": {"timeline": {"instructions": [
+            {"type": "TimelineClearCache"},
+            {"type": "TimelineAddEntries", "entries": [
+                {"entryId": "cursor-bottom-1", "content": {"value": "next", "cursorType": "Bottom"}},
+                {"entryId": "cursor-top-1", "content": {"value": "prev", "cursorType": "Top"}}
+            ]}
+        ]}}}}
+    });
+    let request = serde_json::from_value::<ListMembersRequest>(page).unwrap();
+    assert_eq!(
+        request.filter_cursor(FilterCursorTweetRequest::Bottom),
+        Some("next")
+    );
+    assert!(request.into_user_results().is_empty());
+}
+
+#[cfg(feature = "scrape")]
+#[test]
+fn broken_list_tweets_are_skipped() {
+    use crate::scrape::dry_run;
+    use crate::tweet::tweet_results_fixture;
+
+    let good = 1700000000000000001;
+    let broken = 1700000000000000002;
+    let item = |results: serde_json::Value| {
+        serde_json::json!({"item": {
+            "itemType": "TimelineTweet",
+            "__typename": "TimelineTweet",
+            "tweet_results": results,
+        }})
+    };
+    let conversation = serde_json::json!({
+        "entryId": format!("list-conversation-{good}"),
+        "content": {
+            "items": [
+                item(tweet_results_fixture(good, 12, serde_json::json!({}))),
+                item(tweet_results_fixture(
+                    broken,
+                    13,
+                    serde_json::json!({"created_at": "yesterday"}),
+                )),
+            ],
+            "metadata": {
+                "allTweetIds": [good.to_string(), broken.to_string()],
+                "enable_deduplication": true,
+            },
+        },
+    });
+    let canned = serde_json::json!({
+        "data": {"list": {"tweets_timeline": {"timeline": {"instructions": [
+            {"type": "TimelineAddEntries", "entries": [conversation]},
+        ]}}}},
+    });
+
+    dry_run(vec![canned.to_string()], |scraper| async move {
+        let list = List::scroll(&scraper, 7).await.unwrap();
+        assert_eq!(
+            list.tweets.iter().map(|tweet| tweet.id).collect::<Vec<_>>(),
+            vec![good]
+        );
+        assert_eq!(list.users.len(), 1);
+    });
+}
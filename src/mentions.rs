@@ -0,0 +1,197 @@
+#[cfg(feature = "scrape")]
+use crate::error::SResult;
+#[cfg(feature = "scrape")]
+use crate::error::TwtScrapeError::NotAuthenticated;
+#[cfg(feature = "scrape")]
+use crate::id::TweetId;
+#[cfg(feature = "scrape")]
+use crate::paginate::{HasCursor, Pager};
+#[cfg(feature = "scrape")]
+use crate::scrape::Scraper;
+#[cfg(feature = "scrape")]
+use crate::tweet::{EntryVariant, FilterCursorTweetRequest, TweetResults};
+use crate::tweet::{Instruction, Tweet};
+#[cfg(feature = "scrape")]
+use crate::user::TwtUsrResult;
+use crate::user::{Error, User};
+#[cfg(feature = "scrape")]
+use ahash::{HashSet, HashSetExt};
+use rkyv::Archive;
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "scrape")]
+use tracing::warn;
+
+#[cfg(feature = "scrape")]
+pub fn twitter_request_url_mentions(cursor: Option<impl AsRef<str>>) -> String {
+    match cursor {
+        Some(cursor) => {
+            let crsr = urlencoding::encode(cursor.as_ref());
+            format!("https://twitter.com/i/api/graphql/Ev6UMJRROInk_RMH2oVbBg/NotificationsTimeline?variables=%7B%22timeline_type%22%3A%22Mentions%22%2C%22count%22%3A40%2C%22cursor%22%3A%22{crsr}%22%7D&features=%7B%22responsive_web_twitter_blue_verified_badge_is_enabled%22%3Atrue%2C%22verified_phone_label_enabled%22%3Afalse%2C%22responsive_web_graphql_timeline_navigation_enabled%22%3Atrue%2C%22tweetypie_unmention_optimization_enabled%22%3Atrue%2C%22vibe_api_enabled%22%3Atrue%2C%22responsive_web_edit_tweet_api_enabled%22%3Atrue%2C%22graphql_is_translatable_rweb_tweet_is_translatable_enabled%22%3Atrue%2C%22standardized_nudges_misinfo%22%3Atrue%2C%22interactive_text_enabled%22%3Atrue%2C%22responsive_web_enhance_cards_enabled%22%3Atrue%7D")
+        }
+        None => {
+            format!("https://twitter.com/i/api/graphql/Ev6UMJRROInk_RMH2oVbBg/NotificationsTimeline?variables=%7B%22timeline_type%22%3A%22Mentions%22%2C%22count%22%3A40%7D&features=%7B%22responsive_web_twitter_blue_verified_badge_is_enabled%22%3Atrue%2C%22verified_phone_label_enabled%22%3Afalse%2C%22responsive_web_graphql_timeline_navigation_enabled%22%3Atrue%2C%22tweetypie_unmention_optimization_enabled%22%3Atrue%2C%22vibe_api_enabled%22%3Atrue%2C%22responsive_web_edit_tweet_api_enabled%22%3Atrue%2C%22graphql_is_translatable_rweb_tweet_is_translatable_enabled%22%3Atrue%2C%22standardized_nudges_misinfo%22%3Atrue%2C%22interactive_text_enabled%22%3Atrue%2C%22responsive_web_enhance_cards_enabled%22%3Atrue%7D")
+        }
+    }
+}
+
+#[derive(
+    Clone,
+    Debug,
+    Hash,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+    Archive,
+    rkyv::Serialize,
+    rkyv::Deserialize,
+)]
+pub struct Mentions {
+    pub tweets: Vec<Tweet>,
+    pub users: Vec<User>,
+}
+
+#[cfg(feature = "scrape")]
+impl Mentions {
+    // mentions come newest first, so with `since_id` set we stop at the first tweet we already saw.
+    // cancelling stops the same way, keeping the newest mentions.
+    #[tracing::instrument]
+    pub async fn scroll(scraper: &Scraper, since_id: Option<TweetId>) -> SResult<Self> {
+        if !scraper.is_authenticated() {
+            return Err(NotAuthenticated("Mentions"));
+        }
+
+        let mut tweets = Vec::with_capacity(40);
+        let mut users = Vec::with_capacity(40);
+        let mut already_parsed_users = HashSet::with_capacity(40);
+
+        let mut pager = Pager::new(scraper, FilterCursorTweetRequest::Bottom, None, |cursor| {
+            twitter_request_url_mentions(cursor)
+        });
+        'scroll: while let Some(request) = pager.next_page::<MentionsRequest>().await? {
+            for inst in request.into_instructions() {
+                if let Instruction::TimelineAddEntries(add) = inst {
+                    for entry in add.entries {
+                        if let EntryVariant::Tweet(twt) = entry.content {
+                            let tweet = match Tweet::new_from_entry(&twt.item_content.tweet_results)
+                            {
+                                Ok(tweet) => tweet,
+                                Err(why) => {
+                                    warn!(error = %why, "Failed to parse mention. Skipping...");
+                                    continue;
+                                }
+                            };
+                            if tweet.id == 0 {
+                                continue;
+                            }
+                            if since_id.map_or(false, |since| tweet.id <= since) {
+                                break 'scroll;
+                            }
+                            tweets.push(tweet);
+
+                            if let TweetResults::Ok(trr) = twt.item_content.tweet_results {
+                                if let TwtUsrResult::User(user) = &trr.core.user_results.result {
+                                    if !already_parsed_users.insert(user.rest_id.clone()) {
+                                        continue;
+                                    }
+                                }
+                                match User::from_result(scraper, trr.core.user_results.result).await
+                                {
+                                    Ok(user) => users.push(user),
+                                    Err(why) => {
+                                        warn!(error = %why, "Failed to get data. Skipping...")
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        tweets.shrink_to_fit();
+        users.shrink_to_fit();
+
+        Ok(Mentions { tweets, users })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub(crate) struct MentionsRequest {
+    #[serde(default)]
+    pub errors: Vec<Error>,
+    pub data: MentionsData,
+}
+
+#[cfg(feature = "scrape")]
+impl MentionsRequest {
+    pub(crate) fn instructions(&self) -> &[Instruction] {
+        &self
+            .data
+            .viewer_v2
+            .user_results
+            .result
+            .notification_timeline
+            .timeline
+            .instructions
+    }
+
+    pub(crate) fn into_instructions(self) -> Vec<Instruction> {
+        self.data
+            .viewer_v2
+            .user_results
+            .result
+            .notification_timeline
+            .timeline
+            .instructions
+    }
+}
+
+#[cfg(feature = "scrape")]
+crate::impl_filter_json!(MentionsRequest);
+
+#[cfg(feature = "scrape")]
+impl HasCursor for MentionsRequest {
+    fn filter_cursor(&self, filter: FilterCursorTweetRequest) -> Option<&str> {
+        self.instructions()
+            .iter()
+            .find_map(|inst| inst.filter_cursor(filter))
+    }
+
+    fn entry_count(&self) -> usize {
+        self.instructions()
+            .iter()
+            .map(Instruction::entry_count)
+            .sum()
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub(crate) struct MentionsData {
+    pub viewer_v2: MentionsViewer,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub(crate) struct MentionsViewer {
+    pub user_results: MentionsUserResults,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub(crate) struct MentionsUserResults {
+    pub result: MentionsViewerResult,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub(crate) struct MentionsViewerResult {
+    pub notification_timeline: MentionsTimeline,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub(crate) struct MentionsTimeline {
+    pub timeline: MentionsInstructions,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub(crate) struct MentionsInstructions {
+    pub instructions: Vec<Instruction>,
+}
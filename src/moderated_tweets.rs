@@ -1,13 +1,15 @@
-use crate::error::SResult;
-use crate::error::TwtScrapeError::TwitterJSONError;
+use crate::error::{SResult, TwitterWarning};
+use crate::id::TweetId;
+#[cfg(feature = "scrape")]
+use crate::paginate::{paginate, HasCursor};
 use crate::scrape::Scraper;
-use crate::tweet::{Entry, FilterCursorTweetRequest, Instruction, Tweet};
+use crate::tweet::{EntryVariant, FilterCursorTweetRequest, Instruction, Tweet};
 use crate::user::Error;
 use crate::{FilterJSON, TwitterIdType};
 use rkyv::Archive;
 use serde::{Deserialize, Serialize};
 use std::collections::VecDeque;
-use std::fmt::Display;
+use std::fmt::{Debug, Display};
 use tracing::{instrument, warn};
 
 #[cfg(feature = "scrape")]
@@ -39,16 +41,19 @@ pub fn twitter_moderated_req(
     rkyv::Deserialize,
 )]
 pub struct ModeratedTweets {
-    pub of_tweet: u64,
+    pub of_tweet: TweetId,
     pub tweets: Vec<Tweet>,
 }
 
 #[cfg(feature = "scrape")]
 impl ModeratedTweets {
+    // on cancellation the hidden replies of the pages fetched so far are returned
     #[instrument]
-    pub async fn moderated_tweets(scraper: &Scraper, tweet_id: u64) -> SResult<Self> {
+    pub async fn moderated_tweets(scraper: &Scraper, tweet_id: TweetId) -> SResult<Self> {
         let first_request = scraper
-            .api_req::<ModTweetsReq>(scraper.make_get_req(twitter_moderated_req(tweet_id, None)))
+            .api_req::<ModTweetsReq>(
+                scraper.make_get_req(twitter_moderated_req(tweet_id, None::<&str>)),
+            )
             .await?;
 
         first_request.filter_json_err()?;
@@ -73,15 +78,13 @@ impl ModeratedTweets {
         let mut tweets = Vec::with_capacity(moderated_reqs.len() * 5);
 
         for modtwt in moderated_reqs {
-            if let Rslt::TimelineResponse(tlr) = modtwt.data.tweet.result {
-                for inst in tlr.instructions {
-                    if let Instruction::TimelineAddEntries(entry) = inst {
-                        for entry in entry.entries {
-                            if let Entry::Tweet(twtent) = entry {
-                                let twet =
-                                    Tweet::new_from_entry(&twtent.item_content.tweet_results)?;
-                                tweets.push(twet);
-                            }
+            let Rslt::TimelineResponse(tlr) = modtwt.data.tweet.result;
+            for inst in tlr.instructions {
+                if let Instruction::TimelineAddEntries(entry) = inst {
+                    for entry in entry.entries {
+                        if let EntryVariant::Tweet(twtent) = entry.content {
+                            let twet = Tweet::new_from_entry(&twtent.item_content.tweet_results)?;
+                            tweets.push(twet);
                         }
                     }
                 }
@@ -116,57 +119,58 @@ pub(crate) struct ModTweetsReq {
 #[cfg(feature = "scrape")]
 impl ModTweetsReq {
     pub(crate) fn filter_cursor(&self, filter: FilterCursorTweetRequest) -> Option<&str> {
-        if let Rslt::TimelineResponse(tlr) = &self.data.tweet.result {
-            for inst in tlr.instructions {
-                inst.filter_cursor(filter)
-            }
+        match &self.data.tweet.result {
+            Rslt::TimelineResponse(tlr) => tlr
+                .instructions
+                .iter()
+                .find_map(|inst| inst.filter_cursor(filter)),
         }
-
-        None
     }
 
     #[tracing::instrument]
     pub(crate) async fn scroll(
         scraper: &Scraper,
-        id: impl TwitterIdType + Display,
+        id: impl TwitterIdType + Display + Debug,
         first_cursor: String,
         filter: FilterCursorTweetRequest,
     ) -> SResult<VecDeque<Self>> {
-        let mut requests = VecDeque::with_capacity(5);
-
-        let mut cursor_counter = first_cursor.to_string();
-        let mut break_on_next = false;
-        loop {
-            let scrolled_up_request = scraper
-                .api_req::<ModTweetsReq>(
-                    scraper.make_get_req(twitter_moderated_req(&id, Some(&cursor_counter))),
-                )
-                .await?;
+        let id = id.to_string();
+        paginate(scraper, filter, first_cursor, None, |cursor| {
+            twitter_moderated_req(id.as_str(), Some(cursor))
+        })
+        .await
+    }
+}
 
-            if let Err(why) = scrolled_up_request.json_request_filter_errors() {
-                warn!(error = why, "Error while scrolling, ending early.")
-            }
+#[cfg(feature = "scrape")]
+crate::impl_filter_json!(ModTweetsReq);
 
-            requests.push_front(scrolled_up_request);
-            if break_on_next {
-                break;
-            }
+#[cfg(feature = "scrape")]
+impl HasCursor for ModTweetsReq {
+    fn filter_cursor(&self, filter: FilterCursorTweetRequest) -> Option<&str> {
+        ModTweetsReq::filter_cursor(self, filter)
+    }
 
-            match scrolled_up_request.filter_cursor(filter) {
-                Some(up) => {
-                    cursor_counter = up.to_string();
-                }
-                None => break_on_next = true,
+    fn entry_count(&self) -> usize {
+        match &self.data.tweet.result {
+            Rslt::TimelineResponse(tlr) => {
+                tlr.instructions.iter().map(Instruction::entry_count).sum()
             }
         }
+    }
 
-        Ok(requests)
+    // hidden replies always scrolled past pages that came back with errors
+    fn page_warnings(&self) -> SResult<Vec<TwitterWarning>> {
+        match self.filter_json_err() {
+            Ok(warnings) => Ok(warnings),
+            Err(why) => {
+                warn!(error = %why, "Error while scrolling, continuing.");
+                Ok(vec![])
+            }
+        }
     }
 }
 
-#[cfg(feature = "scrape")]
-crate::impl_filter_json!(ModTweetsReq);
-
 #[derive(
     Clone,
     Debug,
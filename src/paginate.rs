@@ -0,0 +1,215 @@
+use crate::error::{SResult, TwitterWarning, TwtScrapeError};
+use crate::scrape::Scraper;
+use crate::tweet::FilterCursorTweetRequest;
+use crate::FilterJSON;
+use serde::de::DeserializeOwned;
+use std::collections::VecDeque;
+use tracing::warn;
+
+// a page of a cursor paginated timeline
+pub(crate) trait HasCursor: FilterJSON + DeserializeOwned {
+    // the cursor to the next page in `filter`'s direction, `None` on the last page
+    fn filter_cursor(&self, filter: FilterCursorTweetRequest) -> Option<&str>;
+
+    // timeline entries on the page, cursors left out. counted against a `max_entries`, and a
+    // page without any ends the scroll
+    fn entry_count(&self) -> usize;
+
+    // the warnings to log for the page. errors twitter sent instead of data fail the scroll
+    fn page_warnings(&self) -> SResult<Vec<TwitterWarning>> {
+        self.filter_json_err()
+    }
+}
+
+// walks a cursor paginated timeline one page at a time, for scrolls that parse every page as it
+// comes in. past the end twitter keeps handing out cursors, either the same one again or a new
+// one on a page without entries, so either of those ends it.
+pub(crate) struct Pager<'a, U> {
+    scraper: &'a Scraper,
+    filter: FilterCursorTweetRequest,
+    url: U,
+    cursor: Option<String>,
+    done: bool,
+}
+
+impl<'a, U> Pager<'a, U>
+where
+    U: Fn(Option<&str>) -> String,
+{
+    // `url` builds the request for a cursor, starting at `cursor` or at the first page on `None`
+    pub(crate) fn new(
+        scraper: &'a Scraper,
+        filter: FilterCursorTweetRequest,
+        cursor: Option<String>,
+        url: U,
+    ) -> Self {
+        Pager {
+            scraper,
+            filter,
+            url,
+            cursor,
+            done: false,
+        }
+    }
+
+    // the cursor `next_page` asks for next, or the one that failed. `None` once the last page
+    // was fetched
+    pub(crate) fn cursor(&self) -> Option<&str> {
+        match self.done {
+            true => None,
+            false => self.cursor.as_deref(),
+        }
+    }
+
+    // the last page was fetched
+    pub(crate) fn is_done(&self) -> bool {
+        self.done
+    }
+
+    // `None` after the last page or once the scraper is cancelled
+    pub(crate) async fn next_page<T: HasCursor>(&mut self) -> SResult<Option<T>> {
+        if self.done || self.scraper.is_cancelled() {
+            return Ok(None);
+        }
+        let page = self
+            .scraper
+            .api_req::<T>(
+                self.scraper
+                    .make_get_req((self.url)(self.cursor.as_deref())),
+            )
+            .await?;
+        for warning in page.page_warnings()? {
+            warn!(code = %warning.code, message = warning.message, "Page returned with errors");
+        }
+
+        let next = page.filter_cursor(self.filter).map(ToString::to_string);
+        if page.entry_count() == 0 || next.is_none() || next == self.cursor {
+            self.done = true;
+        } else {
+            self.cursor = next;
+        }
+        Ok(Some(page))
+    }
+}
+
+// fetches the pages after `first_cursor` until one comes without entries or a new cursor,
+// `max_entries` were seen or the scraper is cancelled. newest page first. `url` builds the
+// request for a cursor.
+pub(crate) async fn paginate<T: HasCursor>(
+    scraper: &Scraper,
+    filter: FilterCursorTweetRequest,
+    first_cursor: String,
+    max_entries: Option<usize>,
+    url: impl Fn(&str) -> String,
+) -> SResult<VecDeque<T>> {
+    match paginate_partial(scraper, filter, first_cursor, max_entries, url).await {
+        (_, Some((_, why))) => Err(why),
+        (pages, None) => Ok(pages),
+    }
+}
+
+// like `paginate` but a failed page hands back the pages before it, and the cursor that failed
+pub(crate) async fn paginate_partial<T: HasCursor>(
+    scraper: &Scraper,
+    filter: FilterCursorTweetRequest,
+    first_cursor: String,
+    max_entries: Option<usize>,
+    url: impl Fn(&str) -> String,
+) -> (VecDeque<T>, Option<(String, TwtScrapeError)>) {
+    let mut pages = VecDeque::with_capacity(5);
+    let mut pager = Pager::new(scraper, filter, Some(first_cursor), |cursor| {
+        url(cursor.unwrap_or_default())
+    });
+
+    let mut remaining = max_entries.unwrap_or(usize::MAX);
+    loop {
+        match pager.next_page::<T>().await {
+            Ok(Some(page)) => {
+                remaining = remaining.saturating_sub(page.entry_count());
+                pages.push_front(page);
+                if remaining == 0 {
+                    break;
+                }
+            }
+            Ok(None) => break,
+            Err(why) => {
+                let cursor = pager.cursor().unwrap_or_default().to_string();
+                return (pages, Some((cursor, why)));
+            }
+        }
+    }
+
+    (pages, None)
+}
+
+#[cfg(test)]
+pub(crate) fn follow_page(cursor: &str) -> String {
+    let user = r#"{"entryId":"user-1","content":{"itemContent":{"user_results":{"result":{"__typename":"UserUnavailable","unavailable_message":{"rtl":false,"text":"Suspended"},"reason":"Suspended"}}}}}"#;
+    format!(
+        r#"{{"data":{{"user":{{"result":{{"__typename":"User","timeline":{{"timeline":{{"instructions":[{{"type":"TimelineAddEntries","entries":[{user},{cursor}]}}]}}}}}}}}}}}}"#
+    )
+}
+
+#[cfg(test)]
+fn paginate_follows(canned: Vec<String>) -> (usize, Vec<String>) {
+    use crate::follow::FollowReq;
+    use crate::scrape::dry_run;
+
+    dry_run(canned, |scraper| async move {
+        let pages = paginate::<FollowReq>(
+            &scraper,
+            FilterCursorTweetRequest::Bottom,
+            "first".to_string(),
+            None,
+            |cursor| format!("https://twitter.com/i/api/graphql/test?cursor={cursor}"),
+        )
+        .await
+        .unwrap();
+        (pages.len(), scraper.stop_dry_run())
+    })
+}
+
+#[test]
+fn last_page_fetched_once() {
+    let first = follow_page(
+        r#"{"entryId":"cursor-bottom-1","content":{"value":"second","cursorType":"Bottom"}}"#,
+    );
+    let last =
+        follow_page(r#"{"entryId":"cursor-top-2","content":{"value":"first","cursorType":"Top"}}"#);
+
+    let (pages, requested) = paginate_follows(vec![first, last.clone(), last]);
+    assert_eq!(pages, 2);
+    assert_eq!(requested.len(), 2);
+    assert!(requested[1].ends_with("cursor=second"));
+}
+
+#[test]
+fn repeated_cursor_fetched_once() {
+    let first = follow_page(
+        r#"{"entryId":"cursor-bottom-1","content":{"value":"second","cursorType":"Bottom"}}"#,
+    );
+    let last = follow_page(
+        r#"{"entryId":"cursor-bottom-2","content":{"value":"second","cursorType":"Bottom"}}"#,
+    );
+
+    let (pages, requested) = paginate_follows(vec![first, last.clone(), last]);
+    assert_eq!(pages, 2);
+    assert_eq!(requested.len(), 2);
+}
+
+#[test]
+fn empty_page_ends_the_scroll() {
+    let first = follow_page(
+        r#"{"entryId":"cursor-bottom-1","content":{"value":"second","cursorType":"Bottom"}}"#,
+    );
+    let empty = |cursor: &str| {
+        format!(
+            r#"{{"data":{{"user":{{"result":{{"__typename":"User","timeline":{{"timeline":{{"instructions":[{{"type":"TimelineAddEntries","entries":[{{"entryId":"cursor-bottom-2","content":{{"value":"{cursor}","cursorType":"Bottom"}}}}]}}]}}}}}}}}}}}}"#
+        )
+    };
+
+    let (pages, requested) = paginate_follows(vec![first, empty("third"), empty("fourth")]);
+    assert_eq!(pages, 2);
+    assert_eq!(requested.len(), 2);
+    assert!(requested[1].ends_with("cursor=second"));
+}
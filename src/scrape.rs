@@ -1,27 +1,110 @@
 use ahash::HashMap;
-use std::sync::Arc;
+use std::collections::VecDeque;
+use std::fmt::Debug;
+use std::ops::Range;
+use std::path::Path;
+use std::sync::{Arc, Mutex, MutexGuard, RwLock, RwLockReadGuard, RwLockWriteGuard};
 use std::time::Duration;
 
 use reqwest::cookie::{CookieStore, Jar};
-use reqwest::{Client, Proxy, RequestBuilder, Response, StatusCode};
+use reqwest::header::{HeaderMap, COOKIE, ORIGIN, REFERER, SET_COOKIE};
+use reqwest::{Client, Method, Proxy, RequestBuilder, Response, StatusCode, Url};
 
+mod ratelimit;
+mod redirect;
+mod retry;
 mod timing;
-use crate::error::TwtScrapeError::{ErrRequestStatus, InvalidProxy, RequestFailed};
-use crate::error::{SResult, TwtScrapeError};
+mod tokens;
+use crate::error::TwtScrapeError::{
+    DryRunExhausted, DryRunRawRequest, ErrRequestStatus, InvalidCredentials, InvalidProxy, Io,
+    NotFound, RateLimited, RequestFailed, TwitterJSONError,
+};
+use crate::error::{SResult, TwitterErrorCode, TwtScrapeError};
+use crate::id::UserId;
+use crate::user::ErrorResponse;
+use ratelimit::reset_at;
+pub use ratelimit::{Endpoint, RateLimitWindow};
+pub use redirect::{RedirectPolicy, DEFAULT_MAX_REDIRECTS};
+pub use retry::{RetryPolicy, DEFAULT_MAX_RETRIES};
 use serde::de::DeserializeOwned;
 use serde_json::Value;
 use timing::*;
-use tracing::warn;
+pub use tokens::Token;
+use tokens::TokenPool;
+use tokio::io::AsyncWriteExt;
+use tokio::sync::Semaphore;
+use tokio_util::sync::CancellationToken;
+use tracing::{info, warn};
+
+pub const DEFAULT_MAX_CONCURRENCY: usize = 4;
+// threads crawled at once by timeline scrolls, still bound by `max_concurrency`
+pub const DEFAULT_THREAD_PARALLELISM: usize = 4;
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
+// a full rate limit window, so by default every exhausted window is waited out
+pub const DEFAULT_MAX_RATELIMIT_WAIT: Duration = Duration::from_secs(15 * 60);
+pub const TWITTER_URL: &str = "https://twitter.com";
+pub const DEFAULT_REFERER: &str = "https://twitter.com/";
+pub const DEFAULT_ORIGIN: &str = "https://twitter.com";
+// @twitter (now @X), looked up by `Scraper::validate`
+const VALIDATE_USER_ID: UserId = UserId(783214);
+
+// an account session, the two cookies twitter needs to treat requests as logged in
+#[derive(Clone, PartialEq, Eq)]
+pub struct Credentials {
+    pub auth_token: String,
+    // the `ct0` cookie, also sent back as the csrf header
+    pub csrf_token: String,
+}
+
+// scrapers end up in tracing spans, keep the tokens out of the logs
+impl std::fmt::Debug for Credentials {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Credentials").finish_non_exhaustive()
+    }
+}
+
+// knobs for what twitter is asked to include in timelines
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ScrapeOptions {
+    // ads come back as their own entries and are labeled, see `UserTweetsAndReplies::promoted`
+    pub include_promoted_content: bool,
+}
 
 //Intended to be associated with a single task
-//Making the time pieces internally mutable with a mutex could make this shareable
+//The client, delays, guest token and concurrency limit sit behind Arcs so clones share them
 #[derive(Debug)]
 pub struct Scraper {
     bearer_token: String,
     client: Client,
-    delayer: Delayer,
-    guest_token: TimedToken,
+    delayer: Arc<Delayer>,
+    guest_token: Arc<TimedToken>,
+    // kept out of the shared client so every account has cookies of its own, see `send`
     cookie: Arc<Jar>,
+    // shared with `clone_with_cancellation` clones so a rotated token reaches them too
+    credentials: Arc<RwLock<Option<Credentials>>>,
+    // when not empty every request goes out as the next healthy token instead
+    tokens: Arc<TokenPool>,
+    referer: String,
+    origin: String,
+    resolve_websites: bool,
+    options: ScrapeOptions,
+    not_found_statuses: Vec<StatusCode>,
+    capture_headers: bool,
+    last_headers: Mutex<Option<HeaderMap>>,
+    rate_limits: Mutex<HashMap<Endpoint, RateLimitWindow>>,
+    max_ratelimit_wait: Duration,
+    retry_policy: RetryPolicy,
+    max_concurrency: usize,
+    thread_parallelism: usize,
+    concurrency: Arc<Semaphore>,
+    cancellation: Option<CancellationToken>,
+    dry_run: Mutex<Option<DryRun>>,
+}
+
+#[derive(Debug, Default)]
+struct DryRun {
+    canned: VecDeque<String>,
+    requested: Vec<String>,
 }
 
 impl Scraper {
@@ -49,69 +132,709 @@ impl Scraper {
         }
     }
 
-    pub fn make_get_req(&self, url: impl AsRef<str>) -> RequestBuilder {
-        self.client.get(url.as_ref())
+    /// Headers of the last response Twitter sent back.
+    /// Always `None` unless enabled with `ScraperBuilder::with_captured_headers`.
+    pub fn last_response_headers(&self) -> Option<HeaderMap> {
+        match self.last_headers.lock() {
+            Ok(guard) => guard.clone(),
+            Err(_) => None,
+        }
+    }
+
+    // the last window twitter reported for every endpoint this scraper has hit
+    pub fn rate_limit_status(&self) -> HashMap<Endpoint, RateLimitWindow> {
+        match self.rate_limits.lock() {
+            Ok(guard) => guard.clone(),
+            Err(_) => HashMap::default(),
+        }
+    }
+
+    fn record_rate_limit(&self, response: &Response) {
+        if let Some(window) = RateLimitWindow::from_headers(response.headers()) {
+            if let Ok(mut guard) = self.rate_limits.lock() {
+                guard.insert(Endpoint::from_url(response.url()), window);
+            }
+        }
+    }
+
+    // sleeps until the endpoint's exhausted window resets, unless that is further out than
+    // `max_ratelimit_wait` in which case the caller gets `RateLimited` straight away
+    async fn wait_for_rate_limit(&self, url: &Url) -> SResult<bool> {
+        // the windows are per token, a token pool benches exhausted tokens instead
+        if !self.tokens.is_empty() {
+            return Ok(false);
+        }
+        let window = match self.rate_limits.lock() {
+            Ok(guard) => guard.get(&Endpoint::from_url(url)).copied(),
+            Err(_) => None,
+        };
+        let (wait, reset_at) = match window.and_then(|w| w.wait().map(|wait| (wait, w.reset_at))) {
+            Some(window) => window,
+            None => return Ok(false),
+        };
+        if wait > self.max_ratelimit_wait {
+            return Err(RateLimited {
+                reset_at: Some(reset_at),
+            });
+        }
+        info!(?wait, %reset_at, "Rate limited, waiting for the window to reset");
+        tokio::time::sleep(wait).await;
+        Ok(true)
+    }
+
+    fn capture_headers(&self, response: &Response) {
+        if !self.capture_headers {
+            return;
+        }
+        if let Ok(mut guard) = self.last_headers.lock() {
+            *guard = Some(response.headers().clone());
+        }
+    }
+
+    // logged in sessions carry twitter's `auth_token` cookie, guest sessions never do
+    pub fn is_authenticated(&self) -> bool {
+        if self.credentials().is_some() {
+            return true;
+        }
+        self.cookie
+            .cookies(&TWITTER_URL.parse().unwrap())
+            .and_then(|cookies| {
+                cookies
+                    .to_str()
+                    .ok()
+                    .map(|cookies| cookies.split("; ").any(|c| c.starts_with("auth_token=")))
+            })
+            .unwrap_or(false)
     }
 
+    // one cheap lookup of a public account, to find out about a dead guest token or account
+    // before a long scrape runs into it. twitter saying no comes back as `InvalidCredentials`,
+    // anything else (network, rate limits) as is.
     #[tracing::instrument]
-    pub async fn api_req<T: DeserializeOwned>(&self, request: RequestBuilder) -> SResult<T> {
-        let response = self.api_req_raw_request(request).await?;
-        response.json().await.map_err(TwtScrapeError::SchemaErr)
+    pub async fn validate(&self) -> SResult<()> {
+        match crate::user::User::by_id(self, VALIDATE_USER_ID).await {
+            Ok(_) => Ok(()),
+            Err(ErrRequestStatus(why))
+                if matches!(
+                    why.status(),
+                    Some(StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN)
+                ) =>
+            {
+                Err(InvalidCredentials(why.to_string()))
+            }
+            Err(TwitterJSONError(TwitterErrorCode::BadAuthentication, message)) => {
+                Err(InvalidCredentials(message))
+            }
+            Err(why) => Err(why),
+        }
+    }
+
+    /// How many requests this scraper lets through at once.
+    pub fn max_concurrency(&self) -> usize {
+        self.max_concurrency
+    }
+
+    // how many threads a timeline scroll crawls at once
+    pub fn thread_parallelism(&self) -> usize {
+        self.thread_parallelism
+    }
+
+    pub fn resolves_websites(&self) -> bool {
+        self.resolve_websites
+    }
+
+    pub fn options(&self) -> &ScrapeOptions {
+        &self.options
+    }
+
+    // shares the client, delays, guest token and concurrency limit with `self` but sends every
+    // request as the given account. nothing account specific (cookies, captured headers, dry runs)
+    // carries over.
+    pub fn clone_with_account(&self, credentials: Credentials) -> Scraper {
+        let mut scraper = self.shared(
+            Arc::new(RwLock::new(Some(credentials))),
+            Arc::new(Jar::default()),
+        );
+        scraper.tokens = Arc::new(TokenPool::default());
+        scraper
+    }
+
+    // rotates every request over `tokens`, replacing the scraper's own guest token and
+    // credentials. a token that gets a 429 or uses up its rate limit window is skipped until the
+    // window resets. once all of them are benched requests wait for the first one back, or fail
+    // with `RateLimited` if that is further out than `with_retry_on_ratelimit_max_wait`.
+    pub fn with_tokens(mut self, tokens: Vec<Token>) -> Scraper {
+        self.tokens = Arc::new(TokenPool::new(tokens));
+        self
+    }
+
+    fn shared(&self, credentials: Arc<RwLock<Option<Credentials>>>, cookie: Arc<Jar>) -> Scraper {
+        Scraper {
+            bearer_token: self.bearer_token.clone(),
+            client: self.client.clone(),
+            delayer: self.delayer.clone(),
+            guest_token: self.guest_token.clone(),
+            cookie,
+            credentials,
+            tokens: self.tokens.clone(),
+            referer: self.referer.clone(),
+            origin: self.origin.clone(),
+            resolve_websites: self.resolve_websites,
+            options: self.options.clone(),
+            not_found_statuses: self.not_found_statuses.clone(),
+            capture_headers: self.capture_headers,
+            last_headers: Mutex::new(None),
+            // limits are counted per account
+            rate_limits: Mutex::new(HashMap::default()),
+            max_ratelimit_wait: self.max_ratelimit_wait,
+            retry_policy: self.retry_policy.clone(),
+            max_concurrency: self.max_concurrency,
+            thread_parallelism: self.thread_parallelism,
+            concurrency: self.concurrency.clone(),
+            cancellation: self.cancellation.clone(),
+            dry_run: Mutex::new(None),
+        }
+    }
+
+    // multi request operations (scrolls, batch lookups, thread crawls) check this between
+    // requests and stop early with what they have so far. a single request is never cut short.
+    pub fn is_cancelled(&self) -> bool {
+        self.cancellation
+            .as_ref()
+            .map(CancellationToken::is_cancelled)
+            .unwrap_or(false)
+    }
+
+    pub fn cancellation_token(&self) -> Option<&CancellationToken> {
+        self.cancellation.as_ref()
+    }
+
+    // the same scraper with its own token, for cancelling one operation without the rest
+    pub fn clone_with_cancellation(&self, token: CancellationToken) -> Scraper {
+        let mut scraper = self.shared(Arc::clone(&self.credentials), Arc::clone(&self.cookie));
+        scraper.cancellation = Some(token);
+        scraper
+    }
+
+    // replaces the account for every request started after this, in flight ones keep the old one
+    pub fn set_credentials(&self, credentials: Credentials) {
+        *self.credentials_mut() = Some(credentials);
     }
 
+    // without credentials set the token goes into the cookie jar instead
+    pub fn set_auth_token(&self, auth_token: impl Into<String>) {
+        let auth_token = auth_token.into();
+        match self.credentials_mut().as_mut() {
+            Some(creds) => creds.auth_token = auth_token,
+            None => self.cookie.add_cookie_str(
+                &format!("auth_token={auth_token}"),
+                &TWITTER_URL.parse().unwrap(),
+            ),
+        }
+    }
+
+    // adds the cookies to this scraper's jar. `auth_token` and `ct0` also update the credentials
+    // when there are any, the rest are sent along with them.
+    pub fn set_cookies(&self, cookies: HashMap<String, String>) {
+        let url = TWITTER_URL.parse().unwrap();
+        let mut credentials = self.credentials_mut();
+        for (name, value) in cookies {
+            self.cookie.add_cookie_str(&format!("{name}={value}"), &url);
+            match (credentials.as_mut(), name.as_str()) {
+                (Some(creds), "auth_token") => creds.auth_token = value,
+                (Some(creds), "ct0") => creds.csrf_token = value,
+                _ => {}
+            }
+        }
+    }
+
+    // credentials are only ever replaced field by field, a panic while they were locked can't
+    // have left them half written
+    fn credentials(&self) -> RwLockReadGuard<'_, Option<Credentials>> {
+        match self.credentials.read() {
+            Ok(guard) => guard,
+            Err(poisoned) => poisoned.into_inner(),
+        }
+    }
+
+    fn credentials_mut(&self) -> RwLockWriteGuard<'_, Option<Credentials>> {
+        match self.credentials.write() {
+            Ok(guard) => guard,
+            Err(poisoned) => poisoned.into_inner(),
+        }
+    }
+
+    fn with_credentials(&self, request: RequestBuilder, url: Option<&Url>) -> RequestBuilder {
+        let jar = url.and_then(|url| self.cookie.cookies(url));
+        let jar = jar.as_ref().and_then(|jar| jar.to_str().ok());
+        match (&*self.credentials(), jar) {
+            (Some(creds), jar) => account_headers(request, creds, jar),
+            (None, Some(jar)) => request.header(COOKIE, jar),
+            (None, None) => request,
+        }
+    }
+
+    // `None` without a token pool. with every token benched this waits for the first one back.
+    async fn pick_token(&self) -> SResult<Option<(usize, Token)>> {
+        loop {
+            if self.tokens.is_empty() {
+                return Ok(None);
+            }
+            if let Some(picked) = self.tokens.next_healthy() {
+                return Ok(Some(picked));
+            }
+            let reset_at = match self.tokens.next_recovery() {
+                Some(reset_at) => reset_at,
+                // one came back in the meantime
+                None => continue,
+            };
+            let wait = (reset_at - chrono::Utc::now()).to_std().unwrap_or_default();
+            if wait > self.max_ratelimit_wait {
+                return Err(RateLimited {
+                    reset_at: Some(reset_at),
+                });
+            }
+            info!(?wait, %reset_at, "Every token is rate limited, waiting for the first to reset");
+            tokio::time::sleep(wait).await;
+        }
+    }
+
+    // a plain GET for media on twimg, none of the api headers or retries
     #[tracing::instrument]
-    pub async fn api_req_raw_request(&self, request: RequestBuilder) -> SResult<Response> {
-        self.delayer.wait().await;
-        let token = self.guest_token.get_token(self.refresh_token()).await?;
-        let headed = request
-            .header("X-Guest-Token", token)
-            .bearer_auth(&self.bearer_token);
+    pub async fn download_bytes(&self, url: &str) -> SResult<Vec<u8>> {
+        let request = self.client.get(url);
+        if let Some(canned) = self.dry_run_request(&request) {
+            return canned.map(String::into_bytes);
+        }
+        let _permit = self.concurrency.acquire().await.ok();
+        let response = request
+            .send()
+            .await
+            .map_err(RequestFailed)?
+            .error_for_status()
+            .map_err(ErrRequestStatus)?;
+        Ok(response.bytes().await.map_err(RequestFailed)?.to_vec())
+    }
 
-        match headed
+    // like `download_bytes` but the body goes to `path` chunk by chunk instead of into memory.
+    // `progress` is called after every chunk with the bytes written so far and the
+    // content-length if twitter sent one. returns the size of the file. a download that breaks
+    // off midway removes the partial file.
+    #[tracing::instrument(skip(progress))]
+    pub async fn download_to_file(
+        &self,
+        url: &str,
+        path: impl AsRef<Path> + Debug,
+        progress: impl Fn(u64, Option<u64>),
+    ) -> SResult<u64> {
+        let request = self.client.get(url);
+        if let Some(canned) = self.dry_run_request(&request) {
+            let body = canned?;
+            tokio::fs::write(path, &body).await.map_err(Io)?;
+            progress(body.len() as u64, Some(body.len() as u64));
+            return Ok(body.len() as u64);
+        }
+        let _permit = self.concurrency.acquire().await.ok();
+        let mut response = request
             .send()
             .await
             .map_err(RequestFailed)?
             .error_for_status()
-        {
-            Ok(req) => Ok(req),
+            .map_err(ErrRequestStatus)?;
+
+        let total = response.content_length();
+        let mut file = tokio::fs::File::create(path.as_ref()).await.map_err(Io)?;
+        let streamed = async {
+            let mut written = 0;
+            while let Some(chunk) = response.chunk().await.map_err(RequestFailed)? {
+                file.write_all(&chunk).await.map_err(Io)?;
+                written += chunk.len() as u64;
+                progress(written, total);
+            }
+            file.flush().await.map_err(Io)?;
+            Ok(written)
+        }
+        .await;
+        if streamed.is_err() {
+            // a truncated file would pass for a finished download later on
+            drop(file);
+            if let Err(why) = tokio::fs::remove_file(path.as_ref()).await {
+                warn!(error = %why, "Failed to remove partial download");
+            }
+        }
+        streamed
+    }
+
+    // `download_to_file` without the progress callback, for twimg media urls
+    pub async fn download_media(&self, url: &str, dest: &Path) -> SResult<u64> {
+        self.download_to_file(url, dest, |_, _| {}).await
+    }
+
+    pub fn make_get_req(&self, url: impl AsRef<str>) -> RequestBuilder {
+        self.client
+            .get(url.as_ref())
+            .header(REFERER, &self.referer)
+            .header(ORIGIN, &self.origin)
+    }
+
+    // nothing is sent while dry running, every api request is logged and answered with the next
+    // canned body instead. once those run out requests fail with `DryRunExhausted`.
+    pub fn dry_run(&self, canned: impl IntoIterator<Item = String>) {
+        *self.dry_run_state() = Some(DryRun {
+            canned: canned.into_iter().collect(),
+            requested: Vec::new(),
+        });
+    }
+
+    // ends the dry run, handing back the urls that would have been requested
+    pub fn stop_dry_run(&self) -> Vec<String> {
+        self.dry_run_state()
+            .take()
+            .map(|dry| dry.requested)
+            .unwrap_or_default()
+    }
+
+    pub fn is_dry_run(&self) -> bool {
+        self.dry_run_state().is_some()
+    }
+
+    // a poisoned lock still holds a usable dry run, giving up on it would send real requests
+    fn dry_run_state(&self) -> MutexGuard<'_, Option<DryRun>> {
+        match self.dry_run.lock() {
+            Ok(guard) => guard,
+            Err(poisoned) => poisoned.into_inner(),
+        }
+    }
+
+    // `None` when not dry running, otherwise the canned body for this request
+    fn dry_run_request(&self, request: &RequestBuilder) -> Option<SResult<String>> {
+        let mut dry_run = self.dry_run_state();
+        let dry = dry_run.as_mut()?;
+        let url = request
+            .try_clone()
+            .and_then(|req| req.build().ok())
+            .map(|req| req.url().to_string())
+            .unwrap_or_default();
+        info!(url, "Dry run, not sending request");
+        dry.requested.push(url.clone());
+        Some(dry.canned.pop_front().ok_or(DryRunExhausted(url)))
+    }
+
+    // transient twitter errors (over capacity, internal error) are retried following the
+    // scraper's `RetryPolicy`, on top of the status retries in `api_req_raw_request`
+    #[tracing::instrument]
+    pub async fn api_req<T: DeserializeOwned>(&self, mut request: RequestBuilder) -> SResult<T> {
+        let mut attempt = 0;
+        loop {
+            let retry = request.try_clone();
+            match self.api_req_once::<T>(request).await {
+                Err(TwitterJSONError(code, message))
+                    if code.is_retriable() && attempt < self.retry_policy.max_retries =>
+                {
+                    request = match retry {
+                        Some(retry) => retry,
+                        None => return Err(TwitterJSONError(code, message)),
+                    };
+                    attempt += 1;
+                    warn!(%code, reason = %message, attempt, "Twitter is struggling. Retrying.");
+                    tokio::time::sleep(self.retry_policy.backoff(attempt)).await;
+                }
+                parsed => return parsed,
+            }
+        }
+    }
+
+    async fn api_req_once<T: DeserializeOwned>(&self, request: RequestBuilder) -> SResult<T> {
+        if let Some(canned) = self.dry_run_request(&request) {
+            return Self::parse_body(canned?.as_bytes());
+        }
+        let response = self.api_req_raw_request(request).await?;
+        let body = response
+            .bytes()
+            .await
+            .map_err(TwtScrapeError::LoadJsonFailed)?;
+        Self::parse_body(&body)
+    }
+
+    // `Ok(None)` where `api_req` would fail with `NotFound`
+    pub async fn api_req_opt<T: DeserializeOwned>(
+        &self,
+        request: RequestBuilder,
+    ) -> SResult<Option<T>> {
+        match self.api_req(request).await {
+            Ok(parsed) => Ok(Some(parsed)),
+            Err(NotFound(_)) => Ok(None),
+            Err(why) => Err(why),
+        }
+    }
+
+    pub(crate) fn parse_body<T: DeserializeOwned>(body: &[u8]) -> SResult<T> {
+        match serde_json::from_slice::<T>(body) {
+            Ok(parsed) => Ok(parsed),
             Err(why) => {
-                warn!(error = why, "Got an error while asking twitter. Retrying.");
-                // twitter randomly tells us to fuck off
-                // retrying after a wait usually works
-                let mut std_delay_secs = 1;
-                if let Some(sc) = why.status() {
-                    if sc == StatusCode::TOO_MANY_REQUESTS {
-                        std_delay_secs = 5;
+                // `data` got nulled out, report why twitter did that instead of the schema mismatch
+                if let Ok(response) = serde_json::from_slice::<ErrorResponse>(body) {
+                    if let Some(error) = response.errors.first() {
+                        return Err(TwitterJSONError(error.error_code(), error.message.clone()));
                     }
                 }
-                tokio::time::sleep(Duration::from_secs(std_delay_secs)).await;
+                Err(TwtScrapeError::BadJSONSchema(
+                    std::any::type_name::<T>(),
+                    why.to_string(),
+                ))
+            }
+        }
+    }
 
-                let token = self.guest_token.get_token(self.refresh_token()).await?;
-                let headed = request
-                    .header("Authorization", format!("Bearer {}", self.bearer_token))
-                    .header("X-Guest-Token", token);
+    // 429s and 5xx statuses on GETs are retried following the scraper's `RetryPolicy`, waiting
+    // for as long as twitter's `Retry-After` or rate limit reset asks for when it sends one
+    #[tracing::instrument]
+    pub async fn api_req_raw_request(&self, mut request: RequestBuilder) -> SResult<Response> {
+        // a canned body can't stand in for a raw response
+        if let Some(canned) = self.dry_run_request(&request) {
+            canned?;
+            return Err(DryRunRawRequest);
+        }
+        let built = request.try_clone().and_then(|req| req.build().ok());
+        let retriable = built
+            .as_ref()
+            .map(|req| req.method() == Method::GET)
+            .unwrap_or(false);
+        let url = built.map(|req| req.url().clone());
 
-                Ok(headed
-                    .send()
-                    .await
-                    .map_err(RequestFailed)?
-                    .error_for_status()
-                    .map_err(ErrRequestStatus)?)
+        let mut attempt = 0;
+        loop {
+            if let Some(url) = &url {
+                self.wait_for_rate_limit(url).await?;
             }
+            let retry = request.try_clone();
+            let response = self.send(request, url.as_ref()).await?;
+
+            // "no such thing", retrying won't change that
+            if self.not_found_statuses.contains(&response.status()) {
+                return Err(NotFound(response.status().as_u16()));
+            }
+
+            let status = response.status();
+            let retry = match retry {
+                Some(retry)
+                    if retriable
+                        && RetryPolicy::retries_status(status)
+                        && attempt < self.retry_policy.max_retries =>
+                {
+                    retry
+                }
+                // out of retries, or never retried, let the caller schedule around the reset
+                _ if status == StatusCode::TOO_MANY_REQUESTS => {
+                    return Err(RateLimited {
+                        reset_at: reset_at(response.headers()),
+                    })
+                }
+                _ => return response.error_for_status().map_err(ErrRequestStatus),
+            };
+            attempt += 1;
+
+            // twitter randomly tells us to fuck off
+            // retrying after a wait usually works
+            let delay = self.retry_policy.retry_delay(
+                response.headers(),
+                attempt,
+                self.max_ratelimit_wait,
+            )?;
+            warn!(%status, attempt, ?delay, "Got an error while asking twitter. Retrying.");
+            // a 429 that came with a reset time waits for that instead
+            let waited = status == StatusCode::TOO_MANY_REQUESTS
+                && self.wait_for_rate_limit(response.url()).await?;
+            // the benched token's spot is taken by the next one right away
+            let rotated = status == StatusCode::TOO_MANY_REQUESTS && self.tokens.has_healthy();
+            if !waited && !rotated {
+                tokio::time::sleep(delay).await;
+            }
+            request = retry;
         }
     }
+
+    async fn send(&self, request: RequestBuilder, url: Option<&Url>) -> SResult<Response> {
+        let picked = self.pick_token().await?;
+        // the semaphore is never closed
+        let _permit = self.concurrency.acquire().await.ok();
+        self.delayer.wait().await;
+        let headed = match &picked {
+            Some((_, Token::Guest(token))) => request.header("X-Guest-Token", token),
+            Some((_, Token::Account(creds))) => {
+                let token = self.guest_token.get_token(self.refresh_token()).await?;
+                account_headers(request, creds, None).header("X-Guest-Token", token)
+            }
+            None => {
+                let token = self.guest_token.get_token(self.refresh_token()).await?;
+                self.with_credentials(request, url)
+                    .header("X-Guest-Token", token)
+            }
+        }
+        .bearer_auth(&self.bearer_token);
+
+        let response = headed.send().await.map_err(RequestFailed)?;
+        self.cookie.set_cookies(
+            &mut response.headers().get_all(SET_COOKIE).iter(),
+            response.url(),
+        );
+        self.capture_headers(&response);
+        self.record_rate_limit(&response);
+        if let Some((index, _)) = picked {
+            self.tokens.report(index, &response);
+        }
+        Ok(response)
+    }
+}
+
+// the account's `auth_token` and `ct0` replace any in `jar`, the jar's other cookies go along
+fn account_headers(
+    request: RequestBuilder,
+    creds: &Credentials,
+    jar: Option<&str>,
+) -> RequestBuilder {
+    request
+        .header(COOKIE, account_cookie(creds, jar))
+        .header("x-csrf-token", &creds.csrf_token)
+        .header("x-twitter-auth-type", "OAuth2Session")
+}
+
+fn account_cookie(creds: &Credentials, jar: Option<&str>) -> String {
+    let mut cookie = format!("auth_token={}; ct0={}", creds.auth_token, creds.csrf_token);
+    let others = jar
+        .into_iter()
+        .flat_map(|jar| jar.split("; "))
+        .filter(|pair| {
+            !pair.is_empty() && !pair.starts_with("auth_token=") && !pair.starts_with("ct0=")
+        });
+    for pair in others {
+        cookie.push_str("; ");
+        cookie.push_str(pair);
+    }
+    cookie
 }
 
 #[test]
-fn make_scraper() {
+fn account_cookie_keeps_other_cookies() {
+    let creds = Credentials {
+        auth_token: "a".to_string(),
+        csrf_token: "c".to_string(),
+    };
+    assert_eq!(account_cookie(&creds, None), "auth_token=a; ct0=c");
+    assert_eq!(
+        account_cookie(
+            &creds,
+            Some("auth_token=old; guest_id=v1; ct0=old; lang=en")
+        ),
+        "auth_token=a; ct0=c; guest_id=v1; lang=en"
+    );
+}
+
+// runs `test` to completion on a fresh single threaded runtime
+#[cfg(test)]
+pub(crate) fn block_on<F: std::future::Future>(test: F) -> F::Output {
     tokio::runtime::Builder::new_current_thread()
         .enable_all()
         .build()
         .unwrap()
-        .block_on(async {
-            let _scraper = ScraperBuilder::new().finish().await.unwrap();
-            //println!("{:?}", scraper);
+        .block_on(test)
+}
+
+// runs `test` against a scraper answering with `canned`, see `ScraperBuilder::with_dry_run`
+#[cfg(test)]
+pub(crate) fn dry_run<T, Fut>(canned: Vec<String>, test: impl FnOnce(Scraper) -> Fut) -> T
+where
+    Fut: std::future::Future<Output = T>,
+{
+    block_on(async {
+        let scraper = ScraperBuilder::new()
+            .with_dry_run(canned)
+            .finish()
+            .await
+            .unwrap();
+        test(scraper).await
+    })
+}
+
+#[test]
+fn make_scraper() {
+    block_on(async {
+        let _scraper = ScraperBuilder::new().finish().await.unwrap();
+        //println!("{:?}", scraper);
+    });
+}
+
+#[test]
+fn account_clones_keep_their_own_cookies() {
+    block_on(async {
+        let mut cookies = HashMap::default();
+        cookies.insert("guest_id".to_string(), "v1%3A1".to_string());
+        let scraper = ScraperBuilder::new()
+            .with_cookies(cookies)
+            .with_dry_run(vec![])
+            .finish()
+            .await
+            .unwrap();
+        let account = scraper.clone_with_account(Credentials {
+            auth_token: "a".to_string(),
+            csrf_token: "c".to_string(),
         });
+        let url = TWITTER_URL.parse().unwrap();
+        assert!(scraper.cookie.cookies(&url).is_some());
+        assert!(account.cookie.cookies(&url).is_none());
+    });
+}
+
+#[test]
+fn retries_over_capacity() {
+    #[derive(serde::Deserialize)]
+    struct Answer {
+        ok: bool,
+    }
+
+    let canned = vec![
+        r#"{"errors":[{"message":"Over capacity","code":130}]}"#.to_string(),
+        r#"{"ok":true}"#.to_string(),
+    ];
+    dry_run(canned, |scraper| async move {
+        let answer = scraper
+            .api_req::<Answer>(scraper.make_get_req("https://twitter.com/i/api/graphql/x/Test"))
+            .await
+            .unwrap();
+        assert!(answer.ok);
+        assert_eq!(scraper.stop_dry_run().len(), 2);
+    });
+}
+
+#[test]
+fn validate_rejected_credentials() {
+    let canned =
+        vec![r#"{"errors":[{"message":"Could not authenticate you.","code":32}]}"#.to_string()];
+    dry_run(canned, |scraper| async move {
+        let validated = scraper.validate().await;
+        assert!(matches!(validated, Err(InvalidCredentials(_))));
+    });
+}
+
+#[test]
+fn builder_client_options() {
+    block_on(async {
+        let scraper = ScraperBuilder::new()
+            .with_ua("twtscrape-test/1.0".to_string())
+            .with_timeout(Duration::from_secs(30))
+            .with_proxy("http://127.0.0.1:3128".to_string())
+            .with_dry_run(vec![])
+            .finish()
+            .await;
+        assert!(scraper.is_ok());
+
+        let bad_proxy = ScraperBuilder::new()
+            .with_proxy("not a proxy".to_string())
+            .with_dry_run(vec![])
+            .finish()
+            .await;
+        assert!(matches!(bad_proxy, Err(InvalidProxy(_))));
+    });
 }
 
 #[derive(Debug, Clone)]
@@ -119,10 +842,27 @@ pub struct ScraperBuilder {
     bearer_token: String,
     delay: Option<u64>,
     variation: Option<u64>,
+    jitter: Option<Range<Duration>>,
+    rng_seed: Option<u64>,
     proxy: Option<String>,
     proxy_auth: Option<(String, String)>,
     user_agent: Option<String>,
+    timeout: Duration,
     cookie: Option<Arc<Jar>>,
+    credentials: Option<Credentials>,
+    referer: Option<String>,
+    origin: Option<String>,
+    resolve_websites: bool,
+    options: ScrapeOptions,
+    redirect_policy: RedirectPolicy,
+    not_found_statuses: Vec<StatusCode>,
+    capture_headers: bool,
+    max_ratelimit_wait: Duration,
+    retry_policy: RetryPolicy,
+    max_concurrency: Option<usize>,
+    thread_parallelism: usize,
+    cancellation: Option<CancellationToken>,
+    dry_run: Option<Vec<String>>,
 }
 impl ScraperBuilder {
     pub fn new() -> Self {
@@ -142,6 +882,18 @@ impl ScraperBuilder {
         self
     }
 
+    pub fn with_jitter(mut self, range: Range<Duration>) -> Self {
+        self.jitter = Some(range);
+        self
+    }
+
+    pub fn with_rng_seed(mut self, seed: u64) -> Self {
+        self.rng_seed = Some(seed);
+        self
+    }
+
+    // used for both http and https, e.g. `http://10.0.0.1:3128` or `socks5://...` if reqwest
+    // was built with socks support
     pub fn with_proxy(mut self, addr: String) -> Self {
         self.proxy = Some(addr);
         self
@@ -164,26 +916,138 @@ impl ScraperBuilder {
         self
     }
 
+    pub fn with_credentials(mut self, credentials: Credentials) -> Self {
+        self.credentials = Some(credentials);
+        self
+    }
+
     pub fn with_ua(mut self, ua: String) -> Self {
         self.user_agent = Some(ua);
         self
     }
 
+    // per request, covering connect to the end of the body. defaults to `DEFAULT_TIMEOUT`.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    // the graphql endpoints (UserByScreenName, UserTweetsAndReplies, TweetDetail) have been seen
+    // answering 403 without a plausible referer/origin, override these when using a mirror host
+    pub fn with_referer(mut self, referer: String) -> Self {
+        self.referer = Some(referer);
+        self
+    }
+
+    pub fn with_origin(mut self, origin: String) -> Self {
+        self.origin = Some(origin);
+        self
+    }
+
+    // off by default, `User::website` then holds the raw t.co link. turning it on follows the
+    // link for every parsed user, which doubles the requests of a user lookup. the hops it may
+    // take are limited by `with_redirect_policy`.
+    pub fn with_website_resolution(mut self, resolve: bool) -> Self {
+        self.resolve_websites = resolve;
+        self
+    }
+
+    // applies to every request, but only website resolution should ever see a redirect
+    pub fn with_redirect_policy(mut self, policy: RedirectPolicy) -> Self {
+        self.redirect_policy = policy;
+        self
+    }
+
+    pub fn with_options(mut self, options: ScrapeOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    // statuses that fail with `NotFound` right away instead of being retried, `api_req_opt` and
+    // the `_opt` lookups turn them into `None`. defaults to just 404.
+    pub fn with_not_found_statuses(mut self, statuses: Vec<StatusCode>) -> Self {
+        self.not_found_statuses = statuses;
+        self
+    }
+
+    pub fn with_captured_headers(mut self, capture: bool) -> Self {
+        self.capture_headers = capture;
+        self
+    }
+
+    // exhausted rate limit windows are slept out before the next request to that endpoint. a reset
+    // or `Retry-After` further out than `max` fails with `RateLimited { reset_at }` instead of
+    // sleeping that long.
+    pub fn with_retry_on_ratelimit_max_wait(mut self, max: Duration) -> Self {
+        self.max_ratelimit_wait = max;
+        self
+    }
+
+    // `RetryPolicy::never()` hands every failure straight back
+    pub fn with_retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry_policy = policy;
+        self
+    }
+
+    pub fn with_max_concurrency(mut self, max: usize) -> Self {
+        self.max_concurrency = Some(max.max(1));
+        self
+    }
+
+    // see `Scraper::thread_parallelism`, 1 crawls threads one after another
+    pub fn with_thread_parallelism(mut self, parallelism: usize) -> Self {
+        self.thread_parallelism = parallelism.max(1);
+        self
+    }
+
+    // see `Scraper::is_cancelled`
+    pub fn with_cancellation_token(mut self, token: CancellationToken) -> Self {
+        self.cancellation = Some(token);
+        self
+    }
+
+    // builds a scraper that starts out dry running, see `Scraper::dry_run`
+    pub fn with_dry_run(mut self, canned: Vec<String>) -> Self {
+        self.dry_run = Some(canned);
+        self
+    }
+
     #[tracing::instrument]
     pub async fn finish(self) -> Result<Scraper, TwtScrapeError> {
         let ScraperBuilder {
             bearer_token,
             delay,
             variation,
+            jitter,
+            rng_seed,
             proxy,
             proxy_auth,
             user_agent,
+            timeout,
             cookie,
+            credentials,
+            referer,
+            origin,
+            resolve_websites,
+            options,
+            redirect_policy,
+            not_found_statuses,
+            capture_headers,
+            max_ratelimit_wait,
+            retry_policy,
+            max_concurrency,
+            thread_parallelism,
+            cancellation,
+            dry_run,
         } = self;
 
+        let max_concurrency = max_concurrency.unwrap_or(DEFAULT_MAX_CONCURRENCY);
+
         let delayer = Delayer::new(
             delay.map(Duration::from_millis),
             variation.map(Duration::from_millis),
+            jitter,
+            rng_seed,
         );
 
         let jar = cookie.unwrap_or(Arc::new(Jar::default()));
@@ -193,11 +1057,11 @@ impl ScraperBuilder {
             client: {
                 let mut builder = Client::builder();
                 if let Some(ua) = user_agent {
-                    builder.user_agent(ua)
+                    builder = builder.user_agent(ua);
                 }
                 match proxy {
                     Some(proxy) => {
-                        let mut proxybld = Proxy::https(proxy).map_err(InvalidProxy)?;
+                        let mut proxybld = Proxy::all(proxy).map_err(InvalidProxy)?;
                         if let Some((user, password)) = proxy_auth {
                             proxybld = proxybld.basic_auth(&user, &password);
                         }
@@ -205,16 +1069,37 @@ impl ScraperBuilder {
                     }
                     None => builder,
                 }
-                .timeout(Duration::from_secs(10))
-                .cookie_store(true)
-                .cookie_provider(jar.clone())
+                .timeout(timeout)
+                .redirect(redirect_policy.to_policy())
                 .build()
                 .map_err(TwtScrapeError::ClientBuildError)?
             },
-            delayer,
-            guest_token: TimedToken::new(),
+            delayer: Arc::new(delayer),
+            guest_token: Arc::new(TimedToken::new()),
             cookie: jar,
+            credentials: Arc::new(RwLock::new(credentials)),
+            tokens: Arc::new(TokenPool::default()),
+            referer: referer.unwrap_or_else(|| DEFAULT_REFERER.to_string()),
+            origin: origin.unwrap_or_else(|| DEFAULT_ORIGIN.to_string()),
+            resolve_websites,
+            options,
+            not_found_statuses,
+            capture_headers,
+            last_headers: Mutex::new(None),
+            rate_limits: Mutex::new(HashMap::default()),
+            max_ratelimit_wait,
+            retry_policy,
+            max_concurrency,
+            thread_parallelism,
+            concurrency: Arc::new(Semaphore::new(max_concurrency)),
+            cancellation,
+            dry_run: Mutex::new(None),
         };
+        // a dry run never needs a guest token
+        if let Some(canned) = dry_run {
+            scpr.dry_run(canned);
+            return Ok(scpr);
+        }
         let token = scpr.refresh_token().await?;
         scpr.guest_token
             .init(Duration::from_secs(60 * 60 * 3), token)
@@ -229,10 +1114,27 @@ impl Default for ScraperBuilder {
             bearer_token: "AAAAAAAAAAAAAAAAAAAAAPYXBAAAAAAACLXUNDekMxqa8h%2F40K4moUkGsoc%3DTYfbDKbT3jJPCEVnMYqilB28NHfOPqkca3qaAxGfsyKCs0wRbw".into(),
             delay: None,
             cookie: None,
+            credentials: None,
             proxy: None,
             proxy_auth: None,
             variation: None,
-            user_agent: None
+            jitter: None,
+            rng_seed: None,
+            user_agent: None,
+            timeout: DEFAULT_TIMEOUT,
+            referer: None,
+            origin: None,
+            resolve_websites: false,
+            options: ScrapeOptions::default(),
+            redirect_policy: RedirectPolicy::default(),
+            not_found_statuses: vec![StatusCode::NOT_FOUND],
+            capture_headers: false,
+            max_ratelimit_wait: DEFAULT_MAX_RATELIMIT_WAIT,
+            retry_policy: RetryPolicy::default(),
+            max_concurrency: None,
+            thread_parallelism: DEFAULT_THREAD_PARALLELISM,
+            cancellation: None,
+            dry_run: None,
         }
     }
 }
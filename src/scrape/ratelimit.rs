@@ -0,0 +1,102 @@
+use chrono::{DateTime, TimeZone, Utc};
+use reqwest::header::HeaderMap;
+use reqwest::Url;
+use std::fmt::{Display, Formatter};
+use std::time::Duration;
+
+// the graphql operation (`UserByScreenName`, `TweetDetail`) or the last path segment of a rest url
+#[derive(Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Endpoint(pub String);
+
+impl Endpoint {
+    pub fn from_url(url: &Url) -> Self {
+        let operation = url
+            .path_segments()
+            .and_then(|mut segments| segments.next_back())
+            .unwrap_or_default();
+        Endpoint(operation.trim_end_matches(".json").to_string())
+    }
+}
+
+impl Display for Endpoint {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq)]
+pub struct RateLimitWindow {
+    pub limit: Option<u32>,
+    pub remaining: u32,
+    pub reset_at: DateTime<Utc>,
+}
+
+impl RateLimitWindow {
+    // `None` unless twitter sent both the remaining count and the reset time
+    pub fn from_headers(headers: &HeaderMap) -> Option<Self> {
+        let header = |name: &str| {
+            headers
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| value.parse::<i64>().ok())
+        };
+
+        let remaining = header("x-rate-limit-remaining")?;
+        let reset_at = reset_at(headers)?;
+
+        Some(RateLimitWindow {
+            limit: header("x-rate-limit-limit").and_then(|limit| u32::try_from(limit).ok()),
+            remaining: u32::try_from(remaining).unwrap_or(0),
+            reset_at,
+        })
+    }
+
+    // how long until the window opens up again, `None` while requests are still left
+    pub fn wait(&self) -> Option<Duration> {
+        if self.remaining > 0 {
+            return None;
+        }
+        (self.reset_at - Utc::now()).to_std().ok()
+    }
+}
+
+// the `x-rate-limit-reset` epoch, some 429s send it without the rest of the window
+pub(crate) fn reset_at(headers: &HeaderMap) -> Option<DateTime<Utc>> {
+    let reset = headers
+        .get("x-rate-limit-reset")?
+        .to_str()
+        .ok()?
+        .parse::<i64>()
+        .ok()?;
+    Utc.timestamp_opt(reset, 0).single()
+}
+
+#[test]
+fn window_from_headers() {
+    let mut headers = HeaderMap::new();
+    headers.insert("x-rate-limit-limit", "150".parse().unwrap());
+    headers.insert("x-rate-limit-remaining", "0".parse().unwrap());
+    headers.insert("x-rate-limit-reset", "1700000000".parse().unwrap());
+    let window = RateLimitWindow::from_headers(&headers).unwrap();
+    assert_eq!(window.limit, Some(150));
+    assert_eq!(window.remaining, 0);
+    assert_eq!(window.reset_at.timestamp(), 1700000000);
+    // long past, nothing to wait for
+    assert_eq!(window.wait(), None);
+
+    headers.remove("x-rate-limit-reset");
+    assert!(RateLimitWindow::from_headers(&headers).is_none());
+    assert_eq!(reset_at(&headers), None);
+
+    let mut headers = HeaderMap::new();
+    headers.insert("x-rate-limit-reset", "1700000000".parse().unwrap());
+    assert_eq!(
+        reset_at(&headers).map(|at| at.timestamp()),
+        Some(1700000000)
+    );
+
+    let url = Url::parse("https://twitter.com/i/api/graphql/G3KGOASz96M-Qu0nwmGXNg/UserByScreenName?variables=%7B%7D").unwrap();
+    assert_eq!(Endpoint::from_url(&url).0, "UserByScreenName");
+    let url = Url::parse("https://api.twitter.com/1.1/guest/activate.json").unwrap();
+    assert_eq!(Endpoint::from_url(&url).0, "activate");
+}
@@ -0,0 +1,97 @@
+use reqwest::redirect::Policy;
+use reqwest::Url;
+use std::net::{Ipv4Addr, Ipv6Addr};
+use url::Host;
+
+pub const DEFAULT_MAX_REDIRECTS: usize = 5;
+
+// what the client may follow, mostly there for the t.co links behind `User::website` which
+// point wherever the profile owner wants them to
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RedirectPolicy {
+    pub max_hops: usize,
+    // refuse anything that isn't http(s)
+    pub http_only: bool,
+    // refuse loopback, private and link local addresses. only literal ips and `localhost` are
+    // caught, a public name resolving to an internal address still gets through
+    pub block_internal: bool,
+}
+
+impl Default for RedirectPolicy {
+    fn default() -> Self {
+        RedirectPolicy {
+            max_hops: DEFAULT_MAX_REDIRECTS,
+            http_only: true,
+            block_internal: true,
+        }
+    }
+}
+
+impl RedirectPolicy {
+    pub fn allows(&self, url: &Url) -> bool {
+        if self.http_only && !matches!(url.scheme(), "http" | "https") {
+            return false;
+        }
+        !(self.block_internal && is_internal_host(url))
+    }
+
+    pub(crate) fn to_policy(&self) -> Policy {
+        let policy = self.clone();
+        Policy::custom(move |attempt| {
+            if attempt.previous().len() > policy.max_hops {
+                let error = format!("more than {} redirects", policy.max_hops);
+                attempt.error(error)
+            } else if !policy.allows(attempt.url()) {
+                let error = format!("redirect to {} blocked", attempt.url());
+                attempt.error(error)
+            } else {
+                attempt.follow()
+            }
+        })
+    }
+}
+
+fn is_internal_v4(ip: Ipv4Addr) -> bool {
+    ip.is_loopback()
+        || ip.is_private()
+        || ip.is_link_local()
+        || ip.is_unspecified()
+        || ip.is_broadcast()
+}
+
+fn is_internal_v6(ip: Ipv6Addr) -> bool {
+    if let Some(v4) = ip.to_ipv4_mapped() {
+        return is_internal_v4(v4);
+    }
+    let first = ip.segments()[0];
+    // unique local fc00::/7 and link local fe80::/10
+    ip.is_loopback() || ip.is_unspecified() || first & 0xfe00 == 0xfc00 || first & 0xffc0 == 0xfe80
+}
+
+fn is_internal_host(url: &Url) -> bool {
+    match url.host() {
+        Some(Host::Ipv4(ip)) => is_internal_v4(ip),
+        Some(Host::Ipv6(ip)) => is_internal_v6(ip),
+        Some(Host::Domain(domain)) => {
+            let domain = domain.trim_end_matches('.').to_ascii_lowercase();
+            domain == "localhost" || domain.ends_with(".localhost")
+        }
+        None => true,
+    }
+}
+
+#[test]
+fn blocks_internal_hosts() {
+    let policy = RedirectPolicy::default();
+    let allows = |url: &str| policy.allows(&url.parse().unwrap());
+    assert!(allows("https://example.com/"));
+    assert!(allows("http://93.184.216.34/"));
+    assert!(!allows("http://127.0.0.1:8080/"));
+    assert!(!allows("http://169.254.169.254/latest/meta-data"));
+    assert!(!allows("http://10.0.0.1/"));
+    assert!(!allows("http://[::1]/"));
+    assert!(!allows("http://[::ffff:192.168.0.1]/"));
+    assert!(!allows("http://LOCALHOST./"));
+    assert!(!allows("file:///etc/passwd"));
+    assert!(!allows("ftp://example.com/"));
+}
@@ -0,0 +1,147 @@
+use crate::error::SResult;
+use crate::error::TwtScrapeError::RateLimited;
+use chrono::{DateTime, Utc};
+use nanorand::{Rng, WyRand};
+use reqwest::header::{HeaderMap, RETRY_AFTER};
+use reqwest::StatusCode;
+use std::time::Duration;
+
+pub const DEFAULT_MAX_RETRIES: u32 = 3;
+
+// how GETs that failed with a 429, a 5xx or one of twitter's transient error codes are retried
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RetryPolicy {
+    pub max_retries: u32,
+    // doubled with every attempt
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+    // adds up to half the delay on top so parallel scrapers don't retry in lockstep
+    pub jitter: bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy {
+            max_retries: DEFAULT_MAX_RETRIES,
+            base_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(30),
+            jitter: true,
+        }
+    }
+}
+
+impl RetryPolicy {
+    pub fn never() -> Self {
+        RetryPolicy {
+            max_retries: 0,
+            ..Default::default()
+        }
+    }
+
+    // the wait before retry number `attempt`, counting from 1
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let delay = self
+            .base_delay
+            .saturating_mul(2_u32.saturating_pow(attempt.saturating_sub(1)))
+            .min(self.max_delay);
+        let half = delay.as_millis() as u64 / 2;
+        if !self.jitter || half == 0 {
+            return delay;
+        }
+        delay + Duration::from_millis(WyRand::new().generate_range(0..=half))
+    }
+
+    // twitter's `Retry-After` when it sent one, the backoff otherwise. a `Retry-After` further out
+    // than `max_wait` fails with `RateLimited` instead of sleeping that long.
+    pub(crate) fn retry_delay(
+        &self,
+        headers: &HeaderMap,
+        attempt: u32,
+        max_wait: Duration,
+    ) -> SResult<Duration> {
+        match retry_after(headers) {
+            Some(after) if after > max_wait => Err(RateLimited {
+                reset_at: chrono::Duration::from_std(after)
+                    .ok()
+                    .and_then(|after| Utc::now().checked_add_signed(after)),
+            }),
+            Some(after) => Ok(after),
+            None => Ok(self.backoff(attempt)),
+        }
+    }
+
+    pub fn retries_status(status: StatusCode) -> bool {
+        status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error()
+    }
+}
+
+// `Retry-After` is either a number of seconds or an http date
+pub(crate) fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    let value = headers.get(RETRY_AFTER)?.to_str().ok()?.trim();
+    if let Ok(secs) = value.parse::<u64>() {
+        return Some(Duration::from_secs(secs));
+    }
+    let at = DateTime::parse_from_rfc2822(value).ok()?;
+    (at.with_timezone(&Utc) - Utc::now()).to_std().ok()
+}
+
+#[test]
+fn backoff_doubles_up_to_max() {
+    let policy = RetryPolicy {
+        max_retries: 10,
+        base_delay: Duration::from_millis(500),
+        max_delay: Duration::from_secs(3),
+        jitter: false,
+    };
+    assert_eq!(policy.backoff(1), Duration::from_millis(500));
+    assert_eq!(policy.backoff(2), Duration::from_secs(1));
+    assert_eq!(policy.backoff(3), Duration::from_secs(2));
+    assert_eq!(policy.backoff(4), Duration::from_secs(3));
+    assert_eq!(policy.backoff(40), Duration::from_secs(3));
+
+    let jittered = RetryPolicy {
+        jitter: true,
+        ..policy
+    };
+    let delay = jittered.backoff(2);
+    assert!(delay >= Duration::from_secs(1) && delay <= Duration::from_millis(1500));
+
+    let mut headers = HeaderMap::new();
+    headers.insert(RETRY_AFTER, "120".parse().unwrap());
+    assert_eq!(retry_after(&headers), Some(Duration::from_secs(120)));
+    headers.insert(
+        RETRY_AFTER,
+        "Wed, 21 Oct 2015 07:28:00 GMT".parse().unwrap(),
+    );
+    assert_eq!(retry_after(&headers), None);
+}
+
+#[test]
+fn retry_after_past_max_wait_is_rate_limited() {
+    let policy = RetryPolicy {
+        max_retries: 10,
+        base_delay: Duration::from_millis(500),
+        max_delay: Duration::from_secs(3),
+        jitter: false,
+    };
+    let max_wait = Duration::from_secs(60);
+
+    let mut headers = HeaderMap::new();
+    assert_eq!(
+        policy.retry_delay(&headers, 2, max_wait).unwrap(),
+        Duration::from_secs(1)
+    );
+    // twitter's wait isn't capped by `max_delay`, only by `max_wait`
+    headers.insert(RETRY_AFTER, "30".parse().unwrap());
+    assert_eq!(
+        policy.retry_delay(&headers, 2, max_wait).unwrap(),
+        Duration::from_secs(30)
+    );
+    headers.insert(RETRY_AFTER, "3600".parse().unwrap());
+    match policy.retry_delay(&headers, 2, max_wait) {
+        Err(RateLimited {
+            reset_at: Some(reset_at),
+        }) => assert!(reset_at > Utc::now() + chrono::Duration::minutes(59)),
+        other => panic!("expected RateLimited, got {other:?}"),
+    }
+}
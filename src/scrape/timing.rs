@@ -1,5 +1,5 @@
 use nanorand::{Rng, WyRand};
-use std::env::var;
+use std::ops::Range;
 use std::sync::Arc;
 use std::{future::Future, time::Duration};
 use tokio::{
@@ -77,24 +77,56 @@ impl TimeToken {
 pub struct Delayer {
     delay: u64,
     variation: u64,
+    jitter: Option<Range<u64>>,
     last_invocation: Mutex<Instant>,
     rng: Arc<Mutex<WyRand>>,
 }
 impl Delayer {
-    pub fn new(delay: Option<Duration>, variation: Option<Duration>) -> Delayer {
+    pub fn new(
+        delay: Option<Duration>,
+        variation: Option<Duration>,
+        jitter: Option<Range<Duration>>,
+        seed: Option<u64>,
+    ) -> Delayer {
         let delay = delay.unwrap_or_default();
         let last_invocation = Mutex::new(Instant::now());
+        let rng = match seed {
+            Some(seed) => WyRand::new_seed(seed),
+            None => WyRand::new(),
+        };
 
         Delayer {
             delay: delay.as_millis() as u64,
             variation: variation.unwrap_or_default().as_millis() as u64,
+            jitter: jitter
+                .map(|range| range.start.as_millis() as u64..range.end.as_millis() as u64),
             last_invocation,
-            rng: Arc::new(Mutex::new(WyRand::new())),
+            rng: Arc::new(Mutex::new(rng)),
+        }
+    }
+
+    // separate from the delay, every request gets some jitter
+    async fn jitter(&self) {
+        if let Some(jitter) = self.next_jitter().await {
+            sleep(jitter).await;
+        }
+    }
+
+    // `None` without a jitter range. a seeded rng hands out the same jitter every run
+    async fn next_jitter(&self) -> Option<Duration> {
+        match &self.jitter {
+            Some(jitter) if !jitter.is_empty() => {
+                let mut rng = self.rng.lock().await;
+                Some(Duration::from_millis(rng.generate_range(jitter.clone())))
+            }
+            _ => None,
         }
     }
 
     #[tracing::instrument]
     pub async fn wait(&self) {
+        self.jitter().await;
+
         if self.delay == 0 {
             return;
         }
@@ -120,3 +152,27 @@ impl Delayer {
     //    sleep(self.delay - diff).await;
     //}
 }
+
+#[test]
+fn seeded_jitter_repeats() {
+    let jitter = Duration::from_millis(10)..Duration::from_millis(500);
+    let draws = |seed| {
+        let delayer = Delayer::new(None, None, Some(jitter.clone()), Some(seed));
+        super::block_on(async {
+            let mut draws = Vec::new();
+            for _ in 0..8 {
+                draws.push(delayer.next_jitter().await.unwrap());
+            }
+            draws
+        })
+    };
+
+    let first = draws(42);
+    assert_eq!(first, draws(42));
+    assert_ne!(first, draws(43));
+    assert!(first.iter().all(|draw| jitter.contains(draw)));
+
+    let none = Delayer::new(None, None, None, Some(42));
+    let none = super::block_on(none.next_jitter());
+    assert_eq!(none, None);
+}
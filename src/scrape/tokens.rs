@@ -0,0 +1,151 @@
+use super::retry::retry_after;
+use super::{Credentials, RateLimitWindow};
+use chrono::{DateTime, Utc};
+use reqwest::{Response, StatusCode};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+// a full rate limit window, for 429s that came without a reset time
+const DEFAULT_COOLDOWN_SECS: i64 = 15 * 60;
+
+// what a request can be sent as, see `Scraper::with_tokens`
+#[derive(Clone, PartialEq, Eq)]
+pub enum Token {
+    // an already activated guest token, sent as `X-Guest-Token`
+    Guest(String),
+    Account(Credentials),
+}
+
+// scrapers end up in tracing spans, keep the tokens out of the logs
+impl std::fmt::Debug for Token {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Token::Guest(_) => f.write_str("Guest(..)"),
+            Token::Account(creds) => f.debug_tuple("Account").field(creds).finish(),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct PooledToken {
+    token: Token,
+    // benched until then after a 429 or an exhausted window
+    cooldown_until: Mutex<Option<DateTime<Utc>>>,
+}
+
+impl PooledToken {
+    fn recovers_at(&self) -> Option<DateTime<Utc>> {
+        self.cooldown_until.lock().ok().and_then(|guard| *guard)
+    }
+
+    fn is_healthy(&self, now: DateTime<Utc>) -> bool {
+        self.recovers_at().map(|until| until <= now).unwrap_or(true)
+    }
+}
+
+// an empty pool leaves the scraper on its own guest token and credentials
+#[derive(Debug, Default)]
+pub(crate) struct TokenPool {
+    tokens: Vec<PooledToken>,
+    next: AtomicUsize,
+}
+
+impl TokenPool {
+    pub(crate) fn new(tokens: Vec<Token>) -> Self {
+        TokenPool {
+            tokens: tokens
+                .into_iter()
+                .map(|token| PooledToken {
+                    token,
+                    cooldown_until: Mutex::new(None),
+                })
+                .collect(),
+            next: AtomicUsize::new(0),
+        }
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.tokens.is_empty()
+    }
+
+    // round robin, skipping benched tokens. `None` when every token is benched.
+    pub(crate) fn next_healthy(&self) -> Option<(usize, Token)> {
+        let now = Utc::now();
+        let len = self.tokens.len();
+        let start = self.next.load(Ordering::Relaxed);
+        let index = (0..len)
+            .map(|offset| (start + offset) % len)
+            .find(|&index| self.tokens[index].is_healthy(now))?;
+        self.next.store(index + 1, Ordering::Relaxed);
+        Some((index, self.tokens[index].token.clone()))
+    }
+
+    pub(crate) fn has_healthy(&self) -> bool {
+        let now = Utc::now();
+        self.tokens.iter().any(|token| token.is_healthy(now))
+    }
+
+    // the soonest a benched token comes back
+    pub(crate) fn next_recovery(&self) -> Option<DateTime<Utc>> {
+        self.tokens
+            .iter()
+            .filter_map(PooledToken::recovers_at)
+            .min()
+    }
+
+    fn bench(&self, index: usize, until: DateTime<Utc>) {
+        if let Some(Ok(mut guard)) = self.tokens.get(index).map(|t| t.cooldown_until.lock()) {
+            *guard = Some(until);
+        }
+    }
+
+    // benches the token that sent `response` if it got a 429 or used up its window
+    pub(crate) fn report(&self, index: usize, response: &Response) {
+        let window = RateLimitWindow::from_headers(response.headers());
+        let exhausted = response.status() == StatusCode::TOO_MANY_REQUESTS
+            || window.map(|w| w.remaining == 0).unwrap_or(false);
+        if !exhausted {
+            return;
+        }
+        let until = window
+            .map(|w| w.reset_at)
+            .or_else(|| {
+                retry_after(response.headers())
+                    .and_then(|wait| chrono::Duration::from_std(wait).ok())
+                    .map(|wait| Utc::now() + wait)
+            })
+            .unwrap_or_else(|| Utc::now() + chrono::Duration::seconds(DEFAULT_COOLDOWN_SECS));
+        self.bench(index, until);
+    }
+}
+
+#[test]
+fn pool_rotates_past_benched_tokens() {
+    let pool = TokenPool::new(vec![
+        Token::Guest("a".to_string()),
+        Token::Guest("b".to_string()),
+        Token::Guest("c".to_string()),
+    ]);
+    let picked = (0..4)
+        .map(|_| pool.next_healthy().unwrap().0)
+        .collect::<Vec<_>>();
+    assert_eq!(picked, vec![0, 1, 2, 0]);
+
+    let reset_at = Utc::now() + chrono::Duration::seconds(60);
+    pool.bench(1, reset_at);
+    assert_eq!(pool.next_healthy().unwrap().0, 2);
+    assert_eq!(pool.next_healthy().unwrap().0, 0);
+    assert_eq!(pool.next_recovery(), Some(reset_at));
+
+    pool.bench(0, reset_at);
+    pool.bench(2, reset_at);
+    assert!(!pool.has_healthy());
+    assert!(pool.next_healthy().is_none());
+
+    // a reset in the past puts the token straight back
+    pool.bench(2, Utc::now() - chrono::Duration::seconds(1));
+    assert_eq!(
+        pool.next_healthy().unwrap().1,
+        Token::Guest("c".to_string())
+    );
+}
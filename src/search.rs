@@ -1,13 +1,26 @@
 use crate::error::SResult;
 use crate::error::TwtScrapeError::TwitterBadRestId;
 #[cfg(feature = "scrape")]
+use crate::graphql::GraphQlRequest;
+use crate::id::TweetId;
+#[cfg(feature = "scrape")]
+use crate::paginate::{HasCursor, Pager};
+#[cfg(feature = "scrape")]
 use crate::scrape::Scraper;
+#[cfg(feature = "scrape")]
+use crate::tweet::FilterCursorTweetRequest;
+use crate::tweet::{Tweet, TweetEnt, TweetResults, UserResults};
+use crate::user::{Error, TwtUsrResult, User};
+use crate::SetAsVec;
+use ahash::{HashSet, HashSetExt};
 use rkyv::Archive;
-use serde::de::{MapAccess, Visitor};
+use serde::de::{DeserializeSeed, IgnoredAny, MapAccess, Visitor};
 use serde::{de, Deserialize, Deserializer, Serialize};
 use std::fmt;
 use std::fmt::Display;
 #[cfg(feature = "scrape")]
+use tracing::warn;
+#[cfg(feature = "scrape")]
 use url::Url;
 
 #[cfg(feature = "scrape")]
@@ -32,6 +45,73 @@ pub fn twitter_request_url_search(
     url.to_string()
 }
 
+// the tabs of the search page
+#[derive(
+    Copy,
+    Clone,
+    Debug,
+    Hash,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+    Archive,
+    rkyv::Serialize,
+    rkyv::Deserialize,
+)]
+pub enum SearchProduct {
+    Top,
+    Latest,
+    People,
+    Media,
+}
+
+impl SearchProduct {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SearchProduct::Top => "Top",
+            SearchProduct::Latest => "Latest",
+            SearchProduct::People => "People",
+            SearchProduct::Media => "Media",
+        }
+    }
+}
+
+// the query goes through as typed, operators like `from:` and `since:` included
+#[cfg(feature = "scrape")]
+pub fn twitter_request_url_search_timeline(
+    query: &str,
+    product: SearchProduct,
+    cursor: Option<&str>,
+) -> String {
+    let request = GraphQlRequest::new("nK1dw4oV3k4w5TdtcAdSww", "SearchTimeline")
+        .variable("rawQuery", query)
+        .variable("count", 20)
+        .variable("querySource", "typed_query")
+        .variable("product", product.as_str())
+        .features([
+            ("responsive_web_graphql_exclude_directive_enabled", true),
+            ("verified_phone_label_enabled", false),
+            ("responsive_web_graphql_timeline_navigation_enabled", true),
+            ("tweetypie_unmention_optimization_enabled", true),
+            ("responsive_web_edit_tweet_api_enabled", true),
+            (
+                "graphql_is_translatable_rweb_tweet_is_translatable_enabled",
+                true,
+            ),
+            ("view_counts_everywhere_api_enabled", true),
+            ("longform_notetweets_consumption_enabled", true),
+            ("freedom_of_speech_not_reach_fetch_enabled", true),
+            ("standardized_nudges_misinfo", true),
+            ("longform_notetweets_rich_text_read_enabled", true),
+            ("responsive_web_enhance_cards_enabled", false),
+        ]);
+    match cursor {
+        Some(cursor) => request.variable("cursor", cursor).build(),
+        None => request.build(),
+    }
+}
+
 #[derive(
     Clone,
     Debug,
@@ -45,11 +125,12 @@ pub fn twitter_request_url_search(
     rkyv::Deserialize,
 )]
 pub struct Search {
-    pub tweets: Vec<u64>,
+    pub tweets: Vec<TweetId>,
 }
 
 #[cfg(feature = "scrape")]
 impl Search {
+    // on cancellation the ids of the pages fetched so far are returned
     #[tracing::instrument]
     pub async fn make_query(scraper: &Scraper, query: impl AsRef<str>) -> SResult<Self> {
         let first_request = scraper
@@ -66,7 +147,7 @@ impl Search {
             if let Instruction::AddEntry(add) = inst {
                 for entry in add.entries {
                     match entry.content {
-                        Entry::Item(item) => {
+                        EntryContent::Item(item) => {
                             if item.content.tweet.id.is_empty() || item.content.tweet.id == "0" {
                                 return Err(TwitterBadRestId(
                                     "Search Tweet RestID",
@@ -74,11 +155,11 @@ impl Search {
                                 ));
                             }
 
-                            tweets.push(item.content.tweet.id.parse::<u64>().map_err(|why| {
-                                TwitterBadRestId("Search Tweet RestID", why.to_string())
-                            })?);
+                            tweets.push(item.content.tweet.id.parse::<TweetId>().map_err(
+                                |why| TwitterBadRestId("Search Tweet RestID", why.to_string()),
+                            )?);
                         }
-                        Entry::Cursor(op) => {
+                        EntryContent::Cursor(op) => {
                             if entry.entry_id.starts_with("sq-cursor-bottom") {
                                 next_cursor = Some(op.cursor.value)
                             }
@@ -91,6 +172,10 @@ impl Search {
         if let Some(next) = next_cursor {
             let mut cursor_counter = next;
             loop {
+                // cancelled, the pages fetched so far are still returned
+                if scraper.is_cancelled() {
+                    break;
+                }
                 let mut request = scraper
                     .api_req::<SearchRequest>(scraper.make_get_req(twitter_request_url_search(
                         query.as_ref(),
@@ -102,7 +187,7 @@ impl Search {
                     match inst {
                         Instruction::AddEntry(add) => {
                             for entry in add.entries {
-                                if let Entry::Item(item) = entry {
+                                if let EntryContent::Item(item) = entry.content {
                                     if item.content.tweet.id.is_empty()
                                         || item.content.tweet.id == "0"
                                     {
@@ -112,7 +197,7 @@ impl Search {
                                         ));
                                     }
 
-                                    tweets.push(item.content.tweet.id.parse::<u64>().map_err(
+                                    tweets.push(item.content.tweet.id.parse::<TweetId>().map_err(
                                         |why| {
                                             TwitterBadRestId("Search Tweet RestID", why.to_string())
                                         },
@@ -122,7 +207,7 @@ impl Search {
                         }
                         Instruction::ReplaceEntry(replace) => {
                             if replace.entry_id_to_replace.starts_with("sq-cursor-bottom") {
-                                if let Entry::Cursor(op) = replace.entry {
+                                if let EntryContent::Cursor(op) = replace.entry.content {
                                     match op.cursor.value.strip_prefix("") {
                                         Some(new) => {
                                             cursor_counter = new.to_string();
@@ -142,6 +227,74 @@ impl Search {
 
         Ok(Self { tweets })
     }
+
+    // pages until twitter runs out of results. on cancellation the pages fetched so far are
+    // returned
+    #[tracing::instrument]
+    pub async fn query(
+        scraper: &Scraper,
+        query: &str,
+        product: SearchProduct,
+    ) -> SResult<SearchResults> {
+        let mut tweets = HashSet::with_capacity(20);
+        let mut users = HashSet::with_capacity(20);
+        let mut seen_users = HashSet::with_capacity(20);
+
+        let mut pager = Pager::new(scraper, FilterCursorTweetRequest::Bottom, None, |cursor| {
+            twitter_request_url_search_timeline(query, product, cursor)
+        });
+        while let Some(request) = pager.next_page::<SearchTimelineRequest>().await? {
+            for entry in request.into_entries() {
+                let user = match entry {
+                    SearchTimelineEntry::Tweet(tweet) => {
+                        let results = tweet.content.item_content.tweet_results;
+                        if results.rest_id_or_skip().is_none() {
+                            continue;
+                        }
+                        match Tweet::new_from_entry(&results) {
+                            Ok(tweet) => {
+                                tweets.insert(tweet);
+                            }
+                            Err(why) => warn!(error = %why, "Failed to get data. Skipping..."),
+                        }
+                        match results {
+                            TweetResults::Ok(trr) => trr.core.user_results.result,
+                            _ => continue,
+                        }
+                    }
+                    SearchTimelineEntry::User(user) => {
+                        user.content.item_content.user_results.result
+                    }
+                    SearchTimelineEntry::Cursor(_) | SearchTimelineEntry::Other(_) => continue,
+                };
+
+                if let TwtUsrResult::User(usr) = &user {
+                    if !seen_users.insert(usr.rest_id.clone()) {
+                        continue;
+                    }
+                }
+                match User::from_result(scraper, user).await {
+                    Ok(user) => {
+                        users.insert(user);
+                    }
+                    Err(why) => warn!(error = %why, "Failed to get data. Skipping..."),
+                }
+            }
+        }
+
+        Ok(SearchResults { tweets, users })
+    }
+}
+
+#[derive(
+    Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Archive, rkyv::Serialize, rkyv::Deserialize,
+)]
+pub struct SearchResults {
+    #[with(SetAsVec)]
+    pub tweets: HashSet<Tweet>,
+    // tweet authors, and the accounts found on the `People` tab
+    #[with(SetAsVec)]
+    pub users: HashSet<User>,
 }
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
@@ -176,7 +329,13 @@ pub(crate) struct AddEntry {
 }
 
 #[derive(Clone, Debug, PartialEq, Serialize)]
-pub(crate) enum Entry {
+pub(crate) struct Entry {
+    pub entry_id: String,
+    pub content: EntryContent,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub(crate) enum EntryContent {
     Item(Item),
     Cursor(Operation),
 }
@@ -188,8 +347,9 @@ impl<'de> Deserialize<'de> for Entry {
     {
         enum Field {
             EntryId,
-            SortIndex,
             Content,
+            // `sortIndex` and whatever else twitter sends along
+            Ignored,
         }
 
         impl<'de> Deserialize<'de> for Field {
@@ -212,12 +372,8 @@ impl<'de> Deserialize<'de> for Entry {
                     {
                         match value {
                             "entryId" => Ok(Field::EntryId),
-                            "sortIndex" => Ok(Field::SortIndex),
                             "content" => Ok(Field::Content),
-                            _ => Err(de::Error::unknown_field(
-                                value,
-                                &["entryId", "sortIndex", "content"],
-                            )),
+                            _ => Ok(Field::Ignored),
                         }
                     }
                 }
@@ -226,6 +382,44 @@ impl<'de> Deserialize<'de> for Entry {
             }
         }
 
+        // `content` wraps the item or cursor in one more object
+        #[derive(Deserialize)]
+        struct ItemWrapper {
+            item: Item,
+        }
+
+        #[derive(Deserialize)]
+        struct OperationWrapper {
+            operation: Operation,
+        }
+
+        // the id says what the content is
+        fn from_content<'de, D>(id: &str, content: D) -> Result<EntryContent, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            Ok(if id.starts_with("sq-I") {
+                EntryContent::Item(ItemWrapper::deserialize(content)?.item)
+            } else if id.starts_with("sq-cursor") {
+                EntryContent::Cursor(OperationWrapper::deserialize(content)?.operation)
+            } else {
+                return Err(de::Error::unknown_variant(id, VARIANTS));
+            })
+        }
+
+        struct ContentSeed<'a>(&'a str);
+
+        impl<'de> DeserializeSeed<'de> for ContentSeed<'_> {
+            type Value = EntryContent;
+
+            fn deserialize<D>(self, deserializer: D) -> Result<EntryContent, D::Error>
+            where
+                D: Deserializer<'de>,
+            {
+                from_content(self.0, deserializer)
+            }
+        }
+
         struct EntryVisitor;
 
         impl<'de> Visitor<'de> for EntryVisitor {
@@ -240,35 +434,37 @@ impl<'de> Deserialize<'de> for Entry {
                 V: MapAccess<'de>,
             {
                 let mut entry_id: Option<String> = None;
-                let mut sort_index: Option<String> = None;
+                let mut content: Option<EntryContent> = None;
+                // content that showed up before the id, parsed once the id is known
+                let mut buffered: Option<serde_json::Value> = None;
                 while let Some(key) = map.next_key()? {
                     match key {
                         Field::EntryId => {
                             entry_id = Some(map.next_value()?);
                         }
-                        Field::SortIndex => {
-                            sort_index = Some(map.next_value()?);
-                        }
-                        Field::Content => {
-                            if let Some(entry) = &entry_id {
-                                if entry.starts_with("sq-I") {
-                                    Ok(Entry::Item(map.next_value()?))
-                                } else if entry.starts_with("sq-cursor") {
-                                    Ok(Entry::Cursor(map.next_value()?))
-                                } else {
-                                    Err(de::Error::unknown_variant(entry, &["sq-I", "sq-cursor"]))
-                                }
-                            }
-                            Err(de::Error::unknown_variant("None", &["sq-I", "sq-cursor"]))
+                        Field::Content => match entry_id.as_deref() {
+                            Some(id) => content = Some(map.next_value_seed(ContentSeed(id))?),
+                            None => buffered = Some(map.next_value()?),
+                        },
+                        Field::Ignored => {
+                            map.next_value::<IgnoredAny>()?;
                         }
                     }
                 }
-                Err(de::Error::missing_field("content"))
+                let entry_id = entry_id.ok_or_else(|| de::Error::missing_field("entryId"))?;
+                let content = match (content, buffered) {
+                    (Some(content), _) => content,
+                    (None, Some(buffered)) => {
+                        from_content(&entry_id, buffered).map_err(de::Error::custom)?
+                    }
+                    (None, None) => return Err(de::Error::missing_field("content")),
+                };
+                Ok(Entry { entry_id, content })
             }
         }
 
-        const VARIANTS: &[&str] = &["Item", "Cursor"];
-        deserializer.deserialize_enum("Entry", VARIANTS, EntryVisitor)
+        const VARIANTS: &[&str] = &["sq-I", "sq-cursor"];
+        deserializer.deserialize_map(EntryVisitor)
     }
 }
 
@@ -296,3 +492,212 @@ pub(crate) struct ItemContent {
 pub(crate) struct SearchTweet {
     pub id: String,
 }
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub(crate) struct SearchTimelineRequest {
+    #[serde(default)]
+    pub errors: Vec<Error>,
+    pub data: SearchTimelineData,
+}
+
+impl SearchTimelineRequest {
+    fn instructions(&self) -> &[SearchInstruction] {
+        &self
+            .data
+            .search_by_raw_query
+            .search_timeline
+            .timeline
+            .instructions
+    }
+
+    // the first page adds the cursor, later ones replace the previous one in place
+    pub(crate) fn bottom_cursor(&self) -> Option<&str> {
+        self.instructions()
+            .iter()
+            .flat_map(|inst| match inst {
+                SearchInstruction::TimelineAddEntries(add) => add.entries.iter().collect(),
+                SearchInstruction::TimelineReplaceEntry(replace) => vec![&replace.entry],
+                SearchInstruction::Other => vec![],
+            })
+            .find_map(|entry| match entry {
+                SearchTimelineEntry::Cursor(cursor) if cursor.content.cursor_type == "Bottom" => {
+                    Some(cursor.content.value.as_str())
+                }
+                _ => None,
+            })
+    }
+
+    pub(crate) fn into_entries(self) -> Vec<SearchTimelineEntry> {
+        self.data
+            .search_by_raw_query
+            .search_timeline
+            .timeline
+            .instructions
+            .into_iter()
+            .flat_map(|inst| match inst {
+                SearchInstruction::TimelineAddEntries(add) => add.entries,
+                _ => vec![],
+            })
+            .collect()
+    }
+}
+
+#[cfg(feature = "scrape")]
+crate::impl_filter_json!(SearchTimelineRequest);
+
+#[cfg(feature = "scrape")]
+impl HasCursor for SearchTimelineRequest {
+    // search only scrolls down
+    fn filter_cursor(&self, filter: FilterCursorTweetRequest) -> Option<&str> {
+        match filter {
+            FilterCursorTweetRequest::Top => None,
+            FilterCursorTweetRequest::Bottom => self.bottom_cursor(),
+        }
+    }
+
+    fn entry_count(&self) -> usize {
+        self.instructions()
+            .iter()
+            .flat_map(|inst| match inst {
+                SearchInstruction::TimelineAddEntries(add) => add.entries.as_slice(),
+                _ => &[],
+            })
+            .filter(|entry| {
+                matches!(
+                    entry,
+                    SearchTimelineEntry::Tweet(_) | SearchTimelineEntry::User(_)
+                )
+            })
+            .count()
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub(crate) struct SearchTimelineData {
+    pub search_by_raw_query: SearchByRawQuery,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub(crate) struct SearchByRawQuery {
+    pub search_timeline: SearchTimelineWrapper,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub(crate) struct SearchTimelineWrapper {
+    pub timeline: SearchTimeline,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub(crate) struct SearchTimeline {
+    pub instructions: Vec<SearchInstruction>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub(crate) enum SearchInstruction {
+    TimelineAddEntries(SearchAddEntries),
+    TimelineReplaceEntry(SearchReplaceEntry),
+    #[serde(other)]
+    Other,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub(crate) struct SearchAddEntries {
+    pub entries: Vec<SearchTimelineEntry>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub(crate) struct SearchReplaceEntry {
+    pub entry_id_to_replace: String,
+    pub entry: SearchTimelineEntry,
+}
+
+// told apart by shape rather than by `entryId`, whatever else twitter slots in (modules, prompts)
+// ends up in `Other`
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub(crate) enum SearchTimelineEntry {
+    Tweet(SearchTweetEntry),
+    User(SearchUserEntry),
+    Cursor(SearchCursorEntry),
+    #[serde(skip_serializing)]
+    Other(IgnoredAny),
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub(crate) struct SearchTweetEntry {
+    pub content: TweetEnt,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub(crate) struct SearchUserEntry {
+    pub content: SearchUserContent,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct SearchUserContent {
+    pub item_content: SearchUserItemContent,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub(crate) struct SearchUserItemContent {
+    pub user_results: UserResults,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub(crate) struct SearchCursorEntry {
+    pub content: SearchCursor,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct SearchCursor {
+    pub value: String,
+    pub cursor_type: String,
+}
+
+#[cfg(feature = "scrape")]
+#[test]
+fn search_timeline_cursors() {
+    let first = serde_json::from_str::<SearchTimelineRequest>(
+        r#"{"data":{"search_by_raw_query":{"search_timeline":{"timeline":{"instructions":[{"type":"TimelineAddEntries","entries":[{"entryId":"toptabsrpusermodule-1714580000000","sortIndex":"1714580000000","content":{"entryType":"TimelineTimelineModule","__typename":"TimelineTimelineModule","items":[],"displayType":"Carousel"}},{"entryId":"cursor-top-1714580000000","sortIndex":"1714580000000","content":{"entryType":"TimelineTimelineCursor","__typename":"TimelineTimelineCursor","value":"DAADDAABCgABGMVw","cursorType":"Top"}},{"entryId":"cursor-bottom-0","sortIndex":"0","content":{"entryType":"TimelineTimelineCursor","__typename":"TimelineTimelineCursor","value":"DAADDAABCgABGMVwBottom","cursorType":"Bottom"}}]}]}}}}}"#,
+    )
+    .unwrap();
+    assert_eq!(first.bottom_cursor(), Some("DAADDAABCgABGMVwBottom"));
+    assert!(first.into_entries().iter().all(|entry| !matches!(
+        entry,
+        SearchTimelineEntry::Tweet(_) | SearchTimelineEntry::User(_)
+    )));
+
+    let next = serde_json::from_str::<SearchTimelineRequest>(
+        r#"{"data":{"search_by_raw_query":{"search_timeline":{"timeline":{"instructions":[{"type":"TimelineAddEntries","entries":[]},{"type":"TimelineReplaceEntry","entry_id_to_replace":"cursor-bottom-0","entry":{"entryId":"cursor-bottom-0","sortIndex":"0","content":{"entryType":"TimelineTimelineCursor","__typename":"TimelineTimelineCursor","value":"DAADDAABCgABGMVwNext","cursorType":"Bottom"}}}]}}}}}"#,
+    )
+    .unwrap();
+    assert_eq!(next.bottom_cursor(), Some("DAADDAABCgABGMVwNext"));
+    assert_eq!(
+        twitter_request_url_search_timeline("from:jack since:2020-01-01", SearchProduct::Latest, None)
+            .split("&features=")
+            .next(),
+        Some("https://twitter.com/i/api/graphql/nK1dw4oV3k4w5TdtcAdSww/SearchTimeline?variables=%7B%22count%22%3A20%2C%22product%22%3A%22Latest%22%2C%22querySource%22%3A%22typed_query%22%2C%22rawQuery%22%3A%22from%3Ajack%20since%3A2020-01-01%22%7D")
+    );
+}
+
+#[cfg(feature = "scrape")]
+#[test]
+fn adaptive_entries_by_id() {
+    let add = serde_json::from_str::<AddEntry>(
+        r#"{"entries":[{"entryId":"sq-I-t-1212092628029698048","sortIndex":"999","content":{"item":{"content":{"tweet":{"id":"1212092628029698048","displayType":"Tweet"}},"clientEventInfo":{"component":"result"}}}},{"entryId":"sq-cursor-bottom","sortIndex":"0","content":{"operation":{"cursor":{"value":"scroll:thGAVUV0VFVBY","cursorType":"Bottom"}}}}]}"#,
+    )
+    .unwrap();
+    match &add.entries[0].content {
+        EntryContent::Item(item) => assert_eq!(item.content.tweet.id, "1212092628029698048"),
+        other => panic!("expected an item, got {other:?}"),
+    }
+    assert_eq!(add.entries[1].entry_id, "sq-cursor-bottom");
+    match &add.entries[1].content {
+        EntryContent::Cursor(op) => assert_eq!(op.cursor.value, "scroll:thGAVUV0VFVBY"),
+        other => panic!("expected a cursor, got {other:?}"),
+    }
+    assert!(serde_json::from_str::<Entry>(r#"{"entryId":"sq-M-1","content":{}}"#).is_err());
+}
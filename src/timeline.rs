@@ -1,7 +1,8 @@
 use rkyv::Archive;
 use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
 
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
 #[derive(Debug, Deserialize)]
 pub struct GlobalTimeline {
@@ -98,6 +99,7 @@ pub struct VideoVariant {
     rkyv::Serialize,
     rkyv::Deserialize,
 )]
+#[cfg_attr(feature = "validation", archive(check_bytes))]
 pub struct Place {
     pub id: String,
     pub place_type: String,
@@ -109,19 +111,145 @@ pub struct Place {
 }
 
 #[derive(
+    Clone, Debug, PartialEq, Serialize, Deserialize, Archive, rkyv::Serialize, rkyv::Deserialize,
+)]
+#[cfg_attr(feature = "validation", archive(check_bytes))]
+pub struct BoundingBox {
+    #[serde(alias = "type")]
+    pub box_type: String,
+    // `[longitude, latitude]` corners. twitter nests them in a polygon ring, which is dropped
+    #[serde(with = "polygon_ring")]
+    pub coordinates: Vec<[f64; 2]>,
+}
+
+// twitter never sends NaN coordinates
+impl Eq for BoundingBox {}
+
+impl Hash for BoundingBox {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.box_type.hash(state);
+        for [longitude, latitude] in &self.coordinates {
+            longitude.to_bits().hash(state);
+            latitude.to_bits().hash(state);
+        }
+    }
+}
+
+mod polygon_ring {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(
+        corners: &[[f64; 2]],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        [corners].serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Vec<[f64; 2]>, D::Error> {
+        let rings = Vec::<Vec<[f64; 2]>>::deserialize(deserializer)?;
+        Ok(rings.into_iter().flatten().collect())
+    }
+}
+
+// the exact spot a tweet was sent from, only there when the author shared their location
+#[derive(
+    Copy,
     Clone,
     Debug,
-    Hash,
     PartialEq,
-    Eq,
     Serialize,
     Deserialize,
     Archive,
     rkyv::Serialize,
     rkyv::Deserialize,
 )]
-pub struct BoundingBox {
-    #[serde(alias = "type")]
-    pub box_type: String,
-    pub coordinates: Vec<Vec<Vec<f64>>>,
+#[cfg_attr(feature = "validation", archive(check_bytes))]
+pub struct GeoPoint {
+    pub latitude: f64,
+    pub longitude: f64,
+}
+
+impl Eq for GeoPoint {}
+
+impl Hash for GeoPoint {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.latitude.to_bits().hash(state);
+        self.longitude.to_bits().hash(state);
+    }
+}
+
+impl GeoPoint {
+    // twitter sends the point twice, `coordinates` as geojson (`[longitude, latitude]`) and the
+    // deprecated `geo` the other way around
+    pub(crate) fn from_legacy(
+        coordinates: Option<&TwtGeoPoint>,
+        geo: Option<&TwtGeoPoint>,
+    ) -> Option<Self> {
+        match (coordinates, geo) {
+            (Some(point), _) => Some(GeoPoint {
+                latitude: point.coordinates[1],
+                longitude: point.coordinates[0],
+            }),
+            (None, Some(point)) => Some(GeoPoint {
+                latitude: point.coordinates[0],
+                longitude: point.coordinates[1],
+            }),
+            (None, None) => None,
+        }
+    }
+}
+
+#[derive(
+    Clone, Debug, PartialEq, Serialize, Deserialize, Archive, rkyv::Serialize, rkyv::Deserialize,
+)]
+pub(crate) struct TwtGeoPoint {
+    #[serde(rename = "type")]
+    pub point_type: String,
+    pub coordinates: [f64; 2],
+}
+
+impl Eq for TwtGeoPoint {}
+
+impl Hash for TwtGeoPoint {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.point_type.hash(state);
+        self.coordinates[0].to_bits().hash(state);
+        self.coordinates[1].to_bits().hash(state);
+    }
+}
+
+#[cfg(feature = "scrape")]
+#[test]
+fn geotagged_tweet_fixture() {
+    let place = serde_json::from_str::<Place>(
+        r#"{"id":"01fbe706f872cb32","url":"https://api.twitter.com/1.1/geo/id/01fbe706f872cb32.json","place_type":"city","name":"Washington","full_name":"Washington, DC","country_code":"US","country":"United States","contained_within":[],"bounding_box":{"type":"Polygon","coordinates":[[[-77.119401,38.801826],[-76.909396,38.801826],[-76.909396,38.9953797],[-77.119401,38.9953797]]]},"attributes":{}}"#,
+    )
+    .unwrap();
+    assert_eq!(place.full_name, "Washington, DC");
+    assert_eq!(place.country, "United States");
+    assert_eq!(place.place_type, "city");
+    assert_eq!(place.bounding_box.coordinates.len(), 4);
+    assert_eq!(place.bounding_box.coordinates[0], [-77.119401, 38.801826]);
+
+    let json = serde_json::to_value(&place.bounding_box).unwrap();
+    assert_eq!(json["coordinates"][0][2][1], 38.9953797);
+
+    let coordinates =
+        serde_json::from_str::<TwtGeoPoint>(r#"{"type":"Point","coordinates":[-77.0364,38.8951]}"#)
+            .unwrap();
+    let geo =
+        serde_json::from_str::<TwtGeoPoint>(r#"{"type":"Point","coordinates":[38.8951,-77.0364]}"#)
+            .unwrap();
+    let point = GeoPoint {
+        latitude: 38.8951,
+        longitude: -77.0364,
+    };
+    assert_eq!(
+        GeoPoint::from_legacy(Some(&coordinates), Some(&geo)),
+        Some(point)
+    );
+    assert_eq!(GeoPoint::from_legacy(None, Some(&geo)), Some(point));
+    assert_eq!(GeoPoint::from_legacy(None, None), None);
 }
@@ -1,27 +1,41 @@
+#[cfg(feature = "scrape")]
+use crate::error::ScrapeOutcome;
 use crate::error::TwtScrapeError::IdParseError;
-use crate::timeline::Place;
+use crate::id::{TweetId, UserId};
+#[cfg(feature = "scrape")]
+use crate::paginate::{paginate, HasCursor, Pager};
+use crate::timeline::{GeoPoint, Place, TwtGeoPoint};
 use crate::{
     error::{
         SResult,
-        TwtScrapeError::{BadJSONSchema, TwitterBadRestId, TwitterBadTimeParse},
+        TwtScrapeError::{
+            BadJSONSchema, Io, NotTranslatable, TweetUnavailable, TwitterBadRestId,
+            TwitterBadTimeParse,
+        },
     },
     scrape::Scraper,
+    twitter_url_segments,
     user::{Error, TwtUsrResult, User},
-    TwitterIdType,
+    SetAsVec, TwitterIdType,
 };
 use ahash::{HashSet, HashSetExt};
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, TimeZone, Utc};
+#[cfg(feature = "scrape")]
+use futures::{future, stream, StreamExt};
 use rkyv::Archive;
 #[cfg(feature = "scrape")]
 use scraper::{Html, Selector};
 use serde::{
-    de::{self, MapAccess, Visitor},
+    de::{self, DeserializeSeed, MapAccess, Visitor},
     Deserialize, Deserializer, Serialize,
 };
 use std::hash::Hasher;
+#[cfg(feature = "scrape")]
+use std::path::{Path, PathBuf};
 use std::{
     collections::{HashMap, VecDeque},
-    fmt::{self, Display, Write},
+    fmt::{self, Debug, Display},
+    future::Future,
 };
 use tracing::warn;
 
@@ -45,22 +59,71 @@ pub fn twitter_request_url_thread(
     }
 }
 
+#[cfg(feature = "scrape")]
+pub fn twitter_request_url_tweet(id: impl TwitterIdType + Display) -> String {
+    format!("https://twitter.com/i/api/graphql/DJS3BdhUhcaEpZ7B7irJDg/TweetResultByRestId?variables=%7B%22tweetId%22%3A%22{id}%22%2C%22withCommunity%22%3Afalse%2C%22includePromotedContent%22%3Afalse%2C%22withVoice%22%3Afalse%7D&features=%7B%22responsive_web_twitter_blue_verified_badge_is_enabled%22%3Atrue%2C%22verified_phone_label_enabled%22%3Afalse%2C%22responsive_web_graphql_timeline_navigation_enabled%22%3Atrue%2C%22tweetypie_unmention_optimization_enabled%22%3Atrue%2C%22vibe_api_enabled%22%3Atrue%2C%22responsive_web_edit_tweet_api_enabled%22%3Atrue%2C%22graphql_is_translatable_rweb_tweet_is_translatable_enabled%22%3Atrue%2C%22standardized_nudges_misinfo%22%3Atrue%2C%22tweet_with_visibility_results_prefer_gql_limited_actions_policy_enabled%22%3Afalse%2C%22interactive_text_enabled%22%3Atrue%2C%22responsive_web_text_conversations_enabled%22%3Afalse%2C%22responsive_web_enhance_cards_enabled%22%3Atrue%7D")
+}
+
+#[cfg(feature = "scrape")]
+pub const TWEET_BATCH_SIZE: usize = 100;
+
+// how many quotes deep `parse_thread` follows a quote chain by default
+pub const DEFAULT_MAX_QUOTE_DEPTH: usize = 5;
+
+#[cfg(feature = "scrape")]
+pub fn twitter_request_url_tweet_ids(ids: &[TweetId]) -> String {
+    let ids = ids
+        .iter()
+        .map(|id| format!("%22{id}%22"))
+        .collect::<Vec<String>>()
+        .join("%2C");
+    format!("https://twitter.com/i/api/graphql/BWy5aoI-WvwbeSiHUIf2Hw/TweetResultsByRestIds?variables=%7B%22tweetIds%22%3A%5B{ids}%5D%2C%22withCommunity%22%3Afalse%2C%22includePromotedContent%22%3Afalse%2C%22withVoice%22%3Afalse%7D&features=%7B%22responsive_web_twitter_blue_verified_badge_is_enabled%22%3Atrue%2C%22verified_phone_label_enabled%22%3Afalse%2C%22responsive_web_graphql_timeline_navigation_enabled%22%3Atrue%2C%22tweetypie_unmention_optimization_enabled%22%3Atrue%2C%22vibe_api_enabled%22%3Atrue%2C%22responsive_web_edit_tweet_api_enabled%22%3Atrue%2C%22graphql_is_translatable_rweb_tweet_is_translatable_enabled%22%3Atrue%2C%22standardized_nudges_misinfo%22%3Atrue%2C%22tweet_with_visibility_results_prefer_gql_limited_actions_policy_enabled%22%3Afalse%2C%22interactive_text_enabled%22%3Atrue%2C%22responsive_web_text_conversations_enabled%22%3Afalse%2C%22responsive_web_enhance_cards_enabled%22%3Atrue%7D")
+}
+
+#[cfg(feature = "scrape")]
+pub fn twitter_request_url_translate(
+    id: impl TwitterIdType + Display,
+    target_lang: impl AsRef<str>,
+) -> String {
+    let lang = urlencoding::encode(target_lang.as_ref());
+    format!("https://twitter.com/i/api/1.1/strato/column/None/tweetId={id},destinationLanguage=Some({lang}),translationSource=Some(Google),feature=None,timeout=None,onlyCached=None/translation/service/translateTweet")
+}
+
 #[derive(
     Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Archive, rkyv::Serialize, rkyv::Deserialize,
 )]
+#[cfg_attr(feature = "validation", archive(check_bytes))]
 pub struct Tweet {
-    pub id: u64,
-    pub conversation_id: u64,
-    pub posted_id: Option<u64>,
+    pub id: TweetId,
+    pub conversation_id: TweetId,
+    pub posted_id: Option<UserId>,
     pub tweet_type: TweetType,
 }
 
+// a page or more of replies under one tweet, see `Tweet::replies`
+#[derive(
+    Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Archive, rkyv::Serialize, rkyv::Deserialize,
+)]
+pub struct Replies {
+    // replies anywhere in the tree under the tweet, not only the direct ones
+    #[with(SetAsVec)]
+    pub tweets: HashSet<Tweet>,
+    // stopped at `max_replies` or cancelled with more pages left
+    pub more_remaining: bool,
+}
+
 #[cfg(feature = "scrape")]
 impl Tweet {
+    // on cancellation scrolling up or down the conversation stops, leaving out the replies
+    // and parents that weren't fetched yet.
+    // quoted tweets are fetched too, up to `max_quote_depth` quotes deep (`None` for
+    // `DEFAULT_MAX_QUOTE_DEPTH`). anything deeper is only there as `ReplyInfo::quoting`. the
+    // authors of quoted tweets are not added to the users.
     #[tracing::instrument]
     pub async fn parse_thread(
         scraper: &Scraper,
         id: impl TwitterIdType + Display,
+        max_quote_depth: Option<usize>,
     ) -> SResult<(Vec<Tweet>, Vec<User>)> {
         let base_request = scraper
             .api_req::<TweetRequest>(scraper.make_get_req(twitter_request_url_thread(&id, None)))
@@ -76,7 +139,7 @@ impl Tweet {
             .ok_or(BadJSONSchema("TweetRequest", "No First Tweet".to_string()))?
             .legacy
             .conversation_id_str
-            .parse::<u64>()
+            .parse::<TweetId>()
             .map_err(|why| TwitterBadRestId("Conversation RestID", why.to_string()))?;
 
         if let Some(cursor) = base_request.filter_cursor(FilterCursorTweetRequest::Top) {
@@ -128,7 +191,7 @@ impl Tweet {
                                         .entry_id
                                         .split("-")
                                         .nth(1)
-                                        .map(|x| x.parse::<u64>().ok())
+                                        .map(|x| x.parse::<TweetId>().ok())
                                         .flatten()
                                         .ok_or(IdParseError(format!("Graveyard Tweet No Entry ID")))
                                     {
@@ -148,6 +211,24 @@ impl Tweet {
                                 tweets.push(tweet);
 
                                 if let TweetResults::Ok(trr) = twt.item_content.tweet_results {
+                                    if let Some(original @ TwtUsrResult::User(usr)) =
+                                        trr.retweeted_author()
+                                    {
+                                        if !already_parsed_users.contains(&usr.rest_id) {
+                                            match User::from_result(scraper, original.clone()).await
+                                            {
+                                                Ok(user) => {
+                                                    already_parsed_users
+                                                        .insert(user.id.to_string());
+                                                    users.push(user)
+                                                }
+                                                Err(why) => warn!(
+                                                    error = %why,
+                                                    "Failed to get retweeted author. Skipping..."
+                                                ),
+                                            }
+                                        }
+                                    }
                                     if let TwtUsrResult::User(user) = &trr.core.user_results.result
                                     {
                                         if already_parsed_users.contains(&user.id) {
@@ -177,6 +258,25 @@ impl Tweet {
                                     if let TweetResults::Ok(trr) =
                                         thread.item.item_content.tweet_results
                                     {
+                                        if let Some(original @ TwtUsrResult::User(usr)) =
+                                            trr.retweeted_author()
+                                        {
+                                            if !already_parsed_users.contains(&usr.rest_id) {
+                                                match User::from_result(scraper, original.clone())
+                                                    .await
+                                                {
+                                                    Ok(user) => {
+                                                        already_parsed_users
+                                                            .insert(user.id.to_string());
+                                                        users.push(user)
+                                                    }
+                                                    Err(why) => warn!(
+                                                        error = %why,
+                                                        "Failed to get retweeted author. Skipping..."
+                                                    ),
+                                                }
+                                            }
+                                        }
                                         if let TwtUsrResult::User(usr) =
                                             &trr.core.user_results.result
                                         {
@@ -202,12 +302,83 @@ impl Tweet {
             }
         }
 
+        let mut seen = tweets
+            .iter()
+            .map(|tweet| tweet.id)
+            .collect::<HashSet<TweetId>>();
+        let quoted = resolve_quotes(
+            tweets.iter().filter_map(Tweet::quoting).collect(),
+            &mut seen,
+            max_quote_depth.unwrap_or(DEFAULT_MAX_QUOTE_DEPTH),
+            |quoted_id| async move {
+                if scraper.is_cancelled() {
+                    return Ok(None);
+                }
+                Tweet::by_id_opt(scraper, quoted_id).await
+            },
+            Tweet::quoting,
+        )
+        .await;
+        tweets.extend(quoted);
+
         tweets.shrink_to_fit();
         users.shrink_to_fit();
 
         Ok((tweets, users))
     }
 
+    // follows the bottom and "show more replies" cursors of `TweetDetail` until the tree runs
+    // out or `max_replies` were collected. unlike `parse_thread` the parents of the tweet, the
+    // tweet itself and the authors are left out. deleted and withheld replies are skipped.
+    #[tracing::instrument]
+    pub async fn replies(
+        scraper: &Scraper,
+        tweet_id: TweetId,
+        max_replies: Option<usize>,
+    ) -> SResult<Replies> {
+        let max_replies = max_replies.unwrap_or(usize::MAX);
+        let mut tweets = HashSet::with_capacity(max_replies.min(100));
+
+        let mut pager = Pager::new(scraper, FilterCursorTweetRequest::Bottom, None, |cursor| {
+            twitter_request_url_thread(tweet_id.to_string(), cursor)
+        });
+        let mut truncated = false;
+        while let Some(request) = pager.next_page::<TweetRequest>().await? {
+            for results in request.tweet_results() {
+                if results.rest_id_or_skip().is_none() {
+                    continue;
+                }
+                let reply = match Tweet::new_from_entry(results) {
+                    Ok(reply) => reply,
+                    Err(why) => {
+                        warn!(error = %why, "Failed to parse reply. Skipping...");
+                        continue;
+                    }
+                };
+                // ids only grow, anything up to the tweet is the tweet or one of its parents
+                if reply.id <= tweet_id {
+                    continue;
+                }
+                if tweets.len() >= max_replies {
+                    truncated = true;
+                    break;
+                }
+                tweets.insert(reply);
+            }
+
+            if tweets.len() >= max_replies {
+                break;
+            }
+        }
+        // stopped at `max_replies` or cancelled before the last page
+        let more_remaining = truncated || !pager.is_done();
+
+        Ok(Replies {
+            tweets,
+            more_remaining,
+        })
+    }
+
     /// HEY FUTURE ASS MF!!!
     /// MAKE SURE YOU SET THE `conversation_id` AFTERWARDS!!!!!
     pub(crate) fn new_from_entry(t: &TweetResults) -> SResult<Self> {
@@ -219,7 +390,7 @@ impl Tweet {
 
                 let id = trr
                     .rest_id
-                    .parse::<u64>()
+                    .parse::<TweetId>()
                     .map_err(|why| TwitterBadRestId("Tweet RestID", why.to_string()))?;
 
                 if trr.legacy.conversation_id_str.is_empty()
@@ -234,13 +405,13 @@ impl Tweet {
                 let conversation_id = trr
                     .legacy
                     .conversation_id_str
-                    .parse::<u64>()
+                    .parse::<TweetId>()
                     .map_err(|why| TwitterBadRestId("Conversation RestID", why.to_string()))?;
 
                 let user_id = trr
                     .legacy
                     .user_id_str
-                    .parse::<u64>()
+                    .parse::<UserId>()
                     .map_err(|why| TwitterBadRestId("User RestID", why.to_string()))?;
 
                 let created = DateTime::<Utc>::from(
@@ -248,36 +419,18 @@ impl Tweet {
                         .map_err(|why| TwitterBadTimeParse(why.to_string()))?,
                 );
 
-                let edit_ids = trr
-                    .edit_control
-                    .edit_tweet_ids
-                    .into_iter()
-                    .map(|id| {
-                        id.parse::<u64>()
-                            .map_err(|why| TwitterBadRestId("Tweet RestID", why.to_string()))
-                    })
-                    .collect::<SResult<Vec<u64>>>()?;
+                let edit_control = trr.edit_control.resolved();
+                let edit_ids = edit_control.edit_ids()?;
+                let editable_until = edit_control.editable_until();
+                let edits_remaining = edit_control.edits_remaining();
+                let edit_history = edit_control.history(id)?;
 
                 let media = trr
                     .legacy
                     .extended_entities
                     .media
                     .into_iter()
-                    .map(|x| {
-                        let media_id = x
-                            .id_str
-                            .parse::<u64>()
-                            .map_err(|why| TwitterBadRestId("Tweet RestID", why.to_string()))?;
-                        Ok(Media {
-                            id: media_id,
-                            media_key: x.media_key,
-                            media_url_https: x.media_url_https,
-                            r#type: x.r#type,
-                            ext_alt_text: x.ext_alt_text,
-                            views: x.media_stats.map(|x| x.view_count),
-                            expanded_url: x.expanded_url,
-                        })
-                    })
+                    .map(Media::from_entry)
                     .collect::<SResult<Vec<Media>>>()?;
 
                 let urls = trr
@@ -296,17 +449,8 @@ impl Tweet {
                     .map(|ht| ht.text)
                     .collect::<Vec<String>>();
 
-                let card = trr.card.map(|tcd| Card {
-                    id: tcd.rest_id,
-                    url: tcd.legacy.url,
-                    name: tcd.legacy.name,
-                    values: tcd
-                        .legacy
-                        .binding_values
-                        .into_iter()
-                        .map(|bv| (bv.key, bv.value))
-                        .collect::<HashMap<String, CardValue, ahash::RandomState>>(),
-                });
+                let card = trr.card.as_ref().cloned().map(Card::from);
+                let poll = card.as_ref().and_then(Poll::from_card);
 
                 let display_text_range = {
                     if trr.legacy.display_text_range.len() != 2 {
@@ -325,7 +469,7 @@ impl Tweet {
                             if idstr.is_empty() || idstr == "0" {
                                 None
                             } else {
-                                Some(idstr.parse::<u64>().map_err(|why| {
+                                Some(idstr.parse::<TweetId>().map_err(|why| {
                                     TwitterBadRestId("Reply Tweet ID", why.to_string())
                                 })?)
                             }
@@ -334,6 +478,21 @@ impl Tweet {
                     }
                 };
 
+                let replying_to_user = {
+                    match &trr.legacy.in_reply_to_user_id_str {
+                        Some(idstr) => {
+                            if idstr.is_empty() || idstr == "0" {
+                                None
+                            } else {
+                                Some(idstr.parse::<UserId>().map_err(|why| {
+                                    TwitterBadRestId("Reply User ID", why.to_string())
+                                })?)
+                            }
+                        }
+                        None => None,
+                    }
+                };
+
                 let quoting = {
                     if !trr.legacy.is_quote_status {
                         None
@@ -343,7 +502,7 @@ impl Tweet {
                                 if qrtid.is_empty() || qrtid == "0" {
                                     None
                                 } else {
-                                    Some(qrtid.parse::<u64>().map_err(|why| {
+                                    Some(qrtid.parse::<TweetId>().map_err(|why| {
                                         TwitterBadRestId("Quote Tweet ID", why.to_string())
                                     })?)
                                 }
@@ -353,14 +512,23 @@ impl Tweet {
                     }
                 };
 
-                let source = {
-                    let frag = Html::parse_fragment(&trr.legacy.source);
-                    frag.select(&LINK_SELECTOR)
-                        .next()
-                        .map(|elem| elem.inner_html())
-                        .unwrap_or_default()
+                let (retweeting, retweeting_user) = match trr
+                    .legacy
+                    .retweeted_status_result
+                    .as_deref()
+                    .map(|retweeted| &retweeted.result)
+                {
+                    Some(TweetResults::Ok(original)) => (
+                        Some(original.rest_id.parse::<TweetId>().map_err(|why| {
+                            TwitterBadRestId("Retweeted Tweet ID", why.to_string())
+                        })?),
+                        original.legacy.user_id_str.parse::<UserId>().ok(),
+                    ),
+                    _ => (None, None),
                 };
 
+                let source = TweetSource::parse(&trr.legacy.source);
+
                 let collaborator_ctrl = trr
                     .legacy
                     .collab_control
@@ -368,7 +536,7 @@ impl Tweet {
                         x.collaborators_results
                             .into_iter()
                             .map(|x| match x.result {
-                                TwtUsrResult::User(u) => u.rest_id.parse::<u64>().ok(),
+                                TwtUsrResult::User(u) => u.rest_id.parse::<UserId>().ok(),
                                 TwtUsrResult::UserUnavailable(_) => None,
                             })
                             .collect::<Option<Vec<_>>>()
@@ -382,6 +550,9 @@ impl Tweet {
                     tweet_type: TweetType::Tweet(Box::new(TweetData {
                         created,
                         edit_ids,
+                        editable_until,
+                        edits_remaining,
+                        edit_history,
                         entry: Entries {
                             media,
                             mentions: trr.legacy.entities.user_mentions.clone(),
@@ -389,6 +560,7 @@ impl Tweet {
                             hashtags,
                         },
                         card,
+                        poll,
                         text: trr.legacy.full_text.clone(),
                         source,
                         display_text_range,
@@ -397,31 +569,542 @@ impl Tweet {
                             retweets: trr.legacy.retweet_count,
                             likes: trr.legacy.favourite_count,
                             replies: trr.legacy.reply_count,
+                            bookmarks: trr.legacy.bookmark_count,
                         },
                         reply_info: ReplyInfo {
                             replying_to,
+                            replying_to_user,
                             quoting,
+                            retweeting,
+                            retweeting_user,
                         },
                         moderated: false,
-                        conversation_control: ConversationControl::None,
+                        reply_restriction: trr
+                            .legacy
+                            .conversation_control
+                            .as_ref()
+                            .map(ReplyRestriction::from),
                         vibe: trr.vibe.map(|v| Vibe {
                             discovery_query_text: v.discovery_query_text,
                             text: v.text,
                             img_description: v.img_description,
                         }),
                         place: trr.legacy.place.clone(),
+                        geo: GeoPoint::from_legacy(
+                            trr.legacy.coordinates.as_ref(),
+                            trr.legacy.geo.as_ref(),
+                        ),
                         collaborators: collaborator_ctrl,
+                        is_translatable: trr.is_translatable,
+                        bookmarked: trr.legacy.bookmarked,
+                        withheld_scope: trr
+                            .legacy
+                            .withheld
+                            .withheld_scope
+                            .as_deref()
+                            .map(WithheldScope::from),
+                        withheld_copyright: trr.legacy.withheld.withheld_copyright,
+                        withheld_text: trr.legacy.withheld.withheld_text.clone(),
+                        withheld_in_countries: trr.legacy.withheld.withheld_in_countries.clone(),
+                        article: trr.article.as_ref().and_then(TwtArticle::to_article),
                     })),
                 })
             }
             TweetResults::Tombstone(tomb) => Ok(Tweet {
-                id: 0,
-                conversation_id: 0,
+                id: TweetId(0),
+                conversation_id: TweetId(0),
                 posted_id: None,
                 tweet_type: TweetType::Tombstone(tomb.tombstone.text.text.clone()),
             }),
         }
     }
+
+    // only looks at the typename, the tweet itself is never parsed
+    #[tracing::instrument]
+    pub async fn exists(
+        scraper: &Scraper,
+        id: impl TwitterIdType + Display + Debug,
+    ) -> SResult<bool> {
+        let request = scraper
+            .api_req::<TweetExistsRequest>(scraper.make_get_req(twitter_request_url_tweet(id)))
+            .await?;
+
+        Ok(match request.data.tweet_result.and_then(|tr| tr.result) {
+            Some(result) => matches!(
+                result.__typename.as_str(),
+                "Tweet" | "TweetWithVisibilityResults"
+            ),
+            None => false,
+        })
+    }
+
+    // just the one tweet, none of its thread. deleted, protected and otherwise hidden tweets fail
+    // with `TweetUnavailable` saying why instead of coming back empty.
+    #[tracing::instrument]
+    pub async fn get(scraper: &Scraper, tweet_id: &str) -> SResult<Tweet> {
+        let id = tweet_id.parse::<TweetId>()?;
+        match scraper
+            .api_req_opt::<TweetByIdRequest>(scraper.make_get_req(twitter_request_url_tweet(id)))
+            .await?
+        {
+            Some(request) => request.data.into_tweet(id),
+            None => Err(TweetUnavailable {
+                id,
+                reason: TombstoneReason::Deleted,
+            }),
+        }
+    }
+
+    // `None` for deleted or unavailable tweets, whether twitter answers with a 404 or a tombstone
+    #[tracing::instrument]
+    pub async fn by_id_opt(
+        scraper: &Scraper,
+        id: impl TwitterIdType + Display + Debug,
+    ) -> SResult<Option<Tweet>> {
+        let request = match scraper
+            .api_req_opt::<TweetByIdRequest>(scraper.make_get_req(twitter_request_url_tweet(id)))
+            .await?
+        {
+            Some(req) => req,
+            None => return Ok(None),
+        };
+
+        match request.data.tweet_result.and_then(|tr| tr.result) {
+            Some(result @ TweetResults::Ok(_)) => Ok(Some(Tweet::new_from_entry(&result)?)),
+            Some(TweetResults::Tombstone(_)) | None => Ok(None),
+        }
+    }
+
+    // chunks are fetched concurrently, a failed chunk ends up in the errors with its ids instead
+    // of failing the batch. once cancelled no new chunks are started, the ones already sent are
+    // still collected.
+    #[tracing::instrument(skip(ids))]
+    pub async fn by_ids(scraper: &Scraper, ids: &[TweetId]) -> SResult<ScrapeOutcome<Vec<Tweet>>> {
+        let chunk_requests = stream::iter(ids.chunks(TWEET_BATCH_SIZE))
+            .take_while(|_| future::ready(!scraper.is_cancelled()))
+            .map(|chunk| async move {
+                let request = scraper
+                    .api_req::<TweetsByIdsRequest>(
+                        scraper.make_get_req(twitter_request_url_tweet_ids(chunk)),
+                    )
+                    .await;
+                (chunk, request)
+            })
+            .buffer_unordered(scraper.max_concurrency())
+            .collect::<Vec<_>>()
+            .await;
+
+        let mut tweets = Vec::with_capacity(ids.len());
+
+        let mut errors = Vec::new();
+
+        for (chunk, request) in chunk_requests {
+            let request = match request {
+                Ok(req) => req,
+                Err(why) => {
+                    warn!(
+                        error = %why,
+                        chunk_len = chunk.len(),
+                        "Failed to get tweet chunk. Skipping..."
+                    );
+                    let ids = chunk.iter().map(ToString::to_string).collect::<Vec<_>>();
+                    errors.push((format!("tweets {}", ids.join(",")), why));
+                    continue;
+                }
+            };
+
+            for result in request
+                .data
+                .tweet_result
+                .into_iter()
+                .flat_map(|tr| tr.result)
+            {
+                match Tweet::new_from_entry(&result) {
+                    // tombstones carry no id, nothing to hand back
+                    Ok(tweet) if tweet.id == 0 => continue,
+                    Ok(tweet) => tweets.push(tweet),
+                    Err(why) => {
+                        warn!(error = %why, "Failed to parse tweet. Skipping...")
+                    }
+                }
+            }
+        }
+
+        tweets.shrink_to_fit();
+        Ok(ScrapeOutcome {
+            data: tweets,
+            errors,
+        })
+    }
+
+    #[tracing::instrument]
+    pub async fn translate(
+        &self,
+        scraper: &Scraper,
+        target_lang: impl AsRef<str> + Debug,
+    ) -> SResult<Translation> {
+        match &self.tweet_type {
+            TweetType::Tweet(data) if data.is_translatable => {}
+            _ => return Err(NotTranslatable(self.id)),
+        }
+
+        let translated = scraper
+            .api_req::<TranslationResponse>(
+                scraper.make_get_req(twitter_request_url_translate(self.id, target_lang.as_ref())),
+            )
+            .await?;
+
+        if translated.translation_state != "Success" {
+            return Err(BadJSONSchema(
+                "TranslationResponse",
+                translated.translation_state,
+            ));
+        }
+
+        Ok(Translation {
+            text: translated.translation,
+            source_language: translated.source_language,
+            destination_language: translated.destination_language,
+        })
+    }
+
+    // saves every photo, video and gif of the tweet into `dir` as `<media id>.<ext>`, photos at
+    // their original size and videos at the highest bitrate. a failed download is logged and
+    // skipped, the paths of the files written are returned.
+    #[tracing::instrument(skip(self))]
+    pub async fn download_all_media(
+        &self,
+        scraper: &Scraper,
+        dir: impl AsRef<Path> + Debug,
+    ) -> SResult<Vec<PathBuf>> {
+        tokio::fs::create_dir_all(dir.as_ref()).await.map_err(Io)?;
+
+        let mut written = Vec::with_capacity(self.media_count());
+        for media in self.media() {
+            let url = match media.download_url() {
+                Some(url) => url,
+                None => {
+                    warn!(media = media.id, "No downloadable variant. Skipping...");
+                    continue;
+                }
+            };
+            let dest = dir
+                .as_ref()
+                .join(format!("{}.{}", media.id, media.extension()));
+            match scraper.download_media(&url, &dest).await {
+                Ok(_) => written.push(dest),
+                Err(why) => warn!(error = %why, url, "Failed to download media. Skipping..."),
+            }
+        }
+        Ok(written)
+    }
+}
+
+// follows quote chains one level at a time, the tweets quoted by `quoting` being the first.
+// ids already in `seen` are never fetched again, which also ends quote cycles.
+#[cfg(feature = "scrape")]
+pub(crate) async fn resolve_quotes<I, T, Fut>(
+    mut quoting: Vec<I>,
+    seen: &mut HashSet<I>,
+    max_depth: usize,
+    mut fetch: impl FnMut(I) -> Fut,
+    quoted_id: impl Fn(&T) -> Option<I>,
+) -> Vec<T>
+where
+    I: Copy + Eq + std::hash::Hash + Display,
+    Fut: Future<Output = SResult<Option<T>>>,
+{
+    let mut resolved = Vec::new();
+    for _ in 0..max_depth {
+        let mut next = Vec::new();
+        for id in quoting {
+            if !seen.insert(id) {
+                continue;
+            }
+            match fetch(id).await {
+                Ok(Some(tweet)) => {
+                    next.extend(quoted_id(&tweet));
+                    resolved.push(tweet);
+                }
+                Ok(None) => {}
+                Err(why) => warn!(error = %why, %id, "Failed to get quoted tweet. Skipping..."),
+            }
+        }
+        if next.is_empty() {
+            break;
+        }
+        quoting = next;
+    }
+    resolved
+}
+
+#[cfg(feature = "scrape")]
+#[test]
+fn quote_chain_stops_at_max_depth() {
+    // tweet `n` quotes tweet `n + 1`, the thread itself is tweet 0
+    let fetched = std::cell::RefCell::new(Vec::new());
+    let mut seen = HashSet::new();
+    seen.insert(0);
+    let resolved = crate::scrape::block_on(resolve_quotes(
+        vec![1],
+        &mut seen,
+        DEFAULT_MAX_QUOTE_DEPTH,
+        |id| {
+            fetched.borrow_mut().push(id);
+            future::ready(Ok(Some((id, (id < 10).then(|| id + 1)))))
+        },
+        |&(_, quoting): &(u64, Option<u64>)| quoting,
+    ));
+    assert_eq!(*fetched.borrow(), vec![1, 2, 3, 4, 5]);
+    // the deepest one fetched still points at the next, unfetched quote
+    assert_eq!(resolved.last(), Some(&(5, Some(6))));
+}
+
+// `/<handle>/status/<id>`, `/i/web/status/<id>`, anything after the id (`/photo/1`, `?s=20`) is ignored
+pub fn tweet_id_from_url(url: &str) -> Option<TweetId> {
+    let segments = twitter_url_segments(url)?;
+    let status = segments
+        .iter()
+        .position(|segment| *segment == "status" || *segment == "statuses")?;
+    segments.get(status + 1)?.parse().ok()
+}
+
+#[test]
+fn tweet_id_from_status_url() {
+    assert_eq!(
+        tweet_id_from_url("https://twitter.com/jack/status/20"),
+        Some(TweetId(20))
+    );
+    assert_eq!(
+        tweet_id_from_url("https://x.com/jack/status/20/photo/1?s=20"),
+        Some(TweetId(20))
+    );
+    assert_eq!(
+        tweet_id_from_url("twitter.com/i/web/status/20/"),
+        Some(TweetId(20))
+    );
+    assert_eq!(tweet_id_from_url("https://twitter.com/jack"), None);
+}
+
+impl Tweet {
+    // for retweets this is the original author, see `retweeter_id`
+    pub fn author_id(&self) -> Option<UserId> {
+        match &self.tweet_type {
+            TweetType::Tweet(data) if data.reply_info.retweeting.is_some() => {
+                data.reply_info.retweeting_user.or(self.posted_id)
+            }
+            _ => self.posted_id,
+        }
+    }
+
+    // the account that retweeted, `None` unless this is a retweet
+    pub fn retweeter_id(&self) -> Option<UserId> {
+        match &self.tweet_type {
+            TweetType::Tweet(data) if data.reply_info.retweeting.is_some() => self.posted_id,
+            _ => None,
+        }
+    }
+
+    pub fn is_retweet(&self) -> bool {
+        self.retweeter_id().is_some()
+    }
+
+    // tombstones carry no media
+    pub fn media(&self) -> &[Media] {
+        match &self.tweet_type {
+            TweetType::Tweet(data) => &data.entry.media,
+            TweetType::Tombstone(_) => &[],
+        }
+    }
+
+    pub fn bookmark_count(&self) -> Option<u64> {
+        match &self.tweet_type {
+            TweetType::Tweet(data) => data.stats.bookmarks,
+            TweetType::Tombstone(_) => None,
+        }
+    }
+
+    pub fn poll(&self) -> Option<&Poll> {
+        match &self.tweet_type {
+            TweetType::Tweet(data) => data.poll.as_ref(),
+            TweetType::Tombstone(_) => None,
+        }
+    }
+
+    pub fn place(&self) -> Option<&Place> {
+        match &self.tweet_type {
+            TweetType::Tweet(data) => data.place.as_ref(),
+            TweetType::Tombstone(_) => None,
+        }
+    }
+
+    pub fn geo(&self) -> Option<GeoPoint> {
+        match &self.tweet_type {
+            TweetType::Tweet(data) => data.geo,
+            TweetType::Tombstone(_) => None,
+        }
+    }
+
+    pub fn bookmarked(&self) -> Option<bool> {
+        match &self.tweet_type {
+            TweetType::Tweet(data) => data.bookmarked,
+            TweetType::Tombstone(_) => None,
+        }
+    }
+
+    pub fn withheld_scope(&self) -> Option<&WithheldScope> {
+        match &self.tweet_type {
+            TweetType::Tweet(data) => data.withheld_scope.as_ref(),
+            TweetType::Tombstone(_) => None,
+        }
+    }
+
+    pub fn source(&self) -> Option<&TweetSource> {
+        match &self.tweet_type {
+            TweetType::Tweet(data) => data.source.as_ref(),
+            TweetType::Tombstone(_) => None,
+        }
+    }
+
+    // empty for tombstones, twitter doesn't say where a tombstoned tweet was withheld
+    pub fn withheld_in_countries(&self) -> &[String] {
+        match &self.tweet_type {
+            TweetType::Tweet(data) => &data.withheld_in_countries,
+            TweetType::Tombstone(_) => &[],
+        }
+    }
+
+    pub fn article(&self) -> Option<&Article> {
+        match &self.tweet_type {
+            TweetType::Tweet(data) => data.article.as_ref(),
+            TweetType::Tombstone(_) => None,
+        }
+    }
+
+    pub fn media_count(&self) -> usize {
+        self.media().len()
+    }
+
+    pub fn has_media(&self) -> bool {
+        !self.media().is_empty()
+    }
+
+    // gifs are served as looping mp4s, so they count as video
+    pub fn has_video(&self) -> bool {
+        self.media().iter().any(|media| {
+            matches!(
+                media.kind,
+                MediaKind::Video { .. } | MediaKind::AnimatedGif { .. }
+            )
+        })
+    }
+
+    pub fn has_photos(&self) -> bool {
+        self.media()
+            .iter()
+            .any(|media| matches!(media.kind, MediaKind::Photo { .. }))
+    }
+
+    // includes the leading @handles of a reply, twitter lists those as regular mentions
+    pub fn mentions(&self) -> &[TweetUserMentions] {
+        match &self.tweet_type {
+            TweetType::Tweet(data) => &data.entry.mentions,
+            TweetType::Tombstone(_) => &[],
+        }
+    }
+
+    pub fn quoting(&self) -> Option<TweetId> {
+        match &self.tweet_type {
+            TweetType::Tweet(data) => data.reply_info.quoting,
+            TweetType::Tombstone(_) => None,
+        }
+    }
+
+    pub fn replying_to(&self) -> Option<TweetId> {
+        match &self.tweet_type {
+            TweetType::Tweet(data) => data.reply_info.replying_to,
+            TweetType::Tombstone(_) => None,
+        }
+    }
+
+    // the author answering their own tweet, which is how threads are strung together
+    pub fn replies_to_self(&self) -> bool {
+        match &self.tweet_type {
+            TweetType::Tweet(data) => {
+                self.posted_id.is_some() && data.reply_info.replying_to_user == self.posted_id
+            }
+            TweetType::Tombstone(_) => false,
+        }
+    }
+
+    // how often the tweet was edited, the version we have is always the latest
+    pub fn edit_count(&self) -> usize {
+        match &self.tweet_type {
+            TweetType::Tweet(data) => data.edit_ids.len().saturating_sub(1),
+            TweetType::Tombstone(_) => 0,
+        }
+    }
+
+    pub fn is_edited(&self) -> bool {
+        self.edit_count() > 0
+    }
+
+    pub fn edit_history(&self) -> Option<&EditHistory> {
+        match &self.tweet_type {
+            TweetType::Tweet(data) => data.edit_history.as_ref(),
+            TweetType::Tombstone(_) => None,
+        }
+    }
+
+    pub fn edits_remaining(&self) -> Option<u32> {
+        match &self.tweet_type {
+            TweetType::Tweet(data) => data.edits_remaining,
+            TweetType::Tombstone(_) => None,
+        }
+    }
+
+    pub fn editable_until(&self) -> Option<DateTime<Utc>> {
+        match &self.tweet_type {
+            TweetType::Tweet(data) => data.editable_until,
+            TweetType::Tombstone(_) => None,
+        }
+    }
+
+    pub fn tombstone_reason(&self) -> Option<TombstoneReason> {
+        match &self.tweet_type {
+            TweetType::Tweet(_) => None,
+            TweetType::Tombstone(text) => Some(TombstoneReason::from(text.as_str())),
+        }
+    }
+
+    // replies + retweets + likes + quote tweets. bookmarks and views are left out, twitter
+    // doesn't report them for every tweet. tombstones count as 0.
+    pub fn engagement_total(&self) -> u64 {
+        match &self.tweet_type {
+            TweetType::Tweet(data) => {
+                let stats = &data.stats;
+                u64::from(stats.replies)
+                    + u64::from(stats.retweets)
+                    + u64::from(stats.likes)
+                    + u64::from(stats.quote_tweets)
+            }
+            TweetType::Tombstone(_) => 0,
+        }
+    }
+
+    pub fn mentioned_user_ids(&self) -> Vec<UserId> {
+        self.mentions()
+            .iter()
+            .filter_map(|mention| mention.id_str.parse().ok())
+            .collect()
+    }
+
+    pub fn mentioned_handles(&self) -> Vec<&str> {
+        self.mentions()
+            .iter()
+            .map(|mention| mention.screen_name.as_str())
+            .collect()
+    }
 }
 
 impl std::hash::Hash for Tweet {
@@ -448,6 +1131,7 @@ impl std::hash::Hash for Tweet {
     rkyv::Serialize,
     rkyv::Deserialize,
 )]
+#[cfg_attr(feature = "validation", archive(check_bytes))]
 #[serde(tag = "t", content = "c")]
 pub enum TweetType {
     Tombstone(String),
@@ -466,21 +1150,42 @@ pub enum TweetType {
     rkyv::Serialize,
     rkyv::Deserialize,
 )]
+#[cfg_attr(feature = "validation", archive(check_bytes))]
 pub struct TweetData {
     pub created: DateTime<Utc>,
-    pub edit_ids: Vec<u64>,
+    // every version of the tweet, the original included
+    pub edit_ids: Vec<TweetId>,
+    pub editable_until: Option<DateTime<Utc>>,
+    pub edits_remaining: Option<u32>,
+    // `None` unless the tweet is part of an edit chain
+    pub edit_history: Option<EditHistory>,
     pub entry: Entries,
     pub card: Option<Card>,
+    // parsed out of `card` for poll cards
+    pub poll: Option<Poll>,
     pub text: String,
-    pub source: String,
+    // `None` when twitter left it out
+    pub source: Option<TweetSource>,
     pub display_text_range: (u16, u16),
     pub stats: TweetStats,
     pub reply_info: ReplyInfo,
     pub moderated: bool,
-    pub conversation_control: ConversationControl,
+    pub reply_restriction: Option<ReplyRestriction>,
     pub vibe: Option<Vibe>,
+    // the area the author tagged, a city or a point of interest
     pub place: Option<Place>,
-    pub collaborators: Option<Vec<u64>>,
+    // the precise location, rarer than `place`
+    pub geo: Option<GeoPoint>,
+    pub collaborators: Option<Vec<UserId>>,
+    pub is_translatable: bool,
+    pub bookmarked: Option<bool>,
+    pub withheld_scope: Option<WithheldScope>,
+    // set when the withholding came from a copyright complaint (DMCA)
+    pub withheld_copyright: bool,
+    pub withheld_text: Option<String>,
+    // same codes as `User::withheld_in_countries`
+    pub withheld_in_countries: Vec<String>,
+    pub article: Option<Article>,
 }
 
 #[derive(
@@ -495,10 +1200,22 @@ pub struct TweetData {
     rkyv::Serialize,
     rkyv::Deserialize,
 )]
-pub struct Vibe {
-    pub discovery_query_text: String,
-    pub text: String,
-    pub img_description: String,
+#[cfg_attr(feature = "validation", archive(check_bytes))]
+pub struct EditHistory {
+    // the versions before this one, oldest first. empty for the original of an edited tweet.
+    pub previous_ids: Vec<TweetId>,
+    pub editable_until: Option<DateTime<Utc>>,
+    pub edit_eligible: bool,
+}
+
+impl EditHistory {
+    pub fn is_editable(&self) -> bool {
+        self.edit_eligible
+            && self
+                .editable_until
+                .map(|until| until > Utc::now())
+                .unwrap_or(false)
+    }
 }
 
 #[derive(
@@ -513,13 +1230,14 @@ pub struct Vibe {
     rkyv::Serialize,
     rkyv::Deserialize,
 )]
-pub struct TweetStats {
-    pub quote_tweets: u32,
-    pub retweets: u32,
-    pub likes: u32,
-    pub replies: u32,
+#[cfg_attr(feature = "validation", archive(check_bytes))]
+pub struct Article {
+    pub title: String,
+    pub body: String,
+    pub cover_image: Option<String>,
 }
 
+// the client a tweet was posted with, e.g. "Twitter for iPhone"
 #[derive(
     Clone,
     Debug,
@@ -532,25 +1250,67 @@ pub struct TweetStats {
     rkyv::Serialize,
     rkyv::Deserialize,
 )]
-pub enum ConversationControl {
-    None,
-    FollowsOnly,
-    MentionedOnly,
-    Other(String),
+#[cfg_attr(feature = "validation", archive(check_bytes))]
+pub struct TweetSource {
+    pub name: String,
+    // the client's homepage, only there when twitter sent the source as a link
+    pub url: Option<String>,
 }
 
-impl From<Option<TweetConversationControl>> for ConversationControl {
-    fn from(value: Option<TweetConversationControl>) -> Self {
-        if let Some(v) = value {
-            return match v.policy.as_str() {
-                "ByInvitation" => ConversationControl::MentionedOnly,
-                "Community" => ConversationControl::FollowsOnly,
-                o => ConversationControl::Other(o.to_string()),
-            };
+#[cfg(feature = "scrape")]
+impl TweetSource {
+    // twitter sends `<a href="..." rel="nofollow">Twitter for iPhone</a>`, older tweets and some
+    // apis only the plain name
+    pub(crate) fn parse(raw: &str) -> Option<Self> {
+        let frag = Html::parse_fragment(raw);
+        let (name, url) = match frag.select(&LINK_SELECTOR).next() {
+            Some(link) => (
+                link.text().collect::<String>(),
+                link.value()
+                    .attr("href")
+                    .filter(|href| !href.is_empty())
+                    .map(ToString::to_string),
+            ),
+            None => (frag.root_element().text().collect::<String>(), None),
+        };
+        let name = name.trim();
+        if name.is_empty() {
+            return None;
         }
-        ConversationControl::None
+        Some(TweetSource {
+            name: name.to_string(),
+            url,
+        })
     }
 }
+
+#[cfg(feature = "scrape")]
+#[test]
+fn tweet_source_from_html() {
+    let source = TweetSource::parse(
+        r#"<a href="http://twitter.com/download/iphone" rel="nofollow">Twitter for iPhone</a>"#,
+    )
+    .unwrap();
+    assert_eq!(source.name, "Twitter for iPhone");
+    assert_eq!(
+        source.url.as_deref(),
+        Some("http://twitter.com/download/iphone")
+    );
+
+    let escaped = TweetSource::parse(r#"<a href="https://example.com">Tweets &amp; Bots</a>"#);
+    assert_eq!(escaped.unwrap().name, "Tweets & Bots");
+
+    let plain = TweetSource::parse("web").unwrap();
+    assert_eq!(plain.name, "web");
+    assert_eq!(plain.url, None);
+
+    assert_eq!(TweetSource::parse(""), None);
+    assert_eq!(
+        TweetSource::parse("<a href=\"https://example.com\"></a>"),
+        None
+    );
+}
+
 #[derive(
     Clone,
     Debug,
@@ -563,9 +1323,11 @@ impl From<Option<TweetConversationControl>> for ConversationControl {
     rkyv::Serialize,
     rkyv::Deserialize,
 )]
-pub struct ReplyInfo {
-    pub replying_to: Option<u64>,
-    pub quoting: Option<u64>,
+#[cfg_attr(feature = "validation", archive(check_bytes))]
+pub struct Vibe {
+    pub discovery_query_text: String,
+    pub text: String,
+    pub img_description: String,
 }
 
 #[derive(
@@ -580,11 +1342,10 @@ pub struct ReplyInfo {
     rkyv::Serialize,
     rkyv::Deserialize,
 )]
-pub struct Card {
-    pub id: String,
-    pub url: String,
-    pub name: String,
-    pub values: HashMap<String, CardValue, ahash::RandomState>,
+pub struct Translation {
+    pub text: String,
+    pub source_language: String,
+    pub destination_language: String,
 }
 
 #[derive(
@@ -599,8 +1360,360 @@ pub struct Card {
     rkyv::Serialize,
     rkyv::Deserialize,
 )]
-pub struct Entries {
-    pub media: Vec<Media>,
+#[cfg_attr(feature = "validation", archive(check_bytes))]
+pub struct TweetStats {
+    pub quote_tweets: u32,
+    pub retweets: u32,
+    pub likes: u32,
+    pub replies: u32,
+    // older tweets don't carry a bookmark count
+    pub bookmarks: Option<u64>,
+}
+
+#[derive(
+    Clone,
+    Debug,
+    Hash,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+    Archive,
+    rkyv::Serialize,
+    rkyv::Deserialize,
+)]
+#[cfg_attr(feature = "validation", archive(check_bytes))]
+pub enum ReplyRestriction {
+    Following,
+    Mentioned,
+    Verified,
+    Subscribers,
+    Other(String),
+}
+
+#[derive(
+    Clone,
+    Debug,
+    Hash,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+    Archive,
+    rkyv::Serialize,
+    rkyv::Deserialize,
+)]
+#[cfg_attr(feature = "validation", archive(check_bytes))]
+pub enum WithheldScope {
+    Tweet,
+    User,
+    Status,
+    Other(String),
+}
+
+impl From<&str> for WithheldScope {
+    fn from(value: &str) -> Self {
+        match value {
+            "tweet" => WithheldScope::Tweet,
+            "user" => WithheldScope::User,
+            "status" => WithheldScope::Status,
+            o => WithheldScope::Other(o.to_string()),
+        }
+    }
+}
+
+// why a tweet came back as a tombstone, read off the text twitter shows in its place
+#[derive(
+    Clone,
+    Debug,
+    Hash,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+    Archive,
+    rkyv::Serialize,
+    rkyv::Deserialize,
+)]
+pub enum TombstoneReason {
+    Suspended,
+    // deleted by the author, or the whole account is gone
+    Deleted,
+    AgeRestricted,
+    Withheld,
+    Protected,
+    Unknown(String),
+}
+
+impl From<&str> for TombstoneReason {
+    fn from(value: &str) -> Self {
+        let text = value.to_lowercase();
+        if text.contains("suspended account") {
+            TombstoneReason::Suspended
+        } else if text.contains("was deleted") || text.contains("no longer exists") {
+            TombstoneReason::Deleted
+        } else if text.contains("age-restricted") {
+            TombstoneReason::AgeRestricted
+        } else if text.contains("withheld") {
+            TombstoneReason::Withheld
+        } else if text.contains("limits who can view") || text.contains("protected") {
+            TombstoneReason::Protected
+        } else {
+            TombstoneReason::Unknown(value.to_string())
+        }
+    }
+}
+
+#[test]
+fn tombstone_reasons() {
+    assert_eq!(
+        TombstoneReason::from("This Tweet is from a suspended account. Learn more"),
+        TombstoneReason::Suspended
+    );
+    assert_eq!(
+        TombstoneReason::from("This Post was deleted by the Post author. Learn more"),
+        TombstoneReason::Deleted
+    );
+    assert_eq!(
+        TombstoneReason::from("Age-restricted adult content. This content might not be appropriate for people under 18 years old."),
+        TombstoneReason::AgeRestricted
+    );
+    assert_eq!(
+        TombstoneReason::from(
+            "This Tweet from @jack has been withheld in Germany in response to a legal demand."
+        ),
+        TombstoneReason::Withheld
+    );
+    assert_eq!(
+        TombstoneReason::from("You’re unable to view this Tweet because this account owner limits who can view their Tweets."),
+        TombstoneReason::Protected
+    );
+    assert_eq!(
+        TombstoneReason::from("This Tweet is unavailable."),
+        TombstoneReason::Unknown("This Tweet is unavailable.".to_string())
+    );
+}
+
+// `None` on the tweet means everyone can reply
+impl From<&TweetConversationControl> for ReplyRestriction {
+    fn from(value: &TweetConversationControl) -> Self {
+        match value.policy.as_str() {
+            "Community" => ReplyRestriction::Following,
+            "ByInvitation" => ReplyRestriction::Mentioned,
+            "Verified" => ReplyRestriction::Verified,
+            "Subscribers" => ReplyRestriction::Subscribers,
+            o => ReplyRestriction::Other(o.to_string()),
+        }
+    }
+}
+
+#[cfg(feature = "scrape")]
+#[test]
+fn reply_restriction_from_payload() {
+    let ctrl = serde_json::from_str::<TweetConversationControl>(
+        r#"{"policy":"ByInvitation","conversation_owner_results":{"result":{"__typename":"User"}}}"#,
+    )
+    .unwrap();
+    assert_eq!(ReplyRestriction::from(&ctrl), ReplyRestriction::Mentioned);
+
+    let ctrl =
+        serde_json::from_str::<TweetConversationControl>(r#"{"policy":"Community"}"#).unwrap();
+    assert_eq!(ReplyRestriction::from(&ctrl), ReplyRestriction::Following);
+}
+
+#[derive(
+    Clone,
+    Debug,
+    Hash,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+    Archive,
+    rkyv::Serialize,
+    rkyv::Deserialize,
+)]
+#[cfg_attr(feature = "validation", archive(check_bytes))]
+pub struct ReplyInfo {
+    pub replying_to: Option<TweetId>,
+    pub replying_to_user: Option<UserId>,
+    pub quoting: Option<TweetId>,
+    pub retweeting: Option<TweetId>,
+    // the original author, the retweet itself is posted by the retweeter
+    pub retweeting_user: Option<UserId>,
+}
+
+#[derive(
+    Clone,
+    Debug,
+    Hash,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+    Archive,
+    rkyv::Serialize,
+    rkyv::Deserialize,
+)]
+#[cfg_attr(feature = "validation", archive(check_bytes))]
+pub struct Card {
+    pub id: String,
+    pub url: String,
+    pub name: String,
+    pub values: HashMap<String, CardValue, ahash::RandomState>,
+}
+
+impl From<TwtCard> for Card {
+    fn from(tcd: TwtCard) -> Self {
+        Card {
+            id: tcd.rest_id,
+            url: tcd.legacy.url,
+            name: tcd.legacy.name,
+            values: tcd
+                .legacy
+                .binding_values
+                .into_iter()
+                .map(|bv| (bv.key, bv.value))
+                .collect::<HashMap<String, CardValue, ahash::RandomState>>(),
+        }
+    }
+}
+
+// twitter polls have 2 to 4 choices
+const MAX_POLL_CHOICES: usize = 4;
+
+#[derive(
+    Clone,
+    Debug,
+    Hash,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+    Archive,
+    rkyv::Serialize,
+    rkyv::Deserialize,
+)]
+#[cfg_attr(feature = "validation", archive(check_bytes))]
+pub struct Poll {
+    // in the order they are shown
+    pub choices: Vec<PollChoice>,
+    pub total_votes: u64,
+    pub ends_at: Option<DateTime<Utc>>,
+    // voting closed and the counts won't change anymore
+    pub is_final: bool,
+}
+
+#[derive(
+    Clone,
+    Debug,
+    Hash,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+    Archive,
+    rkyv::Serialize,
+    rkyv::Deserialize,
+)]
+#[cfg_attr(feature = "validation", archive(check_bytes))]
+pub struct PollChoice {
+    pub label: String,
+    pub votes: u64,
+}
+
+impl Poll {
+    // poll cards are named like `poll2choice_text_only` or `poll4choice_image`, the choices sit in
+    // the binding values as `choice1_label`, `choice1_count` and so on. `None` for other cards.
+    pub fn from_card(card: &Card) -> Option<Self> {
+        if !card.name.starts_with("poll") {
+            return None;
+        }
+        let string_value = |key: &str| card.values.get(key).map(|v| v.string_value.as_str());
+
+        let choices = (1..=MAX_POLL_CHOICES)
+            .map_while(|n| {
+                let label = string_value(&format!("choice{n}_label"))?;
+                // counts are left out while the viewer hasn't voted on a running poll
+                let votes = string_value(&format!("choice{n}_count"))
+                    .and_then(|count| count.parse().ok())
+                    .unwrap_or(0);
+                Some(PollChoice {
+                    label: label.to_string(),
+                    votes,
+                })
+            })
+            .collect::<Vec<_>>();
+        if choices.is_empty() {
+            return None;
+        }
+
+        Some(Poll {
+            total_votes: choices.iter().map(|choice| choice.votes).sum(),
+            choices,
+            ends_at: string_value("end_datetime_utc")
+                .and_then(|end| DateTime::parse_from_rfc3339(end).ok())
+                .map(|end| end.with_timezone(&Utc)),
+            is_final: card
+                .values
+                .get("counts_are_final")
+                .and_then(|v| v.boolean_value)
+                .unwrap_or(false),
+        })
+    }
+}
+
+#[cfg(feature = "scrape")]
+#[test]
+fn poll_from_card_fixture() {
+    let card = serde_json::from_str::<TwtCard>(
+        r#"{"rest_id":"card://1689636931376476161","legacy":{"binding_values":[{"key":"choice1_label","value":{"string_value":"Tabs","type":"STRING"}},{"key":"choice2_label","value":{"string_value":"Spaces","type":"STRING"}},{"key":"end_datetime_utc","value":{"string_value":"2023-08-11T14:29:46Z","type":"STRING"}},{"key":"counts_are_final","value":{"boolean_value":true,"type":"BOOLEAN"}},{"key":"choice2_count","value":{"string_value":"1523","type":"STRING"}},{"key":"choice1_count","value":{"string_value":"4711","type":"STRING"}},{"key":"last_updated_datetime_utc","value":{"string_value":"2023-08-11T14:29:47Z","type":"STRING"}},{"key":"duration_minutes","value":{"string_value":"1440","type":"STRING"}},{"key":"api","value":{"string_value":"capi://passthrough/1","type":"STRING"}},{"key":"card_url","value":{"scribe_key":"card_url","string_value":"https://twitter.com","type":"STRING"}}],"card_platform":{"platform":{"audience":{"name":"production"},"device":{"name":"Swift","version":"12"}}},"name":"poll2choice_text_only","url":"card://1689636931376476161","user_refs_results":[]}}"#,
+    )
+    .unwrap();
+    let card = Card::from(card);
+    let poll = Poll::from_card(&card).unwrap();
+    assert_eq!(
+        poll.choices,
+        vec![
+            PollChoice {
+                label: "Tabs".to_string(),
+                votes: 4711
+            },
+            PollChoice {
+                label: "Spaces".to_string(),
+                votes: 1523
+            },
+        ]
+    );
+    assert_eq!(poll.total_votes, 6234);
+    assert_eq!(
+        poll.ends_at.map(|end| end.to_rfc3339()),
+        Some("2023-08-11T14:29:46+00:00".to_string())
+    );
+    assert!(poll.is_final);
+
+    let not_a_poll = Card {
+        name: "summary_large_image".to_string(),
+        ..card
+    };
+    assert_eq!(Poll::from_card(&not_a_poll), None);
+}
+
+#[derive(
+    Clone,
+    Debug,
+    Hash,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+    Archive,
+    rkyv::Serialize,
+    rkyv::Deserialize,
+)]
+#[cfg_attr(feature = "validation", archive(check_bytes))]
+pub struct Entries {
+    pub media: Vec<Media>,
     pub mentions: Vec<TweetUserMentions>,
     pub urls: Vec<String>,
     pub hashtags: Vec<String>,
@@ -618,6 +1731,7 @@ pub struct Entries {
     rkyv::Serialize,
     rkyv::Deserialize,
 )]
+#[cfg_attr(feature = "validation", archive(check_bytes))]
 pub struct Media {
     pub id: u64,
     pub media_key: String,
@@ -626,6 +1740,200 @@ pub struct Media {
     pub expanded_url: String,
     pub ext_alt_text: Option<String>,
     pub views: Option<u32>,
+    // twitter's crop hints for thumbnails, in pixels of the original image
+    pub focus_rects: Vec<Rect>,
+    pub kind: MediaKind,
+}
+
+impl Media {
+    pub(crate) fn from_entry(entry: TweetEntryMedia) -> SResult<Self> {
+        let id = entry
+            .id_str
+            .parse::<u64>()
+            .map_err(|why| TwitterBadRestId("Media ID", why.to_string()))?;
+        let kind = MediaKind::from_entry(&entry);
+        Ok(Media {
+            id,
+            media_key: entry.media_key,
+            media_url_https: entry.media_url_https,
+            r#type: entry.r#type,
+            ext_alt_text: entry.ext_alt_text,
+            views: entry.media_stats.map(|stats| stats.view_count),
+            focus_rects: entry
+                .original_info
+                .map(|info| info.focus_rects)
+                .unwrap_or_default(),
+            expanded_url: entry.expanded_url,
+            kind,
+        })
+    }
+
+    // the original upload for photos, the best mp4 for videos. `None` for a video that only
+    // came with its hls playlist.
+    pub fn download_url(&self) -> Option<String> {
+        match &self.kind {
+            MediaKind::Photo { url } => Some(format!("{url}?name=orig")),
+            MediaKind::Video { variants, .. } => variants
+                .iter()
+                .filter(|variant| variant.content_type == "video/mp4")
+                .max_by_key(|variant| variant.bitrate.unwrap_or(0))
+                .map(|variant| variant.url.clone()),
+            MediaKind::AnimatedGif { url } => Some(url.clone()),
+        }
+    }
+
+    // from the url, videos and gifs are always mp4
+    #[cfg(feature = "scrape")]
+    fn extension(&self) -> &str {
+        match &self.kind {
+            MediaKind::Photo { url } => url
+                .rsplit_once('.')
+                .map(|(_, ext)| ext)
+                .filter(|ext| !ext.contains('/'))
+                .unwrap_or("jpg"),
+            MediaKind::Video { .. } | MediaKind::AnimatedGif { .. } => "mp4",
+        }
+    }
+}
+
+#[derive(
+    Clone,
+    Debug,
+    Hash,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+    Archive,
+    rkyv::Serialize,
+    rkyv::Deserialize,
+)]
+#[cfg_attr(feature = "validation", archive(check_bytes))]
+#[serde(tag = "t", content = "c")]
+pub enum MediaKind {
+    Photo {
+        url: String,
+    },
+    // every encoding twitter has of the video, the hls playlist included
+    Video {
+        variants: Vec<VideoVariant>,
+        duration_ms: Option<u64>,
+    },
+    // the looping mp4 twitter turned the gif into, there is no actual gif to get
+    AnimatedGif {
+        url: String,
+    },
+}
+
+impl MediaKind {
+    fn from_entry(entry: &TweetEntryMedia) -> Self {
+        let video_info = entry.video_info.as_ref();
+        match entry.r#type.as_str() {
+            "video" => MediaKind::Video {
+                variants: video_info
+                    .map(|info| info.variants.iter().map(VideoVariant::from).collect())
+                    .unwrap_or_default(),
+                duration_ms: video_info.and_then(|info| info.duration_millis),
+            },
+            "animated_gif" => MediaKind::AnimatedGif {
+                url: video_info
+                    .and_then(|info| info.variants.first())
+                    .map(|variant| variant.url.clone())
+                    .unwrap_or_else(|| entry.media_url_https.clone()),
+            },
+            // "photo", and whatever else twitter comes up with still has a thumbnail
+            _ => MediaKind::Photo {
+                url: entry.media_url_https.clone(),
+            },
+        }
+    }
+}
+
+#[derive(
+    Clone,
+    Debug,
+    Hash,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+    Archive,
+    rkyv::Serialize,
+    rkyv::Deserialize,
+)]
+#[cfg_attr(feature = "validation", archive(check_bytes))]
+pub struct VideoVariant {
+    // `None` for the hls playlist
+    pub bitrate: Option<u32>,
+    pub content_type: String,
+    pub url: String,
+}
+
+impl From<&TweetVideoVariant> for VideoVariant {
+    fn from(variant: &TweetVideoVariant) -> Self {
+        VideoVariant {
+            bitrate: variant.bitrate,
+            content_type: variant.content_type.clone(),
+            url: variant.url.clone(),
+        }
+    }
+}
+
+#[cfg(feature = "scrape")]
+#[test]
+fn media_kind_from_fixture() {
+    let video = serde_json::from_str::<TweetEntryMedia>(
+        r#"{"id_str":"1665420474339651584","media_key":"7_1665420474339651584","media_url_https":"https://pbs.twimg.com/ext_tw_video_thumb/1665420474339651584/pu/img/xb6Hq3OLzl2zZE3V.jpg","type":"video","expanded_url":"https://twitter.com/XDevelopers/status/1665420593462427648/video/1","video_info":{"aspect_ratio":[16,9],"duration_millis":21288,"variants":[{"bitrate":832000,"content_type":"video/mp4","url":"https://video.twimg.com/ext_tw_video/1665420474339651584/pu/vid/640x360/Vtr0dn7oT5W0QOe6.mp4?tag=12"},{"content_type":"application/x-mpegURL","url":"https://video.twimg.com/ext_tw_video/1665420474339651584/pu/pl/CJx6Mxoz0V-DrReW.m3u8?tag=12"},{"bitrate":2176000,"content_type":"video/mp4","url":"https://video.twimg.com/ext_tw_video/1665420474339651584/pu/vid/1280x720/rWu5e9Kn1SKdcz8S.mp4?tag=12"}]}}"#,
+    )
+    .unwrap();
+    let video = Media::from_entry(video).unwrap();
+    assert_eq!(
+        video.download_url().as_deref(),
+        Some("https://video.twimg.com/ext_tw_video/1665420474339651584/pu/vid/1280x720/rWu5e9Kn1SKdcz8S.mp4?tag=12")
+    );
+    match video.kind {
+        MediaKind::Video {
+            variants,
+            duration_ms,
+        } => {
+            assert_eq!(duration_ms, Some(21288));
+            assert_eq!(variants.len(), 3);
+            assert_eq!(variants[1].bitrate, None);
+            assert_eq!(variants[2].bitrate, Some(2176000));
+        }
+        kind => panic!("expected a video, got {kind:?}"),
+    }
+
+    let gif = serde_json::from_str::<TweetEntryMedia>(
+        r#"{"id_str":"1665421059130257408","media_key":"16_1665421059130257408","media_url_https":"https://pbs.twimg.com/tweet_video_thumb/FxwZ8VYWAAAZXyT.jpg","type":"animated_gif","expanded_url":"https://twitter.com/XDevelopers/status/1665421098850324480/photo/1","video_info":{"aspect_ratio":[1,1],"variants":[{"bitrate":0,"content_type":"video/mp4","url":"https://video.twimg.com/tweet_video/FxwZ8VYWAAAZXyT.mp4"}]}}"#,
+    )
+    .unwrap();
+    assert_eq!(
+        Media::from_entry(gif).unwrap().kind,
+        MediaKind::AnimatedGif {
+            url: "https://video.twimg.com/tweet_video/FxwZ8VYWAAAZXyT.mp4".to_string()
+        }
+    );
+}
+
+#[derive(
+    Clone,
+    Debug,
+    Hash,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+    Archive,
+    rkyv::Serialize,
+    rkyv::Deserialize,
+)]
+#[cfg_attr(feature = "validation", archive(check_bytes))]
+pub struct Rect {
+    pub x: u32,
+    pub y: u32,
+    pub w: u32,
+    pub h: u32,
 }
 
 #[derive(
@@ -648,16 +1956,16 @@ pub(crate) struct TweetRequest {
 #[cfg(feature = "scrape")]
 impl TweetRequest {
     pub(crate) fn first_tweet(&self) -> Option<&TweetResultResult> {
-        for inst in self
+        for inst in &self
             .data
             .threaded_conversation_with_injections_v2
             .instructions
         {
             if let Instruction::TimelineAddEntries(add) = inst {
                 for entry in &add.entries {
-                    if let Entry::Tweet(te) = entry {
-                        if let TweetResults::Ok(trr) = &te.item_content.tweet_results {
-                            Some(trr)
+                    if let EntryVariant::Tweet(te) = &entry.content {
+                        if let Some(trr) = te.item_content.tweet_results.ok() {
+                            return Some(trr);
                         }
                     }
                 }
@@ -667,16 +1975,35 @@ impl TweetRequest {
         None
     }
 
-    pub(crate) fn filter_cursor(&self, filter: FilterCursorTweetRequest) -> Option<&str> {
-        for inst in &self
-            .data
+    // focal tweet, parents and replies in page order, tombstones included
+    pub(crate) fn tweet_results(&self) -> impl Iterator<Item = &TweetResults> {
+        self.data
             .threaded_conversation_with_injections_v2
             .instructions
-        {
-            inst.filter_cursor(filter)
-        }
+            .iter()
+            .filter_map(|inst| match inst {
+                Instruction::TimelineAddEntries(add) => Some(&add.entries),
+                _ => None,
+            })
+            .flatten()
+            .flat_map(|entry| match &entry.content {
+                EntryVariant::Tweet(twt) => vec![&twt.item_content.tweet_results],
+                EntryVariant::ConversationThread(ct) => ct
+                    .content
+                    .items
+                    .iter()
+                    .map(|thread| &thread.item.item_content.tweet_results)
+                    .collect(),
+                EntryVariant::Cursor(_) => vec![],
+            })
+    }
 
-        None
+    pub(crate) fn filter_cursor(&self, filter: FilterCursorTweetRequest) -> Option<&str> {
+        self.data
+            .threaded_conversation_with_injections_v2
+            .instructions
+            .iter()
+            .find_map(|inst| inst.filter_cursor(filter))
     }
 
     #[tracing::instrument]
@@ -686,44 +2013,272 @@ impl TweetRequest {
         first_cursor: String,
         filter: FilterCursorTweetRequest,
     ) -> SResult<VecDeque<Self>> {
-        let mut requests = VecDeque::with_capacity(5);
-
-        let mut cursor_counter = first_cursor.to_string();
-        let mut break_on_next = false;
-        loop {
-            let scrolled_up_request = scraper
-                .api_req::<TweetRequest>(
-                    scraper.make_get_req(twitter_request_url_thread(&id, Some(&cursor_counter))),
-                )
-                .await?;
+        let id = id.to_string();
+        paginate(scraper, filter, first_cursor, None, |cursor| {
+            twitter_request_url_thread(&id, Some(cursor))
+        })
+        .await
+    }
+}
 
-            scrolled_up_request.json_request_filter_errors()?;
+#[cfg(feature = "scrape")]
+crate::impl_filter_json!(TweetRequest);
 
-            requests.push_front(scrolled_up_request);
-            if break_on_next {
-                break;
-            }
+#[cfg(feature = "scrape")]
+impl HasCursor for TweetRequest {
+    fn filter_cursor(&self, filter: FilterCursorTweetRequest) -> Option<&str> {
+        TweetRequest::filter_cursor(self, filter)
+    }
 
-            match scrolled_up_request.filter_cursor(filter) {
-                Some(up) => {
-                    cursor_counter = up.to_string();
-                }
-                None => break_on_next = true,
-            }
-        }
+    fn entry_count(&self) -> usize {
+        self.data
+            .threaded_conversation_with_injections_v2
+            .instructions
+            .iter()
+            .map(Instruction::entry_count)
+            .sum()
+    }
+}
 
-        Ok(requests)
+#[derive(
+    Clone,
+    Debug,
+    Hash,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+    Archive,
+    rkyv::Serialize,
+    rkyv::Deserialize,
+)]
+pub(crate) struct TranslationResponse {
+    pub translation: String,
+    #[serde(rename = "translationState")]
+    pub translation_state: String,
+    #[serde(rename = "sourceLanguage")]
+    pub source_language: String,
+    #[serde(rename = "destinationLanguage")]
+    pub destination_language: String,
+}
+
+#[derive(
+    Clone,
+    Debug,
+    Hash,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+    Archive,
+    rkyv::Serialize,
+    rkyv::Deserialize,
+)]
+pub(crate) struct TweetsByIdsRequest {
+    #[serde(default)]
+    pub errors: Vec<Error>,
+    pub data: TweetsByIdsData,
+}
+
+#[derive(
+    Clone,
+    Debug,
+    Hash,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+    Archive,
+    rkyv::Serialize,
+    rkyv::Deserialize,
+)]
+pub(crate) struct TweetsByIdsData {
+    #[serde(rename = "tweetResult")]
+    pub tweet_result: Vec<TweetResultWrapper>,
+}
+
+#[derive(
+    Clone,
+    Debug,
+    Hash,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+    Archive,
+    rkyv::Serialize,
+    rkyv::Deserialize,
+)]
+pub(crate) struct TweetByIdRequest {
+    #[serde(default)]
+    pub errors: Vec<Error>,
+    pub data: TweetByIdData,
+}
+
+#[derive(
+    Clone,
+    Debug,
+    Hash,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+    Archive,
+    rkyv::Serialize,
+    rkyv::Deserialize,
+)]
+pub(crate) struct TweetByIdData {
+    #[serde(rename = "tweetResult")]
+    pub tweet_result: Option<TweetResultWrapper>,
+}
+
+#[cfg(feature = "scrape")]
+impl TweetByIdData {
+    // twitter leaves the result out entirely for tweets that never existed or were deleted
+    pub(crate) fn into_tweet(self, id: TweetId) -> SResult<Tweet> {
+        match self.tweet_result.and_then(|tr| tr.result) {
+            Some(result @ TweetResults::Ok(_)) => Tweet::new_from_entry(&result),
+            Some(TweetResults::Tombstone(tomb)) => Err(TweetUnavailable {
+                id,
+                reason: TombstoneReason::from(tomb.tombstone.text.text.as_str()),
+            }),
+            None => Err(TweetUnavailable {
+                id,
+                reason: TombstoneReason::Deleted,
+            }),
+        }
     }
 }
 
-crate::impl_filter_json!(TweetRequest);
+#[cfg(feature = "scrape")]
+#[test]
+fn tweet_by_id_unavailable() {
+    let protected = TweetByIdData {
+        tweet_result: Some(TweetResultWrapper {
+            result: Some(TweetResults::Tombstone(TweetTombstone {
+                tombstone: TombstoneStone {
+                    __typename: "TextTombstone".to_string(),
+                    text: TombstoneText {
+                        rtl: false,
+                        text: "You’re unable to view this Tweet because this account owner limits who can view their Tweets.".to_string(),
+                    },
+                },
+            })),
+        }),
+    };
+    assert!(matches!(
+        protected.into_tweet(TweetId(20)),
+        Err(TweetUnavailable {
+            id: TweetId(20),
+            reason: TombstoneReason::Protected
+        })
+    ));
+
+    let deleted = TweetByIdData {
+        tweet_result: Some(TweetResultWrapper { result: None }),
+    };
+    assert!(matches!(
+        deleted.into_tweet(TweetId(21)),
+        Err(TweetUnavailable {
+            id: TweetId(21),
+            reason: TombstoneReason::Deleted
+        })
+    ));
+}
+
+#[derive(
+    Clone,
+    Debug,
+    Hash,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+    Archive,
+    rkyv::Serialize,
+    rkyv::Deserialize,
+)]
+pub(crate) struct TweetResultWrapper {
+    pub result: Option<TweetResults>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub(crate) struct TweetExistsRequest {
+    pub data: TweetExistsData,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub(crate) struct TweetExistsData {
+    #[serde(rename = "tweetResult")]
+    pub tweet_result: Option<TweetExistsResult>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub(crate) struct TweetExistsResult {
+    pub result: Option<TweetExistsTypename>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub(crate) struct TweetExistsTypename {
+    pub __typename: String,
+}
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Debug)]
 pub enum FilterCursorTweetRequest {
     Top,
     Bottom,
 }
 
+impl FilterCursorTweetRequest {
+    // pages can carry more than one cursor of a kind. the top cursor is the first one, the
+    // cursor that actually paginates down is the last one.
+    pub(crate) fn pick<'a>(self, cursors: impl IntoIterator<Item = &'a Cursor>) -> Option<&'a str> {
+        let mut matching = cursors.into_iter().filter(|c| self.matches(c));
+        let cursor = match self {
+            FilterCursorTweetRequest::Top => matching.next(),
+            FilterCursorTweetRequest::Bottom => matching.last(),
+        };
+        cursor.map(|c| c.content.item_content.value.as_str())
+    }
+
+    fn matches(self, cursor: &Cursor) -> bool {
+        match self {
+            FilterCursorTweetRequest::Top => {
+                cursor.entry_id.starts_with("cursor-top")
+                    || cursor.content.item_content.cursor_type == "Top"
+            }
+            FilterCursorTweetRequest::Bottom => {
+                cursor.entry_id.starts_with("cursor-bottom")
+                    || cursor.entry_id.starts_with("cursor-showmorethreads")
+                    || cursor
+                        .content
+                        .item_content
+                        .cursor_type
+                        .starts_with("Bottom")
+            }
+        }
+    }
+}
+
+#[cfg(feature = "scrape")]
+#[test]
+fn pick_last_bottom_cursor() {
+    let cursors = serde_json::from_str::<Vec<Cursor>>(
+        r#"[
+            {"entryId":"cursor-top-1","content":{"entryType":"TimelineTimelineCursor","__typename":"TimelineTimelineCursor","itemContent":{"itemType":"TimelineTimelineCursor","__typename":"TimelineTimelineCursor","value":"TOP","cursorType":"Top"}}},
+            {"entryId":"cursor-bottom-1","content":{"entryType":"TimelineTimelineCursor","__typename":"TimelineTimelineCursor","itemContent":{"itemType":"TimelineTimelineCursor","__typename":"TimelineTimelineCursor","value":"STALE","cursorType":"Bottom"}}},
+            {"entryId":"cursor-bottom-2","content":{"entryType":"TimelineTimelineCursor","__typename":"TimelineTimelineCursor","itemContent":{"itemType":"TimelineTimelineCursor","__typename":"TimelineTimelineCursor","value":"NEXT","cursorType":"Bottom"}}}
+        ]"#,
+    )
+    .unwrap();
+    assert_eq!(FilterCursorTweetRequest::Top.pick(&cursors), Some("TOP"));
+    assert_eq!(
+        FilterCursorTweetRequest::Bottom.pick(&cursors),
+        Some("NEXT")
+    );
+    assert_eq!(FilterCursorTweetRequest::Bottom.pick(&cursors[..1]), None);
+}
+
 #[derive(
     Clone,
     Debug,
@@ -777,27 +2332,25 @@ pub(crate) enum Instruction {
 impl Instruction {
     pub(crate) fn filter_cursor(&self, cursor: FilterCursorTweetRequest) -> Option<&str> {
         if let Instruction::TimelineAddEntries(add) = self {
-            for entry in &add.entries {
-                if let Entry::Cursor(c) = entry {
-                    match cursor {
-                        FilterCursorTweetRequest::Top => {
-                            if c.entry_id.starts_with("cursor-top") {
-                                return Some(c.content.item_content.value.as_str());
-                            }
-                        }
-                        FilterCursorTweetRequest::Bottom => {
-                            if c.entry_id.starts_with("cursor-bottom")
-                                || c.entry_id.starts_with("cursor-showmorethreads")
-                            {
-                                return Some(c.content.item_content.value.as_str());
-                            }
-                        }
-                    }
-                }
-            }
+            return cursor.pick(add.entries.iter().filter_map(|entry| match &entry.content {
+                EntryVariant::Cursor(c) => Some(c),
+                _ => None,
+            }));
         }
         None
     }
+
+    // tweets and conversation threads, cursors left out
+    pub(crate) fn entry_count(&self) -> usize {
+        match self {
+            Instruction::TimelineAddEntries(add) => add
+                .entries
+                .iter()
+                .filter(|entry| !matches!(entry.content, EntryVariant::Cursor(_)))
+                .count(),
+            Instruction::TimelineTerminateTimeline(_) => 0,
+        }
+    }
 }
 
 #[derive(
@@ -837,8 +2390,9 @@ impl<'de> Deserialize<'de> for Entry {
     {
         enum Field {
             EntryId,
-            SortId,
             Content,
+            // `sortIndex` and whatever else twitter sends along
+            Ignored,
         }
 
         impl<'de> Deserialize<'de> for Field {
@@ -861,9 +2415,8 @@ impl<'de> Deserialize<'de> for Entry {
                     {
                         match value {
                             "entryId" => Ok(Field::EntryId),
-                            "sortId" => Ok(Field::SortId),
                             "content" => Ok(Field::Content),
-                            _ => Err(de::Error::unknown_variant(value, VARIANTS)),
+                            _ => Ok(Field::Ignored),
                         }
                     }
                 }
@@ -872,6 +2425,40 @@ impl<'de> Deserialize<'de> for Entry {
             }
         }
 
+        // the id says what the content is
+        fn from_content<'de, D>(id: &str, content: D) -> Result<EntryVariant, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            Ok(if id.starts_with("tweet-") {
+                EntryVariant::Tweet(Deserialize::deserialize(content)?)
+            } else if id.starts_with("conversationthread-") {
+                EntryVariant::ConversationThread(ConversationThread {
+                    content: Deserialize::deserialize(content)?,
+                })
+            } else if id.starts_with("cursor-") {
+                EntryVariant::Cursor(Cursor {
+                    entry_id: id.to_string(),
+                    content: Deserialize::deserialize(content)?,
+                })
+            } else {
+                return Err(de::Error::unknown_variant(id, VARIANTS));
+            })
+        }
+
+        struct ContentSeed<'a>(&'a str);
+
+        impl<'de> DeserializeSeed<'de> for ContentSeed<'_> {
+            type Value = EntryVariant;
+
+            fn deserialize<D>(self, deserializer: D) -> Result<EntryVariant, D::Error>
+            where
+                D: Deserializer<'de>,
+            {
+                from_content(self.0, deserializer)
+            }
+        }
+
         struct EntryVisitor;
 
         impl<'de> Visitor<'de> for EntryVisitor {
@@ -886,44 +2473,37 @@ impl<'de> Deserialize<'de> for Entry {
                 V: MapAccess<'de>,
             {
                 let mut entry_id: Option<String> = None;
-                let mut __typename: Option<String> = None;
-                let mut sort_id: Option<String> = None;
+                let mut content: Option<EntryVariant> = None;
+                // content that showed up before the id, parsed once the id is known
+                let mut buffered: Option<serde_json::Value> = None;
                 while let Some(key) = map.next_key()? {
                     match key {
                         Field::EntryId => {
                             entry_id = Some(map.next_value()?);
                         }
-                        Field::SortId => {
-                            sort_id = Some(map.next_value()?);
-                        }
-                        Field::Content => {
-                            if let Some(entry) = &entry_id {
-                                if entry.starts_with("tweet-") {
-                                    Ok(EntryVariant::Tweet(map.next_value()?))
-                                } else if entry.starts_with("conversationthread-") {
-                                    Ok(EntryVariant::ConversationThread(map.next_value()?))
-                                } else if entry.starts_with("cursor-") {
-                                    Ok(EntryVariant::Cursor(map.next_value()?))
-                                } else {
-                                    Err(de::Error::unknown_variant(
-                                        entry,
-                                        &["tweet", "conversationthread", "cursor"],
-                                    ))
-                                }
-                            }
-                            Err(de::Error::unknown_variant(
-                                "None",
-                                &["tweet", "conversationthread", "cursor"],
-                            ))
+                        Field::Content => match entry_id.as_deref() {
+                            Some(id) => content = Some(map.next_value_seed(ContentSeed(id))?),
+                            None => buffered = Some(map.next_value()?),
+                        },
+                        Field::Ignored => {
+                            map.next_value::<de::IgnoredAny>()?;
                         }
                     }
                 }
-                Err(de::Error::missing_field("content"))
+                let entry_id = entry_id.ok_or_else(|| de::Error::missing_field("entryId"))?;
+                let content = match (content, buffered) {
+                    (Some(content), _) => content,
+                    (None, Some(buffered)) => {
+                        from_content(&entry_id, buffered).map_err(de::Error::custom)?
+                    }
+                    (None, None) => return Err(de::Error::missing_field("content")),
+                };
+                Ok(Entry { entry_id, content })
             }
         }
 
-        const VARIANTS: &[&str] = &["Tweet", "ConversationThread", "Cursor"];
-        deserializer.deserialize_map(EntryVisitor {})
+        const VARIANTS: &[&str] = &["tweet", "conversationthread", "cursor"];
+        deserializer.deserialize_map(EntryVisitor)
     }
 }
 
@@ -939,8 +2519,9 @@ impl<'de> Deserialize<'de> for Entry {
     rkyv::Serialize,
     rkyv::Deserialize,
 )]
+#[serde(rename_all = "camelCase")]
 pub(crate) struct TweetEnt {
-    #[serde(rename = "itemContent")]
+    #[serde(alias = "item_content")]
     pub item_content: TweetItemContent,
 }
 
@@ -956,10 +2537,13 @@ pub(crate) struct TweetEnt {
     rkyv::Serialize,
     rkyv::Deserialize,
 )]
+#[serde(rename_all = "camelCase")]
 pub(crate) struct TweetItemContent {
-    #[serde(rename = "itemType")]
+    #[serde(alias = "item_type")]
     pub item_type: String,
+    #[serde(rename = "__typename")]
     pub __typename: String,
+    #[serde(rename = "tweet_results")]
     pub tweet_results: TweetResults,
 }
 
@@ -991,9 +2575,11 @@ pub(crate) struct ConversationThread {
     rkyv::Serialize,
     rkyv::Deserialize,
 )]
+#[serde(rename_all = "camelCase")]
 pub(crate) struct ConversationThreadContent {
-    #[serde(rename = "entryType")]
+    #[serde(alias = "entry_type")]
     pub entry_type: String,
+    #[serde(rename = "__typename")]
     pub __typename: String,
     #[serde(rename = "itemContent")]
     pub items: Vec<ConversationThreadItems>,
@@ -1011,8 +2597,9 @@ pub(crate) struct ConversationThreadContent {
     rkyv::Serialize,
     rkyv::Deserialize,
 )]
+#[serde(rename_all = "camelCase")]
 pub(crate) struct ConversationThreadItems {
-    #[serde(rename = "entryId")]
+    #[serde(alias = "entry_id")]
     pub entry_id: String,
     pub item: ConversationThreadItem,
 }
@@ -1029,8 +2616,9 @@ pub(crate) struct ConversationThreadItems {
     rkyv::Serialize,
     rkyv::Deserialize,
 )]
+#[serde(rename_all = "camelCase")]
 pub(crate) struct ConversationThreadItem {
-    #[serde(rename = "itemContent")]
+    #[serde(alias = "item_content")]
     pub item_content: ConversationThreadItemContent,
 }
 
@@ -1046,10 +2634,13 @@ pub(crate) struct ConversationThreadItem {
     rkyv::Serialize,
     rkyv::Deserialize,
 )]
+#[serde(rename_all = "camelCase")]
 pub(crate) struct ConversationThreadItemContent {
-    #[serde(rename = "itemType")]
+    #[serde(alias = "item_type")]
     pub item_type: String,
+    #[serde(rename = "__typename")]
     pub __typename: String,
+    #[serde(rename = "tweet_results")]
     pub tweet_results: TweetResults,
 }
 
@@ -1059,6 +2650,7 @@ pub(crate) struct ConversationThreadItemContent {
     Hash,
     PartialEq,
     Eq,
+    Default,
     Serialize,
     Deserialize,
     Archive,
@@ -1067,9 +2659,151 @@ pub(crate) struct ConversationThreadItemContent {
 )]
 pub(crate) struct EditControl {
     pub initial_tweet_id: Option<String>,
+    // set instead of the fields below on every version after the original
+    #[serde(default)]
+    pub edit_control_initial: Option<EditControlInitial>,
+    #[serde(default)]
+    pub edit_tweet_ids: Vec<String>,
+    #[serde(default)]
+    pub editable_until_msecs: String,
+    #[serde(default)]
+    pub is_edit_eligible: bool,
+    #[serde(default)]
+    pub edits_remaining: Option<String>,
+}
+
+impl EditControl {
+    pub(crate) fn resolved(&self) -> EditControlInitial {
+        match &self.edit_control_initial {
+            Some(initial) => initial.clone(),
+            None => EditControlInitial {
+                edit_tweet_ids: self.edit_tweet_ids.clone(),
+                editable_until_msecs: self.editable_until_msecs.clone(),
+                is_edit_eligible: self.is_edit_eligible,
+                edits_remaining: self.edits_remaining.clone(),
+            },
+        }
+    }
+}
+
+#[derive(
+    Clone,
+    Debug,
+    Hash,
+    PartialEq,
+    Eq,
+    Default,
+    Serialize,
+    Deserialize,
+    Archive,
+    rkyv::Serialize,
+    rkyv::Deserialize,
+)]
+pub(crate) struct EditControlInitial {
+    #[serde(default)]
     pub edit_tweet_ids: Vec<String>,
+    #[serde(default)]
     pub editable_until_msecs: String,
+    #[serde(default)]
     pub is_edit_eligible: bool,
+    #[serde(default)]
+    pub edits_remaining: Option<String>,
+}
+
+impl EditControlInitial {
+    pub(crate) fn edit_ids(&self) -> SResult<Vec<TweetId>> {
+        self.edit_tweet_ids
+            .iter()
+            .map(|id| {
+                id.parse::<TweetId>()
+                    .map_err(|why| TwitterBadRestId("Tweet RestID", why.to_string()))
+            })
+            .collect()
+    }
+
+    pub(crate) fn editable_until(&self) -> Option<DateTime<Utc>> {
+        self.editable_until_msecs
+            .parse::<i64>()
+            .ok()
+            .and_then(|msecs| Utc.timestamp_millis_opt(msecs).single())
+    }
+
+    pub(crate) fn edits_remaining(&self) -> Option<u32> {
+        self.edits_remaining
+            .as_deref()
+            .and_then(|remaining| remaining.parse::<u32>().ok())
+    }
+
+    // a tweet that was never edited only lists itself
+    pub(crate) fn history(&self, id: TweetId) -> SResult<Option<EditHistory>> {
+        let edit_ids = self.edit_ids()?;
+        if edit_ids.len() < 2 {
+            return Ok(None);
+        }
+        Ok(Some(EditHistory {
+            previous_ids: edit_ids
+                .into_iter()
+                .take_while(|edit| *edit != id)
+                .collect(),
+            editable_until: self.editable_until(),
+            edit_eligible: self.is_edit_eligible,
+        }))
+    }
+}
+
+#[cfg(feature = "scrape")]
+#[test]
+fn edit_history_from_fixture() {
+    // the second version of an edited tweet, the history sits under `edit_control_initial`
+    let control: EditControl = serde_json::from_str(
+        r#"{
+            "initial_tweet_id": "1626266563373326336",
+            "edit_control_initial": {
+                "edit_tweet_ids": ["1626266563373326336", "1626267076135362560"],
+                "editable_until_msecs": "1676751549000",
+                "is_edit_eligible": true,
+                "edits_remaining": "3"
+            }
+        }"#,
+    )
+    .unwrap();
+    let resolved = control.resolved();
+    assert_eq!(resolved.edits_remaining(), Some(3));
+
+    let history = resolved
+        .history(TweetId(1626267076135362560))
+        .unwrap()
+        .unwrap();
+    assert_eq!(history.previous_ids, vec![TweetId(1626266563373326336)]);
+    assert_eq!(
+        history.editable_until.map(|until| until.timestamp_millis()),
+        Some(1676751549000)
+    );
+    // the edit window closed long ago
+    assert!(!history.is_editable());
+
+    let original = resolved
+        .history(TweetId(1626266563373326336))
+        .unwrap()
+        .unwrap();
+    assert!(original.previous_ids.is_empty());
+
+    let unedited: EditControl = serde_json::from_str(
+        r#"{
+            "edit_tweet_ids": ["1626266563373326336"],
+            "editable_until_msecs": "1676751549000",
+            "is_edit_eligible": true,
+            "edits_remaining": "5"
+        }"#,
+    )
+    .unwrap();
+    assert_eq!(
+        unedited
+            .resolved()
+            .history(TweetId(1626266563373326336))
+            .unwrap(),
+        None
+    );
 }
 
 #[derive(
@@ -1086,10 +2820,265 @@ pub(crate) struct EditControl {
 )]
 #[serde(tag = "__typename")]
 pub(crate) enum TweetResults {
+    #[serde(rename(deserialize = "Tweet"))]
     Ok(TweetResultResult),
     Tombstone(TweetTombstone),
 }
 
+impl TweetResults {
+    pub(crate) fn ok(&self) -> Option<&TweetResultResult> {
+        match self {
+            TweetResults::Ok(trr) => Some(trr),
+            TweetResults::Tombstone(_) => None,
+        }
+    }
+
+    pub(crate) fn tombstone(&self) -> Option<&TweetTombstone> {
+        match self {
+            TweetResults::Ok(_) => None,
+            TweetResults::Tombstone(tomb) => Some(tomb),
+        }
+    }
+
+    // the id to look the tweet up with, `None` for tombstones and tweets without a usable id
+    pub(crate) fn rest_id_or_skip(&self) -> Option<&str> {
+        self.ok()
+            .map(|trr| trr.rest_id.as_str())
+            .filter(|id| !id.is_empty() && *id != "0")
+    }
+}
+
+#[test]
+fn tombstone_results_are_skipped() {
+    let tomb = TweetResults::Tombstone(TweetTombstone {
+        tombstone: TombstoneStone {
+            __typename: "TextTombstone".to_string(),
+            text: TombstoneText {
+                rtl: false,
+                text: "This Tweet was deleted by the Tweet author.".to_string(),
+            },
+        },
+    });
+    assert!(tomb.ok().is_none());
+    assert!(tomb.tombstone().is_some());
+    assert_eq!(tomb.rest_id_or_skip(), None);
+}
+
+// a tweet the way timelines send it, by `author` (see `user_results_fixture`). `legacy` is merged
+// over the fields every tweet has
+#[cfg(all(test, feature = "scrape"))]
+pub(crate) fn tweet_results_fixture(
+    id: u64,
+    author: u64,
+    legacy: serde_json::Value,
+) -> serde_json::Value {
+    let mut results = serde_json::json!({
+        "__typename": "Tweet",
+        "rest_id": id.to_string(),
+        "core": {"user_results": {"result": crate::user::user_results_fixture(author)}},
+        "hasModeratedReplies": false,
+        "is_translatable": false,
+        "legacy": {
+            "id_str": id.to_string(),
+            "created_at": "Wed Oct 10 20:19:24 +0000 2018",
+            "conversation_id_str": id.to_string(),
+            "entities": {"user_mentions": [], "urls": [], "hashtags": []},
+            "favourite_count": 0,
+            "is_quote_status": false,
+            "possibly_sensitive": false,
+            "quote_count": 0,
+            "reply_count": 0,
+            "retweet_count": 0,
+            "source": "<a href=\"https://mobile.twitter.com\" rel=\"nofollow\">Twitter Web App</a>",
+            "full_text": "just setting up my twttr",
+            "user_id_str": author.to_string(),
+            "display_text_range": [0, 24],
+            "self_thread": {"id_str": id.to_string()},
+        },
+    });
+    if let (Some(base), serde_json::Value::Object(legacy)) =
+        (results["legacy"].as_object_mut(), legacy)
+    {
+        base.extend(legacy);
+    }
+    results
+}
+
+// a `tweet-` timeline entry holding `results`
+#[cfg(all(test, feature = "scrape"))]
+pub(crate) fn tweet_entry_fixture(id: u64, results: serde_json::Value) -> serde_json::Value {
+    serde_json::json!({
+        "entryId": format!("tweet-{id}"),
+        "content": {
+            "entryType": "TimelineTimelineItem",
+            "__typename": "TimelineTimelineItem",
+            "itemContent": {
+                "itemType": "TimelineTweet",
+                "__typename": "TimelineTweet",
+                "tweet_results": results,
+            },
+        },
+    })
+}
+
+#[cfg(all(test, feature = "scrape"))]
+pub(crate) fn cursor_entry_fixture(cursor_type: &str, value: &str) -> serde_json::Value {
+    serde_json::json!({
+        "entryId": format!("cursor-{}-0", cursor_type.to_lowercase()),
+        "content": {
+            "entryType": "TimelineTimelineItem",
+            "__typename": "TimelineTimelineItem",
+            "itemContent": {
+                "itemType": "TimelineTimelineCursor",
+                "__typename": "TimelineTimelineCursor",
+                "value": value,
+                "cursorType": cursor_type,
+            },
+        },
+    })
+}
+
+#[cfg(all(test, feature = "scrape"))]
+fn replies_from_pages(
+    canned: Vec<String>,
+    max_replies: Option<usize>,
+) -> (Replies, Vec<TweetId>, usize) {
+    crate::scrape::dry_run(canned, |scraper| async move {
+        let replies = Tweet::replies(&scraper, TweetId(1700000000000000000), max_replies)
+            .await
+            .unwrap();
+        let mut ids = replies
+            .tweets
+            .iter()
+            .map(|tweet| tweet.id)
+            .collect::<Vec<_>>();
+        ids.sort();
+        (replies, ids, scraper.stop_dry_run().len())
+    })
+}
+
+#[cfg(feature = "scrape")]
+#[test]
+fn broken_replies_are_skipped() {
+    let entry = |id: u64, legacy: serde_json::Value| {
+        tweet_entry_fixture(id, tweet_results_fixture(id, 7, legacy))
+    };
+    let (_, ids, _) = replies_from_pages(
+        vec![thread_page(vec![
+            entry(1700000000000000000, serde_json::json!({})),
+            entry(1700000000000000001, serde_json::json!({})),
+            entry(
+                1700000000000000002,
+                serde_json::json!({"created_at": "yesterday"}),
+            ),
+        ])],
+        None,
+    );
+    assert_eq!(ids, vec![TweetId(1700000000000000001)]);
+}
+
+#[cfg(feature = "scrape")]
+#[test]
+fn replies_stop_at_max_replies() {
+    let entry =
+        |id: u64| tweet_entry_fixture(id, tweet_results_fixture(id, 7, serde_json::json!({})));
+    let page = || {
+        thread_page(vec![
+            entry(1700000000000000000),
+            entry(1700000000000000001),
+            entry(1700000000000000002),
+            entry(1700000000000000003),
+            cursor_entry_fixture("Bottom", "second"),
+        ])
+    };
+
+    let (replies, ids, requests) = replies_from_pages(vec![page()], Some(2));
+    assert_eq!(
+        ids,
+        vec![TweetId(1700000000000000001), TweetId(1700000000000000002)]
+    );
+    assert!(replies.more_remaining);
+    assert_eq!(requests, 1);
+
+    // filling up on the last reply of a page still leaves the next page unseen
+    let (replies, ids, requests) = replies_from_pages(vec![page()], Some(3));
+    assert_eq!(ids.len(), 3);
+    assert!(replies.more_remaining);
+    assert_eq!(requests, 1);
+}
+
+// a `TweetDetail` page holding `entries`
+#[cfg(all(test, feature = "scrape"))]
+fn thread_page(entries: Vec<serde_json::Value>) -> String {
+    serde_json::json!({
+        "errors": [],
+        "data": {"threaded_conversation_with_injections_v2": {"instructions": [
+            {"type": "TimelineAddEntries", "entries": entries},
+        ]}},
+    })
+    .to_string()
+}
+
+#[cfg(feature = "scrape")]
+#[test]
+fn retweets_are_attributed_to_the_original_author() {
+    use crate::scrape::dry_run;
+
+    let retweet = 1700000000000000002;
+    let original = 1700000000000000001;
+    let thread = |original_results: serde_json::Value| {
+        let results = tweet_results_fixture(
+            retweet,
+            7,
+            serde_json::json!({"retweeted_status_result": {"result": original_results}}),
+        );
+        vec![thread_page(vec![tweet_entry_fixture(retweet, results)])]
+    };
+    let parse = |canned: Vec<String>| {
+        dry_run(canned, |scraper| async move {
+            Tweet::parse_thread(&scraper, retweet, None).await.unwrap()
+        })
+    };
+
+    let (tweets, users) = parse(thread(tweet_results_fixture(
+        original,
+        8,
+        serde_json::json!({}),
+    )));
+    assert_eq!(tweets.len(), 1);
+    assert!(tweets[0].is_retweet());
+    assert_eq!(tweets[0].author_id(), Some(UserId(8)));
+    assert_eq!(tweets[0].retweeter_id(), Some(UserId(7)));
+    let mut user_ids = users.iter().map(|user| user.id).collect::<Vec<_>>();
+    user_ids.sort();
+    assert_eq!(user_ids, vec![UserId(7), UserId(8)]);
+
+    // an original author that fails to parse leaves the retweet and the retweeter alone
+    let mut broken = tweet_results_fixture(original, 8, serde_json::json!({}));
+    broken["core"]["user_results"]["result"]["rest_id"] = "eight".into();
+    let (tweets, users) = parse(thread(broken));
+    assert_eq!(tweets.len(), 1);
+    assert_eq!(tweets[0].retweeter_id(), Some(UserId(7)));
+    assert_eq!(
+        users.iter().map(|user| user.id).collect::<Vec<_>>(),
+        vec![UserId(7)]
+    );
+}
+
+#[cfg(feature = "scrape")]
+#[test]
+fn tweet_from_fixture() {
+    let results = serde_json::from_value::<TweetResults>(tweet_results_fixture(
+        1050118621198921728,
+        12,
+        serde_json::json!({}),
+    ))
+    .unwrap();
+    let tweet = Tweet::new_from_entry(&results).unwrap();
+    assert_eq!(tweet.id, 1050118621198921728);
+    assert_eq!(tweet.posted_id, Some(UserId(12)));
+}
+
 #[derive(
     Clone,
     Debug,
@@ -1157,11 +3146,148 @@ pub(crate) struct TweetResultResult {
     pub core: TwtRsltCore,
     pub card: Option<TwtCard>,
     pub vibe: Option<TwtVibe>,
+    // missing on tweets from before editing existed
+    #[serde(default)]
     pub edit_control: EditControl,
     pub legacy: TweetLegacy,
     #[serde(rename = "hasModeratedReplies")]
     pub has_moderated_replies: bool,
     pub is_translatable: bool,
+    #[serde(default)]
+    pub article: Option<TwtArticle>,
+}
+
+impl TweetResultResult {
+    pub(crate) fn retweeted_author(&self) -> Option<&TwtUsrResult> {
+        self.legacy
+            .retweeted_status_result
+            .as_deref()?
+            .result
+            .ok()
+            .map(|original| &original.core.user_results.result)
+    }
+}
+
+#[derive(
+    Clone,
+    Debug,
+    Hash,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+    Archive,
+    rkyv::Serialize,
+    rkyv::Deserialize,
+)]
+pub(crate) struct TwtArticle {
+    pub article_results: TwtArticleResults,
+}
+
+impl TwtArticle {
+    // prefers the full text, only the preview comes back on some timelines
+    pub(crate) fn to_article(&self) -> Option<Article> {
+        let article = self.article_results.result.as_ref()?;
+        Some(Article {
+            title: article.title.clone(),
+            body: article
+                .plain_text
+                .clone()
+                .unwrap_or_else(|| article.preview_text.clone()),
+            cover_image: article
+                .cover_media
+                .as_ref()
+                .map(|cover| cover.media_info.original_img_url.clone()),
+        })
+    }
+}
+
+#[derive(
+    Clone,
+    Debug,
+    Hash,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+    Archive,
+    rkyv::Serialize,
+    rkyv::Deserialize,
+)]
+pub(crate) struct TwtArticleResults {
+    pub result: Option<TwtArticleResult>,
+}
+
+#[derive(
+    Clone,
+    Debug,
+    Hash,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+    Archive,
+    rkyv::Serialize,
+    rkyv::Deserialize,
+)]
+pub(crate) struct TwtArticleResult {
+    pub title: String,
+    #[serde(default)]
+    pub preview_text: String,
+    pub plain_text: Option<String>,
+    pub cover_media: Option<TwtArticleCoverMedia>,
+}
+
+#[derive(
+    Clone,
+    Debug,
+    Hash,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+    Archive,
+    rkyv::Serialize,
+    rkyv::Deserialize,
+)]
+pub(crate) struct TwtArticleCoverMedia {
+    pub media_info: TwtArticleMediaInfo,
+}
+
+#[derive(
+    Clone,
+    Debug,
+    Hash,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+    Archive,
+    rkyv::Serialize,
+    rkyv::Deserialize,
+)]
+pub(crate) struct TwtArticleMediaInfo {
+    pub original_img_url: String,
+}
+
+#[cfg(feature = "scrape")]
+#[test]
+fn article_from_payload() {
+    let raw = serde_json::from_str::<TwtArticle>(
+        r#"{"article_results":{"result":{"rest_id":"1745000000000000000","id":"QXJ0aWNsZUVudGl0eToxNzQ1","title":"Notes on archiving","preview_text":"Things get lost.","plain_text":"Things get lost. Here is how we keep them.","cover_media":{"id":"QXBpTWVkaWE6","media_key":"3_1745000000000000001","media_id":"1745000000000000001","media_info":{"__typename":"ApiImage","original_img_height":900,"original_img_width":1600,"original_img_url":"https://pbs.twimg.com/media/GEexample.jpg"}}}}}"#,
+    )
+    .unwrap();
+    assert_eq!(
+        raw.to_article(),
+        Some(Article {
+            title: "Notes on archiving".to_string(),
+            body: "Things get lost. Here is how we keep them.".to_string(),
+            cover_image: Some("https://pbs.twimg.com/media/GEexample.jpg".to_string()),
+        })
+    );
+
+    let raw = serde_json::from_str::<TwtArticle>(r#"{"article_results":{}}"#).unwrap();
+    assert_eq!(raw.to_article(), None);
 }
 
 #[derive(
@@ -1248,8 +3374,13 @@ pub(crate) struct TwtCardBindV {
     rkyv::Serialize,
     rkyv::Deserialize,
 )]
+#[cfg_attr(feature = "validation", archive(check_bytes))]
 pub struct CardValue {
+    // empty for values that aren't strings, e.g. images
+    #[serde(default)]
     pub string_value: String,
+    #[serde(default)]
+    pub boolean_value: Option<bool>,
     pub r#type: String,
 }
 
@@ -1270,6 +3401,8 @@ pub(crate) struct TweetLegacy {
     pub created_at: String,
     pub conversation_id_str: String,
     pub entities: TweetEntry,
+    // only present on tweets with media
+    #[serde(default)]
     pub extended_entities: TweetExtEntry,
     pub favourite_count: u32,
     pub is_quote_status: bool,
@@ -1277,6 +3410,11 @@ pub(crate) struct TweetLegacy {
     pub quote_count: u32,
     pub reply_count: u32,
     pub retweet_count: u32,
+    #[serde(default)]
+    pub bookmark_count: Option<u64>,
+    // viewer perspective, only sent to authenticated sessions
+    #[serde(default)]
+    pub bookmarked: Option<bool>,
     pub source: String,
     pub full_text: String,
     pub user_id_str: String,
@@ -1286,8 +3424,59 @@ pub(crate) struct TweetLegacy {
     pub in_reply_to_user_id_str: Option<String>,
     pub quoted_status_id_str: Option<String>,
     pub self_thread: TweetSelfThread,
+    // the retweeted tweet in full. never archived, it would make the type recursive
+    #[serde(default)]
+    #[with(rkyv::with::Skip)]
+    pub retweeted_status_result: Option<Box<TweetRetweetedStatus>>,
     pub place: Option<Place>,
+    #[serde(default)]
+    pub coordinates: Option<TwtGeoPoint>,
+    #[serde(default)]
+    pub geo: Option<TwtGeoPoint>,
     pub collab_control: Option<CollabControl>,
+    #[serde(flatten)]
+    pub withheld: TweetWithheld,
+}
+
+#[derive(
+    Clone,
+    Debug,
+    Hash,
+    PartialEq,
+    Eq,
+    Default,
+    Serialize,
+    Deserialize,
+    Archive,
+    rkyv::Serialize,
+    rkyv::Deserialize,
+)]
+pub(crate) struct TweetWithheld {
+    pub withheld_scope: Option<String>,
+    #[serde(default)]
+    pub withheld_copyright: bool,
+    pub withheld_text: Option<String>,
+    #[serde(default)]
+    pub withheld_in_countries: Vec<String>,
+}
+
+#[cfg(feature = "scrape")]
+#[test]
+fn withheld_copyright_tweet() {
+    let withheld = serde_json::from_str::<TweetWithheld>(
+        r#"{"withheld_copyright":true,"withheld_in_countries":["XY"],"withheld_scope":"status","withheld_text":"This Tweet from @example has been withheld in response to a report from the copyright holder."}"#,
+    )
+    .unwrap();
+    assert_eq!(
+        withheld.withheld_scope.as_deref().map(WithheldScope::from),
+        Some(WithheldScope::Status)
+    );
+    assert!(withheld.withheld_copyright);
+    assert!(withheld.withheld_text.is_some());
+    assert_eq!(withheld.withheld_in_countries, vec!["XY".to_string()]);
+
+    let withheld = serde_json::from_str::<TweetWithheld>("{}").unwrap();
+    assert_eq!(withheld, TweetWithheld::default());
 }
 
 #[derive(
@@ -1338,6 +3527,11 @@ pub(crate) struct TweetSelfThread {
     pub id_str: String,
 }
 
+#[derive(Clone, Debug, Hash, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct TweetRetweetedStatus {
+    pub result: TweetResults,
+}
+
 #[derive(
     Clone,
     Debug,
@@ -1360,6 +3554,7 @@ pub(crate) struct TweetConversationControl {
     Hash,
     PartialEq,
     Eq,
+    Default,
     Serialize,
     Deserialize,
     Archive,
@@ -1367,6 +3562,7 @@ pub(crate) struct TweetConversationControl {
     rkyv::Deserialize,
 )]
 pub(crate) struct TweetExtEntry {
+    #[serde(default)]
     pub media: Vec<TweetEntryMedia>,
 }
 
@@ -1383,6 +3579,7 @@ pub(crate) struct TweetExtEntry {
     rkyv::Deserialize,
 )]
 pub(crate) struct TweetEntry {
+    #[serde(default)]
     pub media: Vec<TweetEntryMedia>,
     pub user_mentions: Vec<TweetUserMentions>,
     pub urls: Vec<TweetEntryUrls>,
@@ -1426,6 +3623,67 @@ pub(crate) struct TweetEntryMedia {
     pub ext_alt_text: Option<String>,
     #[serde(rename = "mediaStats")]
     pub media_stats: Option<TweetMediaStats>,
+    #[serde(default)]
+    pub original_info: Option<TweetMediaOriginalInfo>,
+    // videos and gifs only
+    #[serde(default)]
+    pub video_info: Option<TweetVideoInfo>,
+}
+
+#[derive(
+    Clone,
+    Debug,
+    Hash,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+    Archive,
+    rkyv::Serialize,
+    rkyv::Deserialize,
+)]
+pub(crate) struct TweetVideoInfo {
+    #[serde(default)]
+    pub duration_millis: Option<u64>,
+    #[serde(default)]
+    pub variants: Vec<TweetVideoVariant>,
+}
+
+#[derive(
+    Clone,
+    Debug,
+    Hash,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+    Archive,
+    rkyv::Serialize,
+    rkyv::Deserialize,
+)]
+pub(crate) struct TweetVideoVariant {
+    #[serde(default)]
+    pub bitrate: Option<u32>,
+    pub content_type: String,
+    pub url: String,
+}
+
+#[derive(
+    Clone,
+    Debug,
+    Hash,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+    Archive,
+    rkyv::Serialize,
+    rkyv::Deserialize,
+)]
+pub(crate) struct TweetMediaOriginalInfo {
+    // only photos come with these
+    #[serde(default)]
+    pub focus_rects: Vec<Rect>,
 }
 
 #[derive(
@@ -1474,6 +3732,7 @@ pub(crate) struct TweetEntryUrls {
     rkyv::Serialize,
     rkyv::Deserialize,
 )]
+#[cfg_attr(feature = "validation", archive(check_bytes))]
 pub struct TweetUserMentions {
     pub id_str: String,
     pub name: String,
@@ -1524,8 +3783,9 @@ pub(crate) struct UserResults {
     rkyv::Serialize,
     rkyv::Deserialize,
 )]
+#[serde(rename_all = "camelCase")]
 pub(crate) struct Cursor {
-    #[serde(rename = "entryId")]
+    #[serde(alias = "entry_id")]
     pub entry_id: String,
     pub content: CursorContent,
 }
@@ -1542,11 +3802,13 @@ pub(crate) struct Cursor {
     rkyv::Serialize,
     rkyv::Deserialize,
 )]
+#[serde(rename_all = "camelCase")]
 pub(crate) struct CursorContent {
-    #[serde(rename = "entryType")]
+    #[serde(alias = "entry_type")]
     pub entry_type: String,
+    #[serde(rename = "__typename")]
     pub __typename: String,
-    #[serde(rename = "itemContent")]
+    #[serde(alias = "item_content")]
     pub item_content: CursorItemContent,
 }
 
@@ -1562,15 +3824,53 @@ pub(crate) struct CursorContent {
     rkyv::Serialize,
     rkyv::Deserialize,
 )]
+#[serde(rename_all = "camelCase")]
 pub(crate) struct CursorItemContent {
-    #[serde(rename = "itemType")]
+    #[serde(alias = "item_type")]
     pub item_type: String,
+    #[serde(rename = "__typename")]
     pub __typename: String,
     pub value: String,
-    #[serde(rename = "cursorType")]
+    #[serde(alias = "cursor_type")]
     pub cursor_type: String,
 }
 
+#[cfg(feature = "scrape")]
+#[test]
+fn cursor_either_casing() {
+    let camel = serde_json::from_str::<Cursor>(
+        r#"{"entryId":"cursor-bottom-1","content":{"entryType":"TimelineTimelineCursor","__typename":"TimelineTimelineCursor","itemContent":{"itemType":"TimelineTimelineCursor","__typename":"TimelineTimelineCursor","value":"DAABCgAB","cursorType":"Bottom"}}}"#,
+    )
+    .unwrap();
+    let snake = serde_json::from_str::<Cursor>(
+        r#"{"entry_id":"cursor-bottom-1","content":{"entry_type":"TimelineTimelineCursor","__typename":"TimelineTimelineCursor","item_content":{"item_type":"TimelineTimelineCursor","__typename":"TimelineTimelineCursor","value":"DAABCgAB","cursor_type":"Bottom"}}}"#,
+    )
+    .unwrap();
+    assert_eq!(camel, snake);
+    assert_eq!(snake.content.item_content.cursor_type, "Bottom");
+}
+
+#[cfg(feature = "scrape")]
+#[test]
+fn detail_entries_by_id() {
+    let entries = serde_json::from_str::<Vec<Entry>>(
+        r#"[{"entryId":"tweet-1212092628029698048","sortIndex":"1","content":{"entryType":"TimelineTimelineItem","__typename":"TimelineTimelineItem","itemContent":{"itemType":"TimelineTweet","__typename":"TimelineTweet","tweet_results":{"__typename":"Tombstone","tombstone":{"__typename":"TextTombstone","text":{"rtl":false,"text":"This Tweet was deleted by the Tweet author."}}}}}},{"entryId":"cursor-bottom-1","sortIndex":"0","content":{"entryType":"TimelineTimelineItem","__typename":"TimelineTimelineItem","itemContent":{"itemType":"TimelineTimelineCursor","__typename":"TimelineTimelineCursor","value":"DAABCgAB","cursorType":"Bottom"}}}]"#,
+    )
+    .unwrap();
+    assert_eq!(entries[0].entry_id, "tweet-1212092628029698048");
+    assert!(matches!(entries[0].content, EntryVariant::Tweet(_)));
+    match &entries[1].content {
+        EntryVariant::Cursor(cursor) => {
+            assert_eq!(cursor.entry_id, "cursor-bottom-1");
+            assert_eq!(cursor.content.item_content.value, "DAABCgAB");
+        }
+        other => panic!("expected a cursor, got {other:?}"),
+    }
+
+    assert!(serde_json::from_str::<Entry>(r#"{"entryId":"label-1","content":{}}"#).is_err());
+    assert!(serde_json::from_str::<Entry>(r#"{"content":{}}"#).is_err());
+}
+
 #[derive(
     Clone,
     Debug,
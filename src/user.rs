@@ -1,137 +1,429 @@
-use crate::as_option;
-use crate::error::SResult;
 use crate::error::TwtScrapeError::{
-    TwitterBadRestId, TwitterBadTimeParse, TwitterJSONError, UserResultError,
+    BadJSONSchema, InvalidArchive, InvalidHandle, TwitterBadRestId, TwitterJSONError,
 };
+use crate::error::{SResult, ScrapeOutcome, TwitterErrorCode, TwitterWarning, TwtScrapeError};
+#[cfg(feature = "scrape")]
+use crate::graphql::GraphQlRequest;
+use crate::id::{TweetId, UserId};
 #[cfg(feature = "scrape")]
 use crate::scrape::Scraper;
-use chrono::{DateTime, Utc};
-use rkyv::Archive;
-use serde::{Deserialize, Deserializer, Serialize};
-use std::fmt::Display;
+use crate::{as_option, twitter_url_segments};
+use chrono::{DateTime, FixedOffset, Utc};
+#[cfg(feature = "scrape")]
+use futures::{future, stream, StreamExt};
+use rkyv::{AlignedVec, Archive};
+#[cfg(feature = "validation")]
+use rkyv::{Deserialize as _, Infallible};
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::fmt::{Display, Formatter};
 use std::hash::Hasher;
+use std::str::FromStr;
+#[cfg(feature = "scrape")]
+use tracing::warn;
 
 #[cfg(feature = "scrape")]
-pub const TWITTER_IGNORE_ERROR_CODE: i32 = 37;
+pub const TWITTER_IGNORE_ERROR_CODE: i32 = TwitterErrorCode::Ignore.code();
 // "Fri Oct 09 08:16:38 +0000 2015"
 #[cfg(feature = "scrape")]
 pub const JOINDATE_PARSE_STR: &str = "%a %b %d %T %z %Y";
 
 #[cfg(feature = "scrape")]
 pub fn twitter_request_url_handle(handle: &str) -> String {
-    format!("https://twitter.com/i/api/graphql/ptQPCD7NrFS_TW71Lq07nw/UserByScreenName?variables%3D%7B%22screen_name%22%3A%22{handle}%22%2C%22withSafetyModeUserFields%22%3Atrue%2C%22withSuperFollowsUserFields%22%3Atrue%7D&features=%7B%22responsive_web_twitter_blue_verified_badge_is_enabled%22%3Atrue%2C%22verified_phone_label_enabled%22%3Afalse%2C%22responsive_web_graphql_timeline_navigation_enabled%22%3Atrue%7D")
+    // the handle goes through the same encoding as every other variable
+    GraphQlRequest::new("ptQPCD7NrFS_TW71Lq07nw", "UserByScreenName")
+        .variable("screen_name", handle)
+        .variable("withSafetyModeUserFields", true)
+        .variable("withSuperFollowsUserFields", true)
+        .features([
+            (
+                "responsive_web_twitter_blue_verified_badge_is_enabled",
+                true,
+            ),
+            ("verified_phone_label_enabled", false),
+            ("responsive_web_graphql_timeline_navigation_enabled", true),
+        ])
+        .build()
+}
+
+#[cfg(feature = "scrape")]
+#[test]
+fn handle_url_query_string() {
+    let url = twitter_request_url_handle("jack");
+    assert!(url.starts_with(
+        "https://twitter.com/i/api/graphql/ptQPCD7NrFS_TW71Lq07nw/UserByScreenName?variables=%7B"
+    ));
+    assert!(url.contains("%22screen_name%22%3A%22jack%22"));
+    assert!(url.contains("&features=%7B"));
+    assert!(!url.contains("variables%3D"));
+}
+
+#[cfg(feature = "scrape")]
+pub fn twitter_request_url_user_id(id: UserId) -> String {
+    format!("https://twitter.com/i/api/graphql/GazOglcBvgLigl3ywt6b3Q/UserByRestId?variables=%7B%22userId%22%3A%22{id}%22%2C%22withSafetyModeUserFields%22%3Atrue%7D&features=%7B%22responsive_web_twitter_blue_verified_badge_is_enabled%22%3Atrue%2C%22verified_phone_label_enabled%22%3Afalse%2C%22responsive_web_graphql_timeline_navigation_enabled%22%3Atrue%7D")
+}
+
+#[cfg(feature = "scrape")]
+pub const USER_BATCH_SIZE: usize = 100;
+
+#[cfg(feature = "scrape")]
+pub fn twitter_request_url_user_ids(ids: &[UserId]) -> String {
+    let ids = ids
+        .iter()
+        .map(|id| format!("%22{id}%22"))
+        .collect::<Vec<String>>()
+        .join("%2C");
+    format!("https://twitter.com/i/api/graphql/GD4q8bBE2i6cqWw2iT74Gg/UsersByRestIds?variables=%7B%22userIds%22%3A%5B{ids}%5D%2C%22withSafetyModeUserFields%22%3Atrue%7D&features=%7B%22responsive_web_twitter_blue_verified_badge_is_enabled%22%3Atrue%2C%22verified_phone_label_enabled%22%3Afalse%2C%22responsive_web_graphql_timeline_navigation_enabled%22%3Atrue%7D")
+}
+
+#[cfg(feature = "scrape")]
+async fn follow_tco(scraper: &Scraper, tco: String) -> SResult<Option<String>> {
+    let redirect = scraper
+        .api_req_raw_request(scraper.make_get_req(tco))
+        .await?;
+    Ok(as_option!(redirect.url().to_string(), ""))
+}
+
+// twitter hands out empty or "0" ids for accounts it won't tell us about
+fn parse_rest_id(rest_id: &str) -> SResult<UserId> {
+    if rest_id.is_empty() || rest_id == "0" {
+        return Err(TwitterBadRestId("user", rest_id.to_string()));
+    }
+    rest_id.parse()
+}
+
+#[test]
+fn rest_id_validation() {
+    assert_eq!(parse_rest_id("783214").unwrap(), 783214);
+    assert_eq!(
+        parse_rest_id("1503055759638159366").unwrap(),
+        1503055759638159366
+    );
+    assert!(parse_rest_id("").is_err());
+    assert!(parse_rest_id("0").is_err());
+    assert!(parse_rest_id("VXNlcjo3ODMyMTQ=").is_err());
 }
 
 #[derive(
     Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Archive, rkyv::Serialize, rkyv::Deserialize,
 )]
+#[cfg_attr(feature = "validation", archive(check_bytes))]
+#[non_exhaustive]
 pub struct User {
-    pub id: u64,
+    pub id: UserId,
     pub avatar: Avatar,
     pub name: ProfileName,
     pub profile_stats: ProfileStats,
     pub additional_info: ProfileAdditionalInfo,
     pub bio: String,
-    pub pinned_tweet_id: Option<u64>,
+    pub pinned_tweet_id: Option<TweetId>,
     pub is_sensitive: bool,
     pub is_protected: bool,
+    pub restriction: RestrictionState,
+    // two letter country codes, "XX" for everywhere and "XY" for copyright complaints
+    pub withheld_in_countries: Vec<String>,
+}
+
+// builds a `User` without going through twitter, for tests and mocks. fields that aren't set
+// are empty, zero or `false`.
+#[derive(Clone, Debug)]
+pub struct UserBuilder {
+    user: User,
+}
+
+impl UserBuilder {
+    pub fn with_id(mut self, id: impl Into<UserId>) -> Self {
+        self.user.id = id.into();
+        self
+    }
+
+    pub fn with_handle(mut self, handle: impl Into<String>) -> Self {
+        self.user.name.handle = handle.into();
+        self
+    }
+
+    pub fn with_display_name(mut self, display: impl Into<String>) -> Self {
+        self.user.name.display = display.into();
+        self
+    }
+
+    pub fn with_bio(mut self, bio: impl Into<String>) -> Self {
+        self.user.bio = bio.into();
+        self
+    }
+
+    pub fn with_avatar(mut self, avatar: Avatar) -> Self {
+        self.user.avatar = avatar;
+        self
+    }
+
+    pub fn with_profile_stats(mut self, stats: ProfileStats) -> Self {
+        self.user.profile_stats = stats;
+        self
+    }
+
+    pub fn with_additional_info(mut self, info: ProfileAdditionalInfo) -> Self {
+        self.user.additional_info = info;
+        self
+    }
+
+    pub fn with_pinned_tweet_id(mut self, id: impl Into<TweetId>) -> Self {
+        self.user.pinned_tweet_id = Some(id.into());
+        self
+    }
+
+    pub fn with_sensitive(mut self, sensitive: bool) -> Self {
+        self.user.is_sensitive = sensitive;
+        self
+    }
+
+    pub fn with_protected(mut self, protected: bool) -> Self {
+        self.user.is_protected = protected;
+        self
+    }
+
+    pub fn with_restriction(mut self, restriction: RestrictionState) -> Self {
+        self.user.restriction = restriction;
+        self
+    }
+
+    pub fn with_withheld_in_countries(mut self, countries: Vec<String>) -> Self {
+        self.user.withheld_in_countries = countries;
+        self
+    }
+
+    pub fn build(self) -> User {
+        self.user
+    }
+}
+
+impl Default for UserBuilder {
+    fn default() -> Self {
+        UserBuilder {
+            user: User {
+                id: UserId(0),
+                avatar: Avatar::default(),
+                name: ProfileName::default(),
+                profile_stats: ProfileStats::default(),
+                additional_info: ProfileAdditionalInfo::default(),
+                bio: String::new(),
+                pinned_tweet_id: None,
+                is_sensitive: false,
+                is_protected: false,
+                restriction: RestrictionState::default(),
+                withheld_in_countries: vec![],
+            },
+        }
+    }
+}
+
+impl User {
+    pub fn builder() -> UserBuilder {
+        UserBuilder::default()
+    }
+}
+
+#[test]
+fn build_user() {
+    let user = User::builder()
+        .with_id(783214)
+        .with_handle("X")
+        .with_display_name("X")
+        .with_protected(true)
+        .build();
+    assert_eq!(user.id, 783214);
+    assert_eq!(user.name.handle, "X");
+    assert!(user.is_protected);
+    assert_eq!(user.restriction, RestrictionState::None);
+    assert!(user.additional_info.joined.is_none());
 }
 
 impl User {
     #[tracing::instrument]
     pub(crate) async fn from_result(scraper: &Scraper, result: TwtUsrResult) -> SResult<Self> {
-        if let TwtUsrResult::User(user) = result {
-            if user.rest_id.is_empty() || user.rest_id == "0" {
-                return Err(TwitterBadRestId("user", user.rest_id));
+        Self::from_result_offline(result)?
+            .with_resolved_website(scraper)
+            .await
+    }
+
+    // everything but following the t.co link of the website, no request is made
+    pub(crate) fn from_result_offline(result: TwtUsrResult) -> SResult<Self> {
+        let user = match result {
+            TwtUsrResult::User(user) => user,
+            TwtUsrResult::UserUnavailable(unavailable) => {
+                return Err(TwtScrapeError::UserUnavailable {
+                    reason: unavailable.reason,
+                    message: unavailable.unavailable_message.text,
+                })
             }
+        };
 
-            let website = {
-                let redirect = scraper
-                    .api_req_raw_request(scraper.make_get_req(user.legacy.url))
-                    .await?;
-                as_option!(redirect.url().to_string(), "")
-            };
+        let id = parse_rest_id(&user.rest_id)?;
 
-            let joined = DateTime::<Utc>::from(
-                DateTime::parse_from_str(&user.legacy.created, JOINDATE_PARSE_STR)
-                    .map_err(|why| TwitterBadTimeParse(why.to_string()))?,
-            );
+        let restriction = RestrictionState::from(&user.legacy);
+        let name = ProfileName::from_legacy(&user.legacy);
+        let profile_stats = ProfileStats::from_legacy(&user.legacy, user.is_blue_verified);
 
-            let birthday = match user.legacy_extended_profile {
-                Some(lep) => lep.birthdate,
-                None => None,
-            };
+        // the raw t.co link, see `with_resolved_website`
+        let website = as_option!(user.legacy.url, "");
 
-            let pinned = {
-                if user.legacy.pinned_tweet_ids_str.is_empty() {
-                    None
-                } else {
-                    user.legacy.pinned_tweet_ids_str[0].parse::<u64>().ok()
-                }
-            };
+        // a join date twitter mangled isn't worth losing the rest of the profile over
+        let joined_raw = user.legacy.created.clone();
+        let joined = match DateTime::parse_from_str(&joined_raw, JOINDATE_PARSE_STR) {
+            Ok(joined) => Some(joined.with_timezone(&Utc)),
+            Err(why) => {
+                warn!(user = %id, joined = joined_raw, error = %why, "Unparsable join date, leaving it out");
+                None
+            }
+        };
 
-            let affiliation = match user.affiliates_highlighted_label {
-                Some(affiliate) => Some(UserAffiliation {
-                    badge: affiliate.label.badge.url,
-                    url: affiliate.label.url.url,
-                    description: affiliate.label.description,
-                }),
-                None => None,
-            };
+        let birthday = match user.legacy_extended_profile {
+            Some(lep) => lep.birthdate,
+            None => None,
+        };
 
-            return Ok(Self {
-                id: user.rest_id.parse()?,
-                avatar: Avatar {
-                    url: user.legacy.profile_image_url_https,
-                    banner: user.legacy.profile_banner_url,
-                    is_nft: user.has_nft_avatar,
-                },
-                name: ProfileName {
-                    display: user.legacy.screen_name,
-                    handle: user.legacy.name,
-                },
-                profile_stats: ProfileStats {
-                    tweets: user.legacy.statuses_count,
-                    following: user.legacy.friends_count,
-                    followers: user.legacy.followers_count,
-                    likes: user.legacy.favourites_count,
-                    media_tweets: user.legacy.media_count,
-                    verified: user.legacy.verified,
-                    blue_verified: user.is_blue_verified,
-                },
-                additional_info: ProfileAdditionalInfo {
-                    affiliation,
-                    profession: user.professional,
-                    location: as_option!(user.legacy.location, "", "0"),
-                    website,
-                    joined,
-                    birthday,
-                },
-                bio: user.legacy.description,
-                pinned_tweet_id: pinned,
-                is_sensitive: user.legacy.possibly_sensitive,
-                is_protected: user.legacy.protected,
-            });
-        }
+        let pinned = {
+            if user.legacy.pinned_tweet_ids_str.is_empty() {
+                None
+            } else {
+                user.legacy.pinned_tweet_ids_str[0].parse::<TweetId>().ok()
+            }
+        };
 
-        Err(UserResultError)
+        let affiliation = match user.affiliates_highlighted_label {
+            Some(affiliate) => Some(UserAffiliation {
+                badge: affiliate.label.badge.url,
+                url: affiliate.label.url.url,
+                description: affiliate.label.description,
+            }),
+            None => None,
+        };
+
+        Ok(Self {
+            id,
+            avatar: Avatar {
+                url: user.legacy.profile_image_url_https,
+                banner: user.legacy.profile_banner_url,
+                is_nft: user.has_nft_avatar,
+            },
+            name,
+            profile_stats,
+            additional_info: ProfileAdditionalInfo {
+                affiliation,
+                profession: user.professional,
+                location: as_option!(user.legacy.location, "", "0"),
+                website,
+                joined,
+                joined_raw,
+                birthday,
+            },
+            bio: user.legacy.description,
+            pinned_tweet_id: pinned,
+            is_sensitive: user.legacy.possibly_sensitive,
+            is_protected: user.legacy.protected,
+            restriction,
+            withheld_in_countries: user.legacy.withheld_in_countries,
+        })
     }
 
     #[tracing::instrument]
     pub async fn new(scraper: &Scraper, handle: impl AsRef<str>) -> SResult<Self> {
+        let handle = normalize_handle(handle.as_ref())?;
+        let req = scraper
+            .api_req::<UserRequest>(scraper.make_get_req(twitter_request_url_handle(&handle)))
+            .await?;
+        Self::from_request(scraper, req).await
+    }
+
+    // the id stays the same when the handle is renamed, and we skip resolving the handle
+    #[tracing::instrument]
+    pub async fn by_id(scraper: &Scraper, id: UserId) -> SResult<Self> {
         let req = scraper
-            .api_req::<UserRequest>(
-                scraper.make_get_req(twitter_request_url_handle(handle.as_ref())),
-            )
+            .api_req::<UserRequest>(scraper.make_get_req(twitter_request_url_user_id(id)))
             .await?;
-        // check for errors
-        if let Some(why) = req.errors.first() {
-            if why.code != TWITTER_IGNORE_ERROR_CODE {
-                return Err(TwitterJSONError(why.code, why.message.clone()));
+        Self::from_request(scraper, req).await
+    }
+
+    // follows the t.co link of a user fetched without `ScraperBuilder::with_website_resolution`,
+    // so bulk lookups can skip the extra request and only pay it for the users that need it
+    #[tracing::instrument]
+    pub async fn resolve_website(&mut self, scraper: &Scraper) -> SResult<Option<&str>> {
+        let tco = self
+            .additional_info
+            .website
+            .clone()
+            .filter(|website| website.starts_with("https://t.co/"));
+        if let Some(tco) = tco {
+            self.additional_info.website = follow_tco(scraper, tco).await?;
+        }
+        Ok(self.additional_info.website.as_deref())
+    }
+
+    // following the t.co link costs a request per user, only done when resolving was asked for
+    async fn with_resolved_website(mut self, scraper: &Scraper) -> SResult<Self> {
+        if scraper.resolves_websites() {
+            self.resolve_website(scraper).await?;
+        }
+        Ok(self)
+    }
+
+    async fn from_request(scraper: &Scraper, req: UserRequest) -> SResult<Self> {
+        req.into_user()?.with_resolved_website(scraper).await
+    }
+
+    // chunks are fetched concurrently, a failed chunk ends up in the errors with its ids instead
+    // of failing the batch. once cancelled no new chunks are started, the ones already sent are
+    // still collected.
+    #[tracing::instrument(skip(ids))]
+    pub async fn by_ids(scraper: &Scraper, ids: &[UserId]) -> SResult<ScrapeOutcome<Vec<Self>>> {
+        let chunk_requests = stream::iter(ids.chunks(USER_BATCH_SIZE))
+            .take_while(|_| future::ready(!scraper.is_cancelled()))
+            .map(|chunk| async move {
+                let request = scraper
+                    .api_req::<UsersByIdsRequest>(
+                        scraper.make_get_req(twitter_request_url_user_ids(chunk)),
+                    )
+                    .await;
+                (chunk, request)
+            })
+            .buffer_unordered(scraper.max_concurrency())
+            .collect::<Vec<_>>()
+            .await;
+
+        let mut users = Vec::with_capacity(ids.len());
+
+        let mut errors = Vec::new();
+
+        for (chunk, request) in chunk_requests {
+            let request = match request {
+                Ok(req) => req,
+                Err(why) => {
+                    warn!(
+                        error = %why,
+                        chunk_len = chunk.len(),
+                        "Failed to get user chunk. Skipping..."
+                    );
+                    let ids = chunk.iter().map(ToString::to_string).collect::<Vec<_>>();
+                    errors.push((format!("users {}", ids.join(",")), why));
+                    continue;
+                }
+            };
+
+            for usr in request.data.users {
+                match Self::from_result(scraper, usr.result).await {
+                    Ok(user) => users.push(user),
+                    Err(why) => {
+                        warn!(error = %why, "Failed to get data. Skipping...")
+                    }
+                }
             }
         }
 
-        Self::from_result(scraper, req.data.user.result).await
+        users.shrink_to_fit();
+        Ok(ScrapeOutcome {
+            data: users,
+            errors,
+        })
     }
 }
 
@@ -141,6 +433,52 @@ impl std::hash::Hash for User {
     }
 }
 
+impl User {
+    pub fn to_archive_bytes(&self) -> SResult<AlignedVec> {
+        rkyv::to_bytes::<_, 1024>(self).map_err(|why| InvalidArchive(why.to_string()))
+    }
+
+    // validated before anything is deserialized, so corrupt files fail cleanly
+    #[cfg(feature = "validation")]
+    pub fn from_archive_bytes(bytes: &[u8]) -> SResult<Self> {
+        let archived = rkyv::check_archived_root::<Self>(bytes)
+            .map_err(|why| InvalidArchive(why.to_string()))?;
+        archived
+            .deserialize(&mut Infallible)
+            .map_err(|_| InvalidArchive("User".to_string()))
+    }
+}
+
+#[cfg(feature = "scrape")]
+impl User {
+    pub fn to_json(&self) -> SResult<String> {
+        serde_json::to_string(self).map_err(|why| BadJSONSchema("User", why.to_string()))
+    }
+
+    pub fn from_json(json: &str) -> SResult<Self> {
+        serde_json::from_str(json).map_err(|why| BadJSONSchema("User", why.to_string()))
+    }
+
+    // downloads the avatar and banner next to the profile. a failed image download is logged and
+    // left as `None` so the profile itself still gets archived.
+    #[tracing::instrument]
+    pub async fn archive(&self, scraper: &Scraper) -> SResult<UserArchive> {
+        let avatar = as_option!(self.avatar.url.as_str(), "");
+        let banner = as_option!(self.avatar.banner.as_str(), "");
+
+        let (avatar_bytes, banner_bytes) = tokio::join!(
+            download_profile_image(scraper, avatar),
+            download_profile_image(scraper, banner)
+        );
+
+        Ok(UserArchive {
+            user: self.clone(),
+            avatar_bytes,
+            banner_bytes,
+        })
+    }
+}
+
 #[derive(
     Clone,
     Debug,
@@ -153,6 +491,135 @@ impl std::hash::Hash for User {
     rkyv::Serialize,
     rkyv::Deserialize,
 )]
+pub struct UserArchive {
+    pub user: User,
+    pub avatar_bytes: Option<Vec<u8>>,
+    pub banner_bytes: Option<Vec<u8>>,
+}
+
+#[cfg(feature = "scrape")]
+async fn download_profile_image(scraper: &Scraper, url: Option<&str>) -> Option<Vec<u8>> {
+    let url = url?;
+    match scraper.download_bytes(url).await {
+        Ok(bytes) => Some(bytes),
+        Err(why) => {
+            warn!(error = %why, url, "Failed to download profile image. Skipping...");
+            None
+        }
+    }
+}
+
+// `User` has no ordering of its own, `Hash` only looks at the id. these ascend, so
+// `users.sort_by(|a, b| User::cmp_by_followers(b, a))` puts the biggest accounts first.
+impl User {
+    pub fn cmp_by_followers(a: &User, b: &User) -> Ordering {
+        a.profile_stats.followers.cmp(&b.profile_stats.followers)
+    }
+
+    pub fn cmp_by_following(a: &User, b: &User) -> Ordering {
+        a.profile_stats.following.cmp(&b.profile_stats.following)
+    }
+
+    pub fn cmp_by_tweets(a: &User, b: &User) -> Ordering {
+        a.profile_stats.tweets.cmp(&b.profile_stats.tweets)
+    }
+
+    pub fn cmp_by_likes(a: &User, b: &User) -> Ordering {
+        a.profile_stats.likes.cmp(&b.profile_stats.likes)
+    }
+
+    pub fn cmp_by_media_tweets(a: &User, b: &User) -> Ordering {
+        a.profile_stats
+            .media_tweets
+            .cmp(&b.profile_stats.media_tweets)
+    }
+
+    pub fn cmp_by_joined(a: &User, b: &User) -> Ordering {
+        a.additional_info.joined.cmp(&b.additional_info.joined)
+    }
+}
+
+impl User {
+    // `self` is the older snapshot. only meant for two snapshots of the same account, the id
+    // isn't compared
+    pub fn diff(&self, other: &User) -> UserDiff {
+        fn changed<T: PartialEq + Clone>(old: &T, new: &T) -> Option<(T, T)> {
+            (old != new).then(|| (old.clone(), new.clone()))
+        }
+        let delta = |old: u32, new: u32| i64::from(new) - i64::from(old);
+
+        UserDiff {
+            handle: changed(&self.name.handle, &other.name.handle),
+            display_name: changed(&self.name.display, &other.name.display),
+            bio: changed(&self.bio, &other.bio),
+            location: changed(
+                &self.additional_info.location,
+                &other.additional_info.location,
+            ),
+            website: changed(
+                &self.additional_info.website,
+                &other.additional_info.website,
+            ),
+            avatar: changed(&self.avatar.url, &other.avatar.url),
+            banner: changed(&self.avatar.banner, &other.avatar.banner),
+            pinned_tweet_id: changed(&self.pinned_tweet_id, &other.pinned_tweet_id),
+            is_protected: changed(&self.is_protected, &other.is_protected),
+            verified: changed(&self.profile_stats.verified, &other.profile_stats.verified),
+            blue_verified: changed(
+                &self.profile_stats.blue_verified,
+                &other.profile_stats.blue_verified,
+            ),
+            restriction: changed(&self.restriction, &other.restriction),
+            followers_delta: delta(self.profile_stats.followers, other.profile_stats.followers),
+            following_delta: delta(self.profile_stats.following, other.profile_stats.following),
+            tweets_delta: delta(self.profile_stats.tweets, other.profile_stats.tweets),
+            likes_delta: delta(self.profile_stats.likes, other.profile_stats.likes),
+        }
+    }
+}
+
+// changes between two snapshots of a user, see `User::diff`. changed fields hold
+// `(old, new)`, the counters are `new - old`
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UserDiff {
+    pub handle: Option<(String, String)>,
+    pub display_name: Option<(String, String)>,
+    pub bio: Option<(String, String)>,
+    pub location: Option<(Option<String>, Option<String>)>,
+    pub website: Option<(Option<String>, Option<String>)>,
+    pub avatar: Option<(String, String)>,
+    pub banner: Option<(String, String)>,
+    pub pinned_tweet_id: Option<(Option<TweetId>, Option<TweetId>)>,
+    pub is_protected: Option<(bool, bool)>,
+    pub verified: Option<(bool, bool)>,
+    pub blue_verified: Option<(bool, bool)>,
+    pub restriction: Option<(RestrictionState, RestrictionState)>,
+    pub followers_delta: i64,
+    pub following_delta: i64,
+    pub tweets_delta: i64,
+    pub likes_delta: i64,
+}
+
+impl UserDiff {
+    pub fn is_empty(&self) -> bool {
+        *self == UserDiff::default()
+    }
+}
+
+#[derive(
+    Clone,
+    Debug,
+    Hash,
+    PartialEq,
+    Eq,
+    Default,
+    Serialize,
+    Deserialize,
+    Archive,
+    rkyv::Serialize,
+    rkyv::Deserialize,
+)]
+#[cfg_attr(feature = "validation", archive(check_bytes))]
 pub struct Avatar {
     pub url: String,
     pub banner: String,
@@ -165,17 +632,66 @@ pub struct Avatar {
     Hash,
     PartialEq,
     Eq,
+    Default,
     Serialize,
     Deserialize,
     Archive,
     rkyv::Serialize,
     rkyv::Deserialize,
 )]
+#[cfg_attr(feature = "validation", archive(check_bytes))]
 pub struct ProfileName {
     pub display: String,
     pub handle: String,
 }
 
+impl ProfileName {
+    // twitter calls the handle `screen_name` and the display name `name`
+    pub(crate) fn from_legacy(legacy: &UserLegacy) -> Self {
+        ProfileName {
+            display: legacy.name.clone(),
+            handle: legacy.screen_name.clone(),
+        }
+    }
+}
+
+#[cfg(feature = "scrape")]
+#[test]
+fn profile_name_from_legacy() {
+    let legacy = serde_json::from_str::<UserLegacy>(
+        r#"{"created_at":"Sat Dec 14 04:35:55 +0000 2013","default_profile":false,"default_profile_image":false,"description":"The voice of the X Dev team","favourites_count":2960,"followers_count":512292,"friends_count":1950,"has_custom_timelines":true,"is_translator":false,"listed_count":1777,"location":"127.0.0.1","media_count":820,"name":"Developers of X","normal_followers_count":512292,"pinned_tweet_ids_str":["1770153912013615285"],"possibly_sensitive":false,"profile_banner_url":"https://pbs.twimg.com/profile_banners/2244994945/1690213128","profile_image_url_https":"https://pbs.twimg.com/profile_images/1683501992314798080/xl1POYLw_normal.jpg","profile_interstitial_type":"","protected":false,"screen_name":"XDevelopers","statuses_count":4149,"url":"https://t.co/O13IfbuPqq","verified":false,"withheld_in_countries":[]}"#,
+    )
+    .unwrap();
+    let name = ProfileName::from_legacy(&legacy);
+    assert_eq!(name.handle, "XDevelopers");
+    assert!(!name.handle.contains(' '));
+    assert_eq!(name.display, "Developers of X");
+    assert!(name.display.contains(' '));
+
+    let stats = ProfileStats::from_legacy(&legacy, true);
+    assert_eq!(stats.listed, 1777);
+    assert!(stats.normal_followers <= stats.followers);
+    assert!(stats.blue_verified);
+}
+
+// first path segments on twitter.com that aren't profiles
+const RESERVED_PATHS: &[&str] = &[
+    "i",
+    "home",
+    "explore",
+    "search",
+    "settings",
+    "notifications",
+    "messages",
+    "hashtag",
+    "intent",
+    "share",
+    "login",
+    "signup",
+    "tos",
+    "privacy",
+];
+
 #[derive(
     Clone,
     Debug,
@@ -188,37 +704,237 @@ pub struct ProfileName {
     rkyv::Serialize,
     rkyv::Deserialize,
 )]
+pub struct Handle(pub String);
+
+impl Handle {
+    // `https://twitter.com/jack`, `x.com/jack/status/20?s=20`, `twitter.com/jack/`
+    pub fn from_url(url: &str) -> Option<Self> {
+        let segments = twitter_url_segments(url)?;
+        let first = segments.first()?;
+        if RESERVED_PATHS
+            .iter()
+            .any(|reserved| first.eq_ignore_ascii_case(reserved))
+        {
+            return None;
+        }
+        first.parse().ok()
+    }
+}
+
+impl FromStr for Handle {
+    type Err = TwtScrapeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let handle = s.trim().trim_start_matches('@');
+        if (1..=15).contains(&handle.len())
+            && handle
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_')
+        {
+            Ok(Handle(handle.to_string()))
+        } else {
+            Err(InvalidHandle(s.to_string()))
+        }
+    }
+}
+
+// `" @Jack "` -> `"jack"`, twitter doesn't care about the case of a handle. anything that
+// couldn't be a handle fails with `InvalidHandle` before a request is built from it.
+pub fn normalize_handle(handle: &str) -> SResult<String> {
+    handle
+        .parse::<Handle>()
+        .map(|handle| handle.0.to_ascii_lowercase())
+}
+
+impl Display for Handle {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl AsRef<str> for Handle {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+#[test]
+fn handle_from_url() {
+    assert_eq!(
+        Handle::from_url("https://twitter.com/jack"),
+        Some(Handle("jack".to_string()))
+    );
+    assert_eq!(
+        Handle::from_url("https://x.com/jack/status/20?s=20"),
+        Some(Handle("jack".to_string()))
+    );
+    assert_eq!(
+        Handle::from_url("mobile.twitter.com/jack/"),
+        Some(Handle("jack".to_string()))
+    );
+    assert_eq!(
+        Handle::from_url("https://twitter.com/i/web/status/20"),
+        None
+    );
+    assert_eq!(Handle::from_url("https://example.com/jack"), None);
+    assert!("@jack".parse::<Handle>().is_ok());
+    assert!("not a handle".parse::<Handle>().is_err());
+}
+
+#[test]
+fn handle_normalization() {
+    assert_eq!(normalize_handle(" @Jack ").unwrap(), "jack");
+    assert_eq!(normalize_handle("X_Dev_2").unwrap(), "x_dev_2");
+    assert!(matches!(normalize_handle("@"), Err(InvalidHandle(_))));
+    assert!(matches!(
+        normalize_handle("jack?variables="),
+        Err(InvalidHandle(_))
+    ));
+    assert!(normalize_handle("sixteen_chars_xx").is_err());
+}
+
+#[derive(
+    Clone,
+    Debug,
+    Hash,
+    PartialEq,
+    Eq,
+    Default,
+    Serialize,
+    Deserialize,
+    Archive,
+    rkyv::Serialize,
+    rkyv::Deserialize,
+)]
+#[cfg_attr(feature = "validation", archive(check_bytes))]
 pub struct ProfileStats {
     pub tweets: u32,
     pub following: u32,
     pub followers: u32,
     pub likes: u32,
     pub media_tweets: u32,
+    // followers minus the ones twitter counts as locked or spam
+    pub normal_followers: u32,
+    // how many lists the user is on
+    pub listed: u32,
     pub verified: bool,
     pub blue_verified: bool,
 }
 
+impl ProfileStats {
+    // blue verification sits outside of `legacy`
+    pub(crate) fn from_legacy(legacy: &UserLegacy, blue_verified: bool) -> Self {
+        ProfileStats {
+            tweets: legacy.statuses_count,
+            following: legacy.friends_count,
+            followers: legacy.followers_count,
+            likes: legacy.favourites_count,
+            media_tweets: legacy.media_count,
+            normal_followers: legacy.normal_followers_count,
+            listed: legacy.listed_count,
+            verified: legacy.verified,
+            blue_verified,
+        }
+    }
+}
+
 #[derive(
+    Copy,
     Clone,
     Debug,
     Hash,
     PartialEq,
     Eq,
+    Default,
     Serialize,
     Deserialize,
     Archive,
     rkyv::Serialize,
     rkyv::Deserialize,
 )]
+#[cfg_attr(feature = "validation", archive(check_bytes))]
+pub enum RestrictionState {
+    #[default]
+    None,
+    ReadOnly,
+    Restricted,
+    RequiresVerification,
+}
+
+impl From<&UserLegacy> for RestrictionState {
+    fn from(value: &UserLegacy) -> Self {
+        if value.needs_phone_verification {
+            return RestrictionState::RequiresVerification;
+        }
+        match value.profile_interstitial_type.as_str() {
+            // sensitive media is only a content warning
+            "" | "sensitive_media" => RestrictionState::None,
+            // temporarily limited accounts can browse but not post
+            "timeout" => RestrictionState::ReadOnly,
+            _ => RestrictionState::Restricted,
+        }
+    }
+}
+
+#[derive(
+    Clone,
+    Debug,
+    Hash,
+    PartialEq,
+    Eq,
+    Default,
+    Serialize,
+    Deserialize,
+    Archive,
+    rkyv::Serialize,
+    rkyv::Deserialize,
+)]
+#[cfg_attr(feature = "validation", archive(check_bytes))]
 pub struct ProfileAdditionalInfo {
     pub affiliation: Option<UserAffiliation>,
     pub profession: Option<Professional>,
     pub location: Option<String>,
+    // the t.co link as twitter returns it, see `ScraperBuilder::with_website_resolution`
     pub website: Option<String>,
-    pub joined: DateTime<Utc>,
+    // `None` if twitter's date didn't parse, `joined_raw` still has it
+    pub joined: Option<DateTime<Utc>>,
+    // as twitter sent it, e.g. `Sat Dec 14 04:35:55 +0000 2013`
+    pub joined_raw: String,
     pub birthday: Option<Birthday>,
 }
 
+impl ProfileAdditionalInfo {
+    // `joined` in the offset twitter sent it with
+    pub fn joined_with_offset(&self) -> Option<DateTime<FixedOffset>> {
+        DateTime::parse_from_str(&self.joined_raw, JOINDATE_PARSE_STR).ok()
+    }
+}
+
+#[test]
+fn joined_keeps_offset() {
+    let info = ProfileAdditionalInfo {
+        affiliation: None,
+        profession: None,
+        location: None,
+        website: None,
+        joined: None,
+        joined_raw: "Sat Dec 14 06:35:55 +0200 2013".to_string(),
+        birthday: None,
+    };
+    let joined = info.joined_with_offset().unwrap();
+    assert_eq!(joined.offset().local_minus_utc(), 2 * 60 * 60);
+    assert_eq!(
+        joined.with_timezone(&Utc).to_rfc3339(),
+        "2013-12-14T04:35:55+00:00"
+    );
+
+    let mangled = ProfileAdditionalInfo {
+        joined_raw: "sometime in 2013".to_string(),
+        ..info
+    };
+    assert_eq!(mangled.joined_with_offset(), None);
+}
+
 #[derive(
     Clone,
     Debug,
@@ -231,6 +947,7 @@ pub struct ProfileAdditionalInfo {
     rkyv::Serialize,
     rkyv::Deserialize,
 )]
+#[cfg_attr(feature = "validation", archive(check_bytes))]
 pub struct UserAffiliation {
     pub badge: String,
     pub url: String,
@@ -249,9 +966,62 @@ pub struct UserAffiliation {
     rkyv::Serialize,
     rkyv::Deserialize,
 )]
-pub(crate) struct UserRequest {
+pub struct UserRequest {
+    #[serde(default)]
+    pub(crate) errors: Vec<Error>,
+    pub(crate) data: Data,
+}
+
+// for `UserByScreenName` and `UserByRestId` json saved earlier, nothing is requested
+#[cfg(feature = "scrape")]
+impl UserRequest {
+    pub fn from_json(json: &str) -> SResult<Self> {
+        Scraper::parse_body(json.as_bytes())
+    }
+
+    // the website stays a t.co link, see `User::resolve_website`
+    pub fn into_user(self) -> SResult<User> {
+        // a user is still usable with partial data, so just log what twitter complained about
+        for warning in collect_warnings(&self.errors)? {
+            warn!(code = %warning.code, message = warning.message, "User returned with errors");
+        }
+
+        User::from_result_offline(self.data.user.result)
+    }
+}
+
+#[derive(
+    Clone,
+    Debug,
+    Hash,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+    Archive,
+    rkyv::Serialize,
+    rkyv::Deserialize,
+)]
+pub(crate) struct UsersByIdsRequest {
+    #[serde(default)]
     pub errors: Vec<Error>,
-    pub data: Data,
+    pub data: UsersByIdsData,
+}
+
+#[derive(
+    Clone,
+    Debug,
+    Hash,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+    Archive,
+    rkyv::Serialize,
+    rkyv::Deserialize,
+)]
+pub(crate) struct UsersByIdsData {
+    pub users: Vec<Usr>,
 }
 
 #[derive(
@@ -271,6 +1041,40 @@ pub(crate) struct Error {
     pub code: i32,
 }
 
+impl Error {
+    pub(crate) fn error_code(&self) -> TwitterErrorCode {
+        TwitterErrorCode::from(self.code)
+    }
+}
+
+#[cfg(feature = "scrape")]
+// twitter sends `errors` alongside partial `data`, so only fail on the codes that make the whole
+// response useless. a `data` that got nulled out already fails in `Scraper::api_req`.
+pub(crate) fn collect_warnings(errors: &[Error]) -> SResult<Vec<TwitterWarning>> {
+    let mut warnings = Vec::with_capacity(errors.len());
+    for why in errors {
+        let code = why.error_code();
+        if code == TwitterErrorCode::Ignore {
+            continue;
+        }
+        if code.is_fatal() {
+            return Err(TwitterJSONError(code, why.message.clone()));
+        }
+        warnings.push(TwitterWarning {
+            code,
+            message: why.message.clone(),
+        });
+    }
+    Ok(warnings)
+}
+
+#[cfg(feature = "scrape")]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub(crate) struct ErrorResponse {
+    #[serde(default)]
+    pub errors: Vec<Error>,
+}
+
 #[derive(
     Clone,
     Debug,
@@ -323,6 +1127,75 @@ pub(crate) enum TwtUsrResult {
     UserUnavailable(Box<UserUnavailable>),
 }
 
+// an available user the way timelines send it, handle `user<id>`
+#[cfg(all(test, feature = "scrape"))]
+pub(crate) fn user_results_fixture(id: u64) -> serde_json::Value {
+    serde_json::json!({
+        "__typename": "User",
+        "id": "VXNlcjo3ODMyMTQ=",
+        "rest_id": id.to_string(),
+        "has_nft_avatar": false,
+        "is_blue_verified": false,
+        "legacy": {
+            "created_at": "Tue Feb 20 14:35:54 +0000 2007",
+            "default_profile": false,
+            "default_profile_image": false,
+            "description": "",
+            "entities": {"description": {"urls": []}},
+            "fast_followers_count": 0,
+            "favourites_count": 0,
+            "followers_count": 0,
+            "friends_count": 0,
+            "has_custom_timelines": false,
+            "is_translator": false,
+            "listed_count": 0,
+            "location": "",
+            "media_count": 0,
+            "name": format!("User {id}"),
+            "normal_followers_count": 0,
+            "pinned_tweet_ids_str": [],
+            "possibly_sensitive": false,
+            "profile_banner_url": "",
+            "profile_image_url_https": "https://pbs.twimg.com/profile_images/1/a_normal.jpg",
+            "profile_interstitial_type": "",
+            "protected": false,
+            "screen_name": format!("user{id}"),
+            "statuses_count": 0,
+            "translator_type": "none",
+            "url": "",
+            "verified": false,
+            "withheld_in_countries": [],
+        },
+        "super_follow_eligible": false,
+        "is_profile_translatable": false,
+    })
+}
+
+#[cfg(feature = "scrape")]
+#[test]
+fn failed_user_chunks_are_reported() {
+    use crate::scrape::dry_run;
+
+    let ids = (1..=USER_BATCH_SIZE as u64 + 1)
+        .map(UserId)
+        .collect::<Vec<_>>();
+    // nothing canned, every chunk fails
+    dry_run(vec![], |scraper| async move {
+        let outcome = User::by_ids(&scraper, &ids).await.unwrap();
+        assert!(outcome.data.is_empty());
+        assert!(!outcome.is_complete());
+        let mut failed = outcome
+            .errors
+            .iter()
+            .map(|(what, _)| what.as_str())
+            .collect::<Vec<_>>();
+        failed.sort();
+        assert_eq!(failed.len(), 2);
+        assert!(failed[0].starts_with("users 1,2,3,"));
+        assert_eq!(failed[1], "users 101");
+    });
+}
+
 #[derive(
     Clone,
     Debug,
@@ -429,6 +1302,7 @@ pub(crate) struct WrapperUrl {
     rkyv::Serialize,
     rkyv::Deserialize,
 )]
+#[cfg_attr(feature = "validation", archive(check_bytes))]
 pub struct Professional {
     pub rest_id: String,
     pub professional_type: String,
@@ -447,6 +1321,7 @@ pub struct Professional {
     rkyv::Serialize,
     rkyv::Deserialize,
 )]
+#[cfg_attr(feature = "validation", archive(check_bytes))]
 pub struct ProfessionalCategory {
     pub id: u64,
     pub name: String,
@@ -466,6 +1341,7 @@ pub struct ProfessionalCategory {
     rkyv::Deserialize,
 )]
 pub(crate) struct UserLegacy {
+    #[serde(alias = "created_at")]
     pub created: String,
     pub default_profile: bool,
     pub default_profile_image: bool,
@@ -485,6 +1361,8 @@ pub(crate) struct UserLegacy {
     pub profile_banner_url: String,
     pub profile_image_url_https: String,
     pub profile_interstitial_type: String,
+    #[serde(default)]
+    pub needs_phone_verification: bool,
     pub protected: bool,
     pub screen_name: String,
     pub statuses_count: u32,
@@ -521,9 +1399,35 @@ pub(crate) struct LegacyExtendedProfile {
     rkyv::Serialize,
     rkyv::Deserialize,
 )]
+#[cfg_attr(feature = "validation", archive(check_bytes))]
 pub struct Birthday {
-    day: u8,
-    month: u8,
+    pub day: u8,
+    pub month: u8,
+    // only there when the user shares it
+    #[serde(default)]
+    pub year: Option<i32>,
+    // who may see it, e.g. `Self`, `Followers` or `Public`
+    #[serde(default)]
+    pub visibility: Option<String>,
+}
+
+#[cfg(feature = "scrape")]
+#[test]
+fn birthday_with_year() {
+    let profile = serde_json::from_str::<LegacyExtendedProfile>(
+        r#"{"birthdate":{"day":14,"month":3,"year":1991,"visibility":"Followers","year_visibility":"Self"}}"#,
+    )
+    .unwrap();
+    let birthday = profile.birthdate.unwrap();
+    assert_eq!((birthday.day, birthday.month), (14, 3));
+    assert_eq!(birthday.year, Some(1991));
+    assert_eq!(birthday.visibility.as_deref(), Some("Followers"));
+
+    let profile = serde_json::from_str::<LegacyExtendedProfile>(
+        r#"{"birthdate":{"day":1,"month":12,"visibility":"Public","year_visibility":"Self"}}"#,
+    )
+    .unwrap();
+    assert_eq!(profile.birthdate.unwrap().year, None);
 }
 
 #[derive(
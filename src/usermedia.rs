@@ -0,0 +1,337 @@
+use crate::error::SResult;
+#[cfg(feature = "scrape")]
+use crate::graphql::GraphQlRequest;
+#[cfg(feature = "scrape")]
+use crate::id::UserId;
+#[cfg(feature = "scrape")]
+use crate::paginate::{HasCursor, Pager};
+#[cfg(feature = "scrape")]
+use crate::scrape::{ScrapeOptions, Scraper};
+#[cfg(feature = "scrape")]
+use crate::tweet::FilterCursorTweetRequest;
+use crate::tweet::{Tweet, TweetItemContent};
+use crate::user::{Error, User};
+use crate::SetAsVec;
+use ahash::{HashSet, HashSetExt};
+use rkyv::Archive;
+use serde::de::IgnoredAny;
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "scrape")]
+use tracing::warn;
+
+#[cfg(feature = "scrape")]
+pub fn twitter_request_url_user_media(
+    id: UserId,
+    cursor: Option<impl AsRef<str>>,
+    options: &ScrapeOptions,
+) -> String {
+    let request = GraphQlRequest::new("Le6KlbilFmSu-5VltFND-Q", "UserMedia")
+        .variable("userId", id.to_string())
+        .variable("count", 20)
+        .variable("includePromotedContent", options.include_promoted_content)
+        .variable("withClientEventToken", false)
+        .variable("withBirdwatchNotes", false)
+        .variable("withVoice", true)
+        .variable("withV2Timeline", true)
+        .features([
+            (
+                "responsive_web_twitter_blue_verified_badge_is_enabled",
+                true,
+            ),
+            ("verified_phone_label_enabled", false),
+            ("responsive_web_graphql_timeline_navigation_enabled", true),
+            ("tweetypie_unmention_optimization_enabled", true),
+            ("vibe_api_enabled", true),
+            ("responsive_web_edit_tweet_api_enabled", true),
+            (
+                "graphql_is_translatable_rweb_tweet_is_translatable_enabled",
+                true,
+            ),
+            ("standardized_nudges_misinfo", true),
+            (
+                "tweet_with_visibility_results_prefer_gql_limited_actions_policy_enabled",
+                false,
+            ),
+            ("interactive_text_enabled", true),
+            ("responsive_web_text_conversations_enabled", false),
+            ("responsive_web_enhance_cards_enabled", true),
+        ]);
+    match cursor {
+        Some(cursor) => request.variable("cursor", cursor.as_ref()).build(),
+        None => request.build(),
+    }
+}
+
+#[derive(
+    Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Archive, rkyv::Serialize, rkyv::Deserialize,
+)]
+pub struct UserMedia {
+    pub user: User,
+    // only tweets with media attached
+    #[with(SetAsVec)]
+    pub tweets: HashSet<Tweet>,
+}
+
+#[cfg(feature = "scrape")]
+impl UserMedia {
+    // on cancellation the pages fetched so far are returned
+    #[tracing::instrument]
+    pub async fn scroll(scraper: &Scraper, user_id: UserId) -> SResult<Self> {
+        let user = User::by_id(scraper, user_id).await?;
+        let mut tweets = HashSet::with_capacity(user.profile_stats.media_tweets as usize);
+
+        let mut pager = Pager::new(scraper, FilterCursorTweetRequest::Bottom, None, |cursor| {
+            twitter_request_url_user_media(user_id, cursor, scraper.options())
+        });
+        while let Some(request) = pager.next_page::<UserMediaRequest>().await? {
+            for item in request.into_items() {
+                let results = item.item.item_content.tweet_results;
+                if results.rest_id_or_skip().is_none() {
+                    continue;
+                }
+                match Tweet::new_from_entry(&results) {
+                    Ok(tweet) if tweet.has_media() => {
+                        tweets.insert(tweet);
+                    }
+                    Ok(_) => {}
+                    Err(why) => {
+                        warn!(error = %why, "Failed to parse media tweet. Skipping...");
+                    }
+                }
+            }
+        }
+
+        Ok(UserMedia { user, tweets })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub(crate) struct UserMediaRequest {
+    #[serde(default)]
+    pub errors: Vec<Error>,
+    pub data: UserMediaData,
+}
+
+impl UserMediaRequest {
+    fn instructions(&self) -> &[MediaInstruction] {
+        &self.data.user.result.timeline_v2.timeline.instructions
+    }
+
+    pub(crate) fn bottom_cursor(&self) -> Option<&str> {
+        self.instructions()
+            .iter()
+            .filter_map(|inst| match inst {
+                MediaInstruction::TimelineAddEntries(add) => Some(&add.entries),
+                _ => None,
+            })
+            .flatten()
+            .find_map(|entry| match entry {
+                MediaEntry::Cursor(cursor) if cursor.content.cursor_type == "Bottom" => {
+                    Some(cursor.content.value.as_str())
+                }
+                _ => None,
+            })
+    }
+
+    // the first page opens the grid module, every later one appends to it
+    pub(crate) fn into_items(self) -> Vec<MediaModuleItem> {
+        self.data
+            .user
+            .result
+            .timeline_v2
+            .timeline
+            .instructions
+            .into_iter()
+            .flat_map(|inst| match inst {
+                MediaInstruction::TimelineAddEntries(add) => add
+                    .entries
+                    .into_iter()
+                    .flat_map(|entry| match entry {
+                        MediaEntry::Grid(grid) => grid.content.items,
+                        _ => vec![],
+                    })
+                    .collect(),
+                MediaInstruction::TimelineAddToModule(module) => module.module_items,
+                MediaInstruction::Other => vec![],
+            })
+            .collect()
+    }
+}
+
+#[cfg(feature = "scrape")]
+crate::impl_filter_json!(UserMediaRequest);
+
+#[cfg(feature = "scrape")]
+impl HasCursor for UserMediaRequest {
+    // media timelines only scroll down
+    fn filter_cursor(&self, filter: FilterCursorTweetRequest) -> Option<&str> {
+        match filter {
+            FilterCursorTweetRequest::Top => None,
+            FilterCursorTweetRequest::Bottom => self.bottom_cursor(),
+        }
+    }
+
+    fn entry_count(&self) -> usize {
+        self.instructions()
+            .iter()
+            .map(|inst| match inst {
+                MediaInstruction::TimelineAddEntries(add) => add
+                    .entries
+                    .iter()
+                    .map(|entry| match entry {
+                        MediaEntry::Grid(grid) => grid.content.items.len(),
+                        _ => 0,
+                    })
+                    .sum(),
+                MediaInstruction::TimelineAddToModule(module) => module.module_items.len(),
+                MediaInstruction::Other => 0,
+            })
+            .sum()
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub(crate) struct UserMediaData {
+    pub user: MediaUser,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub(crate) struct MediaUser {
+    pub result: MediaUserResult,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub(crate) struct MediaUserResult {
+    pub timeline_v2: MediaTimelineV2,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub(crate) struct MediaTimelineV2 {
+    pub timeline: MediaTimeline,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub(crate) struct MediaTimeline {
+    pub instructions: Vec<MediaInstruction>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub(crate) enum MediaInstruction {
+    TimelineAddEntries(MediaAddEntries),
+    TimelineAddToModule(MediaAddToModule),
+    #[serde(other)]
+    Other,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub(crate) struct MediaAddEntries {
+    pub entries: Vec<MediaEntry>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct MediaAddToModule {
+    pub module_entry_id: String,
+    pub module_items: Vec<MediaModuleItem>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub(crate) enum MediaEntry {
+    Grid(MediaGridEntry),
+    Cursor(MediaCursorEntry),
+    #[serde(skip_serializing)]
+    Other(IgnoredAny),
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub(crate) struct MediaGridEntry {
+    pub content: MediaGrid,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub(crate) struct MediaGrid {
+    pub items: Vec<MediaModuleItem>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub(crate) struct MediaModuleItem {
+    pub item: MediaItem,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct MediaItem {
+    pub item_content: TweetItemContent,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub(crate) struct MediaCursorEntry {
+    pub content: MediaCursor,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct MediaCursor {
+    pub value: String,
+    pub cursor_type: String,
+}
+
+#[cfg(feature = "scrape")]
+#[test]
+fn broken_media_tweets_are_skipped() {
+    use crate::id::TweetId;
+    use crate::scrape::dry_run;
+    use crate::tweet::tweet_results_fixture;
+
+    let photo = serde_json::json!({"extended_entities": {"media": [{
+        "id_str": "1665421059130257409",
+        "media_key": "3_1665421059130257409",
+        "media_url_https": "https://pbs.twimg.com/media/FxwZ8VYWAAAZXyT.jpg",
+        "type": "photo",
+        "expanded_url": "https://twitter.com/user7/status/1700000000000000001/photo/1",
+    }]}});
+    let mut broken = photo.clone();
+    broken["created_at"] = "yesterday".into();
+    let item = |results: serde_json::Value| {
+        serde_json::json!({"item": {"itemContent": {
+            "itemType": "TimelineTweet",
+            "__typename": "TimelineTweet",
+            "tweet_results": results,
+        }}})
+    };
+    // the grid on the only page, there is no cursor to more of it
+    let items = vec![
+        item(tweet_results_fixture(1700000000000000001, 7, photo)),
+        item(tweet_results_fixture(1700000000000000002, 7, broken)),
+        item(tweet_results_fixture(
+            1700000000000000003,
+            7,
+            serde_json::json!({}),
+        )),
+    ];
+    let canned = vec![
+        serde_json::json!({"data": {"user": {"result": crate::user::user_results_fixture(7)}}})
+            .to_string(),
+        serde_json::json!({
+            "data": {"user": {"result": {"timeline_v2": {"timeline": {"instructions": [
+                {"type": "TimelineAddToModule", "moduleEntryId": "profile-grid-0", "moduleItems": items},
+            ]}}}}},
+        })
+        .to_string(),
+    ];
+
+    dry_run(canned, |scraper| async move {
+        let media = UserMedia::scroll(&scraper, UserId(7)).await.unwrap();
+        assert_eq!(media.user.id, UserId(7));
+        assert_eq!(
+            media
+                .tweets
+                .iter()
+                .map(|tweet| tweet.id)
+                .collect::<Vec<_>>(),
+            vec![TweetId(1700000000000000001)]
+        );
+    });
+}
@@ -1,31 +1,117 @@
-use crate::error::SResult;
-use crate::error::TwtScrapeError::TwitterJSONError;
+#[cfg(feature = "validation")]
+use crate::error::TwtScrapeError::InvalidArchive;
+use crate::error::{SResult, TwitterWarning};
 #[cfg(feature = "scrape")]
-use crate::scrape::Scraper;
-use crate::tweet::{Cursor, Tweet, TweetEnt, TweetItemContent, TweetResults};
+use crate::error::{ScrapeOutcome, TwitterErrorCode, TwtScrapeError};
+#[cfg(feature = "scrape")]
+use crate::graphql::GraphQlRequest;
+use crate::id::{TweetId, UserId};
+#[cfg(feature = "scrape")]
+use crate::paginate::{paginate_partial, HasCursor, Pager};
+#[cfg(feature = "scrape")]
+use crate::scrape::{ScrapeOptions, Scraper};
+#[cfg(feature = "scrape")]
+use crate::tweet::TweetResults;
+use crate::tweet::{
+    Cursor, FilterCursorTweetRequest, Tweet, TweetEnt, TweetItemContent, TweetType,
+};
+#[cfg(feature = "scrape")]
+use crate::user::{collect_warnings, TwtUsrResult};
 use crate::user::{Error, User};
-use ahash::{HashSet, HashSetExt};
+use crate::SetAsVec;
+use ahash::{HashMap, HashMapExt, HashSet, HashSetExt};
+#[cfg(feature = "scrape")]
+use futures::{future, stream, Stream, StreamExt};
 use rkyv::Archive;
-use serde::de::{MapAccess, Visitor};
+use serde::de::{DeserializeSeed, MapAccess, Visitor};
 use serde::{de, Deserialize, Deserializer, Serialize};
 use std::collections::VecDeque;
 use std::fmt;
-use std::fmt::Display;
+use std::ops::Deref;
 #[cfg(feature = "scrape")]
 use tracing::{span, warn};
 
 #[cfg(feature = "scrape")]
 pub fn twitter_request_url_user_tweet_and_replies(
-    id: u64,
+    id: UserId,
     cursor: Option<impl AsRef<str>>,
+    options: &ScrapeOptions,
 ) -> String {
+    let promoted = options.include_promoted_content;
     match cursor {
         Some(crsr) => {
             let crsr = urlencoding::encode(crsr.as_ref());
-            format!("https://twitter.com/i/api/graphql/s0hG9oAmWEYVBqOLJP-TBQ/UserTweetsAndReplies?variables=%7B%22userId%22%3A%22{id}%22%2C%22count%22%3A40%2C%22cursor%22%3A%22{crsr}%22%2C%22includePromotedContent%22%3Afalse%2C%22withCommunity%22%3Atrue%2C%22withSuperFollowsUserFields%22%3Atrue%2C%22withDownvotePerspective%22%3Afalse%2C%22withReactionsMetadata%22%3Afalse%2C%22withReactionsPerspective%22%3Afalse%2C%22withSuperFollowsTweetFields%22%3Atrue%2C%22withVoice%22%3Atrue%2C%22withV2Timeline%22%3Atrue%7D&features=%7B%22responsive_web_twitter_blue_verified_badge_is_enabled%22%3Atrue%2C%22verified_phone_label_enabled%22%3Afalse%2C%22responsive_web_graphql_timeline_navigation_enabled%22%3Atrue%2C%22unified_cards_ad_metadata_container_dynamic_card_content_query_enabled%22%3Atrue%2C%22tweetypie_unmention_optimization_enabled%22%3Atrue%2C%22responsive_web_uc_gql_enabled%22%3Atrue%2C%22vibe_api_enabled%22%3Atrue%2C%22responsive_web_edit_tweet_api_enabled%22%3Atrue%2C%22graphql_is_translatable_rweb_tweet_is_translatable_enabled%22%3Atrue%2C%22standardized_nudges_misinfo%22%3Atrue%2C%22tweet_with_visibility_results_prefer_gql_limited_actions_policy_enabled%22%3Afalse%2C%22interactive_text_enabled%22%3Atrue%2C%22responsive_web_text_conversations_enabled%22%3Afalse%2C%22responsive_web_enhance_cards_enabled%22%3Atrue%7D")
+            format!("https://twitter.com/i/api/graphql/s0hG9oAmWEYVBqOLJP-TBQ/UserTweetsAndReplies?variables=%7B%22userId%22%3A%22{id}%22%2C%22count%22%3A40%2C%22cursor%22%3A%22{crsr}%22%2C%22includePromotedContent%22%3A{promoted}%2C%22withCommunity%22%3Atrue%2C%22withSuperFollowsUserFields%22%3Atrue%2C%22withDownvotePerspective%22%3Afalse%2C%22withReactionsMetadata%22%3Afalse%2C%22withReactionsPerspective%22%3Afalse%2C%22withSuperFollowsTweetFields%22%3Atrue%2C%22withVoice%22%3Atrue%2C%22withV2Timeline%22%3Atrue%7D&features=%7B%22responsive_web_twitter_blue_verified_badge_is_enabled%22%3Atrue%2C%22verified_phone_label_enabled%22%3Afalse%2C%22responsive_web_graphql_timeline_navigation_enabled%22%3Atrue%2C%22unified_cards_ad_metadata_container_dynamic_card_content_query_enabled%22%3Atrue%2C%22tweetypie_unmention_optimization_enabled%22%3Atrue%2C%22responsive_web_uc_gql_enabled%22%3Atrue%2C%22vibe_api_enabled%22%3Atrue%2C%22responsive_web_edit_tweet_api_enabled%22%3Atrue%2C%22graphql_is_translatable_rweb_tweet_is_translatable_enabled%22%3Atrue%2C%22standardized_nudges_misinfo%22%3Atrue%2C%22tweet_with_visibility_results_prefer_gql_limited_actions_policy_enabled%22%3Afalse%2C%22interactive_text_enabled%22%3Atrue%2C%22responsive_web_text_conversations_enabled%22%3Afalse%2C%22responsive_web_enhance_cards_enabled%22%3Atrue%7D")
         }
         None => {
-            format!("https://twitter.com/i/api/graphql/s0hG9oAmWEYVBqOLJP-TBQ/UserTweetsAndReplies?variables=%7B%22userId%22%3A%22{id}%22%2C%22count%22%3A40%2C%22includePromotedContent%22%3Afalse%2C%22withCommunity%22%3Atrue%2C%22withSuperFollowsUserFields%22%3Atrue%2C%22withDownvotePerspective%22%3Afalse%2C%22withReactionsMetadata%22%3Afalse%2C%22withReactionsPerspective%22%3Afalse%2C%22withSuperFollowsTweetFields%22%3Atrue%2C%22withVoice%22%3Atrue%2C%22withV2Timeline%22%3Atrue%7D&features=%7B%22responsive_web_twitter_blue_verified_badge_is_enabled%22%3Atrue%2C%22verified_phone_label_enabled%22%3Afalse%2C%22responsive_web_graphql_timeline_navigation_enabled%22%3Atrue%2C%22unified_cards_ad_metadata_container_dynamic_card_content_query_enabled%22%3Atrue%2C%22tweetypie_unmention_optimization_enabled%22%3Atrue%2C%22responsive_web_uc_gql_enabled%22%3Atrue%2C%22vibe_api_enabled%22%3Atrue%2C%22responsive_web_edit_tweet_api_enabled%22%3Atrue%2C%22graphql_is_translatable_rweb_tweet_is_translatable_enabled%22%3Atrue%2C%22standardized_nudges_misinfo%22%3Atrue%2C%22tweet_with_visibility_results_prefer_gql_limited_actions_policy_enabled%22%3Afalse%2C%22interactive_text_enabled%22%3Atrue%2C%22responsive_web_text_conversations_enabled%22%3Afalse%2C%22responsive_web_enhance_cards_enabled%22%3Atrue%7D")
+            format!("https://twitter.com/i/api/graphql/s0hG9oAmWEYVBqOLJP-TBQ/UserTweetsAndReplies?variables=%7B%22userId%22%3A%22{id}%22%2C%22count%22%3A40%2C%22includePromotedContent%22%3A{promoted}%2C%22withCommunity%22%3Atrue%2C%22withSuperFollowsUserFields%22%3Atrue%2C%22withDownvotePerspective%22%3Afalse%2C%22withReactionsMetadata%22%3Afalse%2C%22withReactionsPerspective%22%3Afalse%2C%22withSuperFollowsTweetFields%22%3Atrue%2C%22withVoice%22%3Atrue%2C%22withV2Timeline%22%3Atrue%7D&features=%7B%22responsive_web_twitter_blue_verified_badge_is_enabled%22%3Atrue%2C%22verified_phone_label_enabled%22%3Afalse%2C%22responsive_web_graphql_timeline_navigation_enabled%22%3Atrue%2C%22unified_cards_ad_metadata_container_dynamic_card_content_query_enabled%22%3Atrue%2C%22tweetypie_unmention_optimization_enabled%22%3Atrue%2C%22responsive_web_uc_gql_enabled%22%3Atrue%2C%22vibe_api_enabled%22%3Atrue%2C%22responsive_web_edit_tweet_api_enabled%22%3Atrue%2C%22graphql_is_translatable_rweb_tweet_is_translatable_enabled%22%3Atrue%2C%22standardized_nudges_misinfo%22%3Atrue%2C%22tweet_with_visibility_results_prefer_gql_limited_actions_policy_enabled%22%3Afalse%2C%22interactive_text_enabled%22%3Atrue%2C%22responsive_web_text_conversations_enabled%22%3Afalse%2C%22responsive_web_enhance_cards_enabled%22%3Atrue%7D")
+        }
+    }
+}
+
+#[cfg(feature = "scrape")]
+pub fn twitter_request_url_user_tweets(
+    id: UserId,
+    cursor: Option<impl AsRef<str>>,
+    options: &ScrapeOptions,
+) -> String {
+    let request = GraphQlRequest::new("VgitpdpNZ-RUIp5D1Z_D-A", "UserTweets")
+        .variable("userId", id.to_string())
+        .variable("count", 40)
+        .variable("includePromotedContent", options.include_promoted_content)
+        .variable("withQuickPromoteEligibilityTweetFields", true)
+        .variable("withVoice", true)
+        .variable("withV2Timeline", true)
+        .features([
+            (
+                "responsive_web_twitter_blue_verified_badge_is_enabled",
+                true,
+            ),
+            ("verified_phone_label_enabled", false),
+            ("responsive_web_graphql_timeline_navigation_enabled", true),
+            (
+                "unified_cards_ad_metadata_container_dynamic_card_content_query_enabled",
+                true,
+            ),
+            ("tweetypie_unmention_optimization_enabled", true),
+            ("responsive_web_uc_gql_enabled", true),
+            ("vibe_api_enabled", true),
+            ("responsive_web_edit_tweet_api_enabled", true),
+            (
+                "graphql_is_translatable_rweb_tweet_is_translatable_enabled",
+                true,
+            ),
+            ("standardized_nudges_misinfo", true),
+            (
+                "tweet_with_visibility_results_prefer_gql_limited_actions_policy_enabled",
+                false,
+            ),
+            ("interactive_text_enabled", true),
+            ("responsive_web_text_conversations_enabled", false),
+            ("responsive_web_enhance_cards_enabled", true),
+        ]);
+    match cursor {
+        Some(cursor) => request.variable("cursor", cursor.as_ref()).build(),
+        None => request.build(),
+    }
+}
+
+// both endpoints answer with the same timeline, only whether replies are in it differs
+#[cfg(feature = "scrape")]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub(crate) enum UserTimeline {
+    TweetsAndReplies,
+    Tweets,
+}
+
+#[cfg(feature = "scrape")]
+impl UserTimeline {
+    fn url(self, id: UserId, cursor: Option<&str>, options: &ScrapeOptions) -> String {
+        match self {
+            UserTimeline::TweetsAndReplies => {
+                twitter_request_url_user_tweet_and_replies(id, cursor, options)
+            }
+            UserTimeline::Tweets => twitter_request_url_user_tweets(id, cursor, options),
         }
     }
 }
@@ -42,114 +128,505 @@ pub fn twitter_request_url_user_tweet_and_replies(
     rkyv::Serialize,
     rkyv::Deserialize,
 )]
+#[cfg_attr(feature = "validation", archive(check_bytes))]
 pub struct UserTweetsAndReplies {
+    #[with(SetAsVec)]
     pub users: HashSet<User>,
+    #[with(SetAsVec)]
     pub tweets: HashSet<Tweet>,
+    // non-fatal errors twitter returned alongside the timeline pages
+    pub warnings: Vec<TwitterWarning>,
+    // ids of the ads in `tweets`, only ever filled with `ScrapeOptions::include_promoted_content`
+    pub promoted: Vec<TweetId>,
+    // bottom cursor of the last page, hand it to `scroll_user_timeline_from` to carry on from
+    // there. `None` once a scroll ran out of pages, `from_request` keeps the page's cursor as is
+    pub next_cursor: Option<String>,
+}
+
+impl UserTweetsAndReplies {
+    pub fn is_promoted(&self, tweet: &Tweet) -> bool {
+        self.promoted.contains(&tweet.id)
+    }
+
+    pub fn user(&self, id: UserId) -> Option<&User> {
+        self.users.iter().find(|user| user.id == id)
+    }
+
+    // tombstones have no author and are left out
+    pub fn by_author(&self) -> HashMap<UserId, Vec<&Tweet>> {
+        let mut authors: HashMap<UserId, Vec<&Tweet>> = HashMap::with_capacity(self.users.len());
+        for tweet in &self.tweets {
+            if let Some(author) = tweet.author_id() {
+                authors.entry(author).or_default().push(tweet);
+            }
+        }
+        authors
+    }
+
+    // tweets without a known conversation (id 0) are left out of both
+    pub fn conversation_count(&self) -> usize {
+        self.tweets
+            .iter()
+            .map(|tweet| tweet.conversation_id)
+            .filter(|id| *id != 0)
+            .collect::<HashSet<TweetId>>()
+            .len()
+    }
+
+    pub fn conversations(&self) -> HashMap<TweetId, Vec<&Tweet>> {
+        let mut conversations: HashMap<TweetId, Vec<&Tweet>> = HashMap::new();
+        for tweet in &self.tweets {
+            if tweet.conversation_id != 0 {
+                conversations
+                    .entry(tweet.conversation_id)
+                    .or_default()
+                    .push(tweet);
+            }
+        }
+        conversations
+    }
+
+    // replies to or mentions of `user_id` by someone else. this only sees the tweets that are
+    // actually in this result set, it is not a notifications feed.
+    pub fn mentioning(&self, user_id: UserId) -> Vec<&Tweet> {
+        self.tweets
+            .iter()
+            .filter(|tweet| tweet.author_id() != Some(user_id))
+            .filter(|tweet| match &tweet.tweet_type {
+                TweetType::Tweet(data) => {
+                    data.reply_info.replying_to_user == Some(user_id)
+                        || data
+                            .entry
+                            .mentions
+                            .iter()
+                            .any(|mention| mention.id_str.parse::<UserId>().ok() == Some(user_id))
+                }
+                TweetType::Tombstone(_) => false,
+            })
+            .collect()
+    }
+
+    // `author_id`'s threads, oldest tweet first. a chain starts at a tweet that isn't a self reply
+    // to something in this set and follows the earliest self reply at every step, so tweets
+    // missing from the set split a thread in two. single tweets are left out.
+    pub fn self_threads(&self, author_id: UserId) -> Vec<Vec<&Tweet>> {
+        let own = self
+            .tweets
+            .iter()
+            .filter(|tweet| tweet.author_id() == Some(author_id))
+            .map(|tweet| (tweet.id, tweet))
+            .collect::<HashMap<TweetId, &Tweet>>();
+
+        let mut replies: HashMap<TweetId, Vec<&Tweet>> = HashMap::new();
+        let mut roots = Vec::new();
+        for tweet in own.values() {
+            match tweet.replying_to() {
+                Some(parent) if tweet.replies_to_self() && own.contains_key(&parent) => {
+                    replies.entry(parent).or_default().push(tweet)
+                }
+                _ => roots.push(*tweet),
+            }
+        }
+        roots.sort_by_key(|tweet| tweet.id);
+
+        roots
+            .into_iter()
+            .map(|root| {
+                let mut thread = vec![root];
+                let mut current = root.id;
+                while let Some(next) = replies
+                    .get(&current)
+                    .and_then(|replies| replies.iter().min_by_key(|tweet| tweet.id))
+                {
+                    thread.push(*next);
+                    current = next.id;
+                }
+                thread
+            })
+            .filter(|thread| thread.len() > 1)
+            .collect()
+    }
+
+    // checks the rkyv buffer without deserializing it, the buffer has to be aligned like `rkyv::AlignedVec`
+    #[cfg(feature = "validation")]
+    pub fn validate_archive(bytes: &[u8]) -> SResult<()> {
+        Self::from_archived_bytes_checked(bytes).map(|_| ())
+    }
+
+    /// Zero copy view of the archive with nothing checked.
+    ///
+    /// # Safety
+    ///
+    /// `bytes` has to be exactly what `rkyv::to_bytes` wrote for this type, aligned like
+    /// `rkyv::AlignedVec` (16 bytes). Anything else is undefined behavior, so anything read back
+    /// from disk or the network should go through `from_archived_bytes_checked`.
+    pub unsafe fn from_archived_bytes(bytes: &[u8]) -> &ArchivedUserTweetsAndReplies {
+        rkyv::archived_root::<Self>(bytes)
+    }
+
+    // same alignment requirement as `from_archived_bytes`, misaligned buffers fail the check
+    #[cfg(feature = "validation")]
+    pub fn from_archived_bytes_checked(bytes: &[u8]) -> SResult<&ArchivedUserTweetsAndReplies> {
+        rkyv::check_archived_root::<Self>(bytes).map_err(|why| InvalidArchive(why.to_string()))
+    }
+}
+
+#[cfg(feature = "validation")]
+#[test]
+fn validate_archive_bytes() {
+    let archive = UserTweetsAndReplies {
+        users: HashSet::new(),
+        tweets: HashSet::new(),
+        warnings: vec![TwitterWarning {
+            code: crate::error::TwitterErrorCode::Unknown(131),
+            message: "Internal error".to_string(),
+        }],
+        promoted: vec![],
+        next_cursor: Some("HBaAgLydt+7pqS4AAA==".to_string()),
+    };
+    let bytes = rkyv::to_bytes::<_, 256>(&archive).unwrap();
+    assert!(UserTweetsAndReplies::validate_archive(&bytes).is_ok());
+    assert!(UserTweetsAndReplies::validate_archive(&bytes[..4]).is_err());
 }
 
+#[test]
+fn archived_bytes_round_trip() {
+    let timeline = UserTweetsAndReplies {
+        users: HashSet::new(),
+        tweets: HashSet::new(),
+        warnings: vec![TwitterWarning {
+            code: crate::error::TwitterErrorCode::Unknown(131),
+            message: "Internal error".to_string(),
+        }],
+        promoted: vec![TweetId(1655555555555555555), TweetId(1666666666666666666)],
+        next_cursor: Some("HBaAgLydt+7pqS4AAA==".to_string()),
+    };
+    let bytes = rkyv::to_bytes::<_, 256>(&timeline).unwrap();
+
+    // straight from `rkyv::to_bytes`, aligned and untouched
+    let archived = unsafe { UserTweetsAndReplies::from_archived_bytes(&bytes) };
+    assert_eq!(
+        archived.promoted.iter().map(|id| id.0).collect::<Vec<_>>(),
+        vec![1655555555555555555, 1666666666666666666]
+    );
+    assert_eq!(archived.warnings[0].message.as_str(), "Internal error");
+    assert_eq!(
+        archived.next_cursor.as_ref().map(|cursor| cursor.as_str()),
+        Some("HBaAgLydt+7pqS4AAA==")
+    );
+    assert!(archived.tweets.is_empty());
+
+    #[cfg(feature = "validation")]
+    {
+        let checked = UserTweetsAndReplies::from_archived_bytes_checked(&bytes).unwrap();
+        assert_eq!(checked.promoted.len(), 2);
+    }
+}
+
+// twitter won't go further back than this on a user timeline
+pub const MAX_TIMELINE_TWEETS: u32 = 3200;
+
 #[cfg(feature = "scrape")]
 impl UserTweetsAndReplies {
+    // stops scrolling once `max_tweets` timeline entries were seen, `None` goes as far back as
+    // twitter allows (`MAX_TIMELINE_TWEETS`). threads are crawled in full so more tweets than that
+    // can come back, `Scraper::thread_parallelism` of them at a time.
+    // on cancellation no further pages or threads are fetched, the threads crawled so far are
+    // returned. a thread cut short by cancellation may be missing replies.
+    #[tracing::instrument]
+    pub async fn scroll_user_timeline(
+        scraper: &Scraper,
+        user_handle: String,
+        max_tweets: Option<u32>,
+    ) -> SResult<Self> {
+        Self::scroll_timeline(
+            scraper,
+            user_handle,
+            UserTimeline::TweetsAndReplies,
+            max_tweets,
+            None,
+        )
+        .await
+    }
+
+    // like `scroll_user_timeline` but starting at a `next_cursor` of an earlier scroll
+    #[tracing::instrument]
+    pub async fn scroll_user_timeline_from(
+        scraper: &Scraper,
+        user_handle: String,
+        cursor: String,
+        max_tweets: Option<u32>,
+    ) -> SResult<Self> {
+        Self::scroll_timeline(
+            scraper,
+            user_handle,
+            UserTimeline::TweetsAndReplies,
+            max_tweets,
+            Some(&cursor),
+        )
+        .await
+    }
+
+    // like `scroll_user_timeline`, but a timeline page that fails ends the scroll instead of the
+    // whole call. the failed page's cursor is left in `next_cursor` to try again from. threads
+    // that failed are listed in the errors too. only not finding the user fails outright.
+    #[tracing::instrument]
+    pub async fn scroll_user_timeline_partial(
+        scraper: &Scraper,
+        user_handle: String,
+        max_tweets: Option<u32>,
+    ) -> SResult<ScrapeOutcome<Self>> {
+        Self::scroll_timeline_outcome(
+            scraper,
+            user_handle,
+            UserTimeline::TweetsAndReplies,
+            max_tweets,
+            None,
+            true,
+        )
+        .await
+    }
+
+    // the user's timeline one page at a time as they come in, each parsed like `from_request`.
+    // threads are not crawled. dropping the stream stops the scroll, so does cancellation.
     #[tracing::instrument]
-    pub async fn scroll_user_timeline(scraper: &Scraper, user_handle: String) -> SResult<Self> {
+    pub async fn scroll_stream<'a>(
+        scraper: &'a Scraper,
+        user_handle: &str,
+    ) -> SResult<impl Stream<Item = SResult<Self>> + 'a> {
+        let user = User::new(scraper, user_handle).await?;
+        Ok(UserTweetAndRepliesRequest::scroll_stream(
+            scraper,
+            UserTimeline::TweetsAndReplies,
+            user.id,
+            None,
+        )
+        .map(|page| page.and_then(Self::from_request)))
+    }
+
+    // the tweets and authors on one page without any requests. conversations are not crawled
+    // and only their tweets on the page end up in `tweets`, websites stay t.co links.
+    pub fn from_request(request: UserTweetAndRepliesRequest) -> SResult<Self> {
+        let warnings = request.json_request_filter_errors()?;
+        let mut tweets = HashSet::new();
+        let mut users = HashSet::new();
+        let mut promoted = Vec::new();
+        let mut seen_users = HashSet::new();
+
+        for (results, is_promoted) in request.tweet_results() {
+            let trr = match (results.rest_id_or_skip(), results.ok()) {
+                (Some(_), Some(trr)) => trr,
+                _ => continue,
+            };
+            let tweet = match Tweet::new_from_entry(results) {
+                Ok(tweet) => tweet,
+                Err(why) => {
+                    warn!(error = %why, "Failed to parse tweet. Skipping...");
+                    continue;
+                }
+            };
+            if is_promoted {
+                promoted.push(tweet.id);
+            }
+            tweets.insert(tweet);
+
+            for author in
+                std::iter::once(&trr.core.user_results.result).chain(trr.retweeted_author())
+            {
+                if let TwtUsrResult::User(usr) = author {
+                    if !seen_users.insert(usr.rest_id.clone()) {
+                        continue;
+                    }
+                }
+                match User::from_result_offline(author.clone()) {
+                    Ok(user) => {
+                        users.insert(user);
+                    }
+                    Err(why) => warn!(error = %why, "Failed to get data. Skipping..."),
+                }
+            }
+        }
+
+        Ok(UserTweetsAndReplies {
+            users,
+            tweets,
+            warnings,
+            promoted,
+            next_cursor: request.filter_cursor().map(ToString::to_string),
+        })
+    }
+
+    pub(crate) async fn scroll_timeline(
+        scraper: &Scraper,
+        user_handle: String,
+        timeline: UserTimeline,
+        max_tweets: Option<u32>,
+        start_cursor: Option<&str>,
+    ) -> SResult<Self> {
+        // failed threads were already logged
+        Self::scroll_timeline_outcome(
+            scraper,
+            user_handle,
+            timeline,
+            max_tweets,
+            start_cursor,
+            false,
+        )
+        .await
+        .map(|outcome| outcome.data)
+    }
+
+    // without `keep_partial` the first failed page fails the call
+    async fn scroll_timeline_outcome(
+        scraper: &Scraper,
+        user_handle: String,
+        timeline: UserTimeline,
+        max_tweets: Option<u32>,
+        start_cursor: Option<&str>,
+        keep_partial: bool,
+    ) -> SResult<ScrapeOutcome<Self>> {
         let _span_ = span!(tracing::Level::TRACE, "user_handle", user_handle).entered();
 
         let user = User::new(scraper, &user_handle).await?;
 
-        let timeline_request_url = twitter_request_url_user_tweet_and_replies(user.id, None);
+        let timeline_request_url = timeline.url(user.id, start_cursor, scraper.options());
+
+        let max_tweets = max_tweets
+            .unwrap_or(MAX_TIMELINE_TWEETS)
+            .min(MAX_TIMELINE_TWEETS);
 
         let mut timelines_requests =
-            Vec::with_capacity(user.profile_stats.tweets.min(3200) as usize / 40);
+            Vec::with_capacity(user.profile_stats.tweets.min(max_tweets) as usize / 40 + 1);
+
+        let mut errors = Vec::new();
+        let mut next_cursor = start_cursor.map(ToString::to_string);
 
         let first_request = scraper
             .api_req::<UserTweetAndRepliesRequest>(scraper.make_get_req(timeline_request_url))
-            .await?;
-        // find the cursor
-        let first_cursor = first_request.filter_cursor();
-
-        if let Some(fc) = first_cursor {
-            timelines_requests.append(
-                &mut UserTweetAndRepliesRequest::scroll(scraper, user.id, fc.to_string())
-                    .await?
-                    .into(),
-            );
+            .await
+            .and_then(|request| request.json_request_filter_errors().map(|_| request));
+        match first_request {
+            Ok(first_request) => {
+                // find the cursor
+                let first_cursor = first_request
+                    .next_cursor(start_cursor)
+                    .map(ToString::to_string);
+                let remaining = (max_tweets as usize).saturating_sub(first_request.tweet_count());
+                timelines_requests.push(first_request);
+
+                next_cursor = first_cursor.clone();
+                if let Some(fc) = first_cursor {
+                    if remaining > 0 {
+                        let (scrolled, failed) = UserTweetAndRepliesRequest::scroll_partial(
+                            scraper,
+                            timeline,
+                            user.id,
+                            fc.clone(),
+                            remaining,
+                        )
+                        .await;
+                        // newest page first, each fetched with the cursor of the one after it
+                        if let Some(newest) = scrolled.front() {
+                            let fetched_with = scrolled
+                                .get(1)
+                                .and_then(UserTweetAndRepliesRequest::filter_cursor)
+                                .unwrap_or(&fc);
+                            next_cursor = newest
+                                .next_cursor(Some(fetched_with))
+                                .map(ToString::to_string);
+                        }
+                        timelines_requests.extend(scrolled);
+                        if let Some((cursor, why)) = failed {
+                            if !keep_partial {
+                                return Err(why);
+                            }
+                            warn!(user_handle, cursor, error = %why, "Failed to get timeline page. Stopping.");
+                            errors.push((format!("page {cursor}"), why));
+                            next_cursor = Some(cursor);
+                        }
+                    }
+                }
+            }
+            Err(why) if keep_partial => {
+                let page = start_cursor.unwrap_or("first");
+                warn!(user_handle, page, error = %why, "Failed to get timeline page. Stopping.");
+                errors.push((format!("page {page}"), why));
+            }
+            Err(why) => return Err(why),
         }
 
         // lets parse these requests
 
         let (mut tweets, mut users) = {
-            let mut max = user.profile_stats.tweets;
-            if max > 3200 {
-                // un oh terminally online tankie posting alert
-                max = 3200;
-            }
+            // un oh terminally online tankie posting alert
+            let max = user.profile_stats.tweets.min(max_tweets);
             (
                 HashSet::with_capacity(max as usize * 2),
                 HashSet::with_capacity((user.profile_stats.following as usize).min(200)),
             )
         };
 
+        let mut warnings = Vec::new();
+        let mut promoted = Vec::new();
+        let mut pinned = None;
+        // thread roots in timeline order, crawled `thread_parallelism` at a time below
+        let mut roots = Vec::new();
+        // conversation ids already queued, plus the tweet ids of conversation modules that asked
+        // for deduplication. replies show up next to their conversation, no need to crawl it twice.
+        let mut seen_roots = HashSet::new();
+
         for request in timelines_requests {
+            // fatal codes already failed the page when it came in, whatever is left is a warning.
+            // bailing here would throw away the pages already fetched.
+            warnings.extend(
+                request
+                    .errors
+                    .iter()
+                    .filter(|why| why.error_code() != TwitterErrorCode::Ignore)
+                    .map(|why| TwitterWarning {
+                        code: why.error_code(),
+                        message: why.message.clone(),
+                    }),
+            );
             for inst in request.data.user.result.timeline_v2.timeline.instructions {
+                if let Instruction::TimelinePinEntry(pin) = &inst {
+                    if let Some(id) = pin
+                        .entry
+                        .content
+                        .item_content
+                        .tweet_results
+                        .rest_id_or_skip()
+                    {
+                        pinned = Some(id.to_string());
+                    }
+                }
                 if let Instruction::TimelineAddEntries(add) = inst {
                     for entry in add.entries {
+                        let is_promoted = matches!(entry, Entry::Promoted(_));
                         match entry {
                             Entry::HomeConversation(homeconvo) => {
                                 let first = homeconvo.content.items.first();
                                 let last = homeconvo.content.items.last();
-                                let equal = first == last;
 
                                 match (first, last) {
                                     (Some(f), Some(l)) => {
-                                        let firstid = match &f.item.tweet_results {
-                                            TweetResults::Ok(t) => t.rest_id.clone(),
-                                            TweetResults::Tombstone(tomb) => continue,
-                                        };
-
-                                        let (mut twts, mut usrs) = match Tweet::parse_thread(
-                                            scraper, &firstid,
-                                        )
-                                        .await
-                                        {
-                                            Ok(x) => x,
-                                            Err(why) => {
-                                                warn!(
-                                                            user_handle,
-                                                            tweet = firstid,
-                                                            error = why,
-                                                            "Failed to get tweet for user timeline. Continuing."
-                                                        );
-                                                continue;
-                                            }
-                                        };
-
-                                        tweets.append(&mut twts);
-                                        users.append(&mut usrs);
-                                        if !equal {
-                                            let firstid = match &l.item.tweet_results {
-                                                TweetResults::Ok(t) => t.rest_id.clone(),
-                                                TweetResults::Tombstone(tomb) => continue,
-                                            };
-
-                                            let (mut twts, mut usrs) = match Tweet::parse_thread(
-                                                scraper, &firstid,
-                                            )
-                                            .await
-                                            {
-                                                Ok(x) => x,
-                                                Err(why) => {
-                                                    warn!(
-                                                            user_handle,
-                                                            tweet = firstid,
-                                                            error = why,
-                                                            "Failed to get tweet for user timeline. Continuing."
-                                                        );
-                                                    continue;
-                                                }
-                                            };
-
-                                            tweets.append(&mut twts);
-                                            users.append(&mut usrs);
+                                        let root = [f, l].into_iter().find_map(|item| {
+                                            conversation_root(&item.item.tweet_results)
+                                        });
+                                        match root {
+                                            Some(root) if seen_roots.insert(root.to_string()) => {}
+                                            _ => continue,
+                                        }
+                                        let meta = &homeconvo.content.metadata;
+                                        if meta.enable_deduplication {
+                                            seen_roots.extend(meta.all_tweet_ids.iter().cloned());
                                         }
+                                        // one crawl per conversation, the thread of its newest
+                                        // tweet reaches back to the first
+                                        let newest = [l, f].into_iter().find_map(|item| {
+                                            item.item.tweet_results.rest_id_or_skip()
+                                        });
+                                        roots.extend(newest.map(ToString::to_string));
                                     }
                                     (_, _) => {
                                         warn!(
@@ -160,37 +637,146 @@ impl UserTweetsAndReplies {
                                     }
                                 }
                             }
-                            Entry::Tweet(tweet) => {
-                                let firstid = match &tweet.item_content.tweet_results {
-                                    TweetResults::Ok(t) => t.rest_id.clone(),
-                                    TweetResults::Tombstone(tomb) => continue,
-                                };
-
-                                let (mut twts, mut usrs) =
-                                    match Tweet::parse_thread(scraper, &firstid).await {
-                                        Ok(x) => x,
-                                        Err(why) => {
-                                            warn!(
-                                            user_handle,
-                                            tweet = firstid,
-                                            error = why,
-                                            "Failed to get tweet for user timeline. Continuing."
-                                        );
-                                            continue;
-                                        }
+                            Entry::Tweet(tweet) | Entry::Promoted(tweet) => {
+                                let firstid =
+                                    match tweet.item_content.tweet_results.rest_id_or_skip() {
+                                        Some(id) => id.to_string(),
+                                        None => continue,
                                     };
-
-                                tweets.append(&mut twts);
-                                users.append(&mut usrs);
+                                if is_promoted {
+                                    if let Ok(id) = firstid.parse() {
+                                        promoted.push(id);
+                                    }
+                                }
+                                if seen_roots.contains(&firstid) {
+                                    continue;
+                                }
+                                match conversation_root(&tweet.item_content.tweet_results) {
+                                    Some(root) if seen_roots.insert(root.to_string()) => {}
+                                    _ => continue,
+                                }
+                                roots.push(firstid);
                             }
-                            Entry::Cursor(_) => continue,
+                            Entry::Cursor(_) | Entry::Unknown(_) => continue,
                         }
                     }
                 }
             }
         }
 
-        Ok(UserTweetsAndReplies { users, tweets })
+        // every request still goes through the scraper's delays and concurrency limit, this only
+        // keeps more of them in flight. merged once they're all back.
+        let threads = stream::iter(roots)
+            .map(|id| async move {
+                // cancelled, threads already in flight still finish
+                if scraper.is_cancelled() {
+                    return None;
+                }
+                let thread = Tweet::parse_thread(scraper, id.as_str(), None).await;
+                Some((id, thread))
+            })
+            .buffer_unordered(scraper.thread_parallelism())
+            .filter_map(future::ready)
+            .collect::<Vec<_>>()
+            .await;
+        for (id, thread) in threads {
+            match thread {
+                Ok((twts, usrs)) => {
+                    tweets.extend(twts);
+                    users.extend(usrs);
+                }
+                Err(why) => {
+                    warn!(
+                        user_handle,
+                        tweet = id,
+                        error = %why,
+                        "Failed to get tweet for user timeline. Continuing."
+                    );
+                    errors.push((format!("tweet {id}"), why));
+                }
+            }
+        }
+
+        // the pin usually shows up in the timeline as well, only crawl it if it didn't
+        if let Some(pinned) = pinned {
+            let already_crawled = pinned
+                .parse::<TweetId>()
+                .map(|id| tweets.iter().any(|tweet: &Tweet| tweet.id == id))
+                .unwrap_or(false);
+            if !already_crawled && !scraper.is_cancelled() {
+                match Tweet::parse_thread(scraper, pinned.as_str(), None).await {
+                    Ok((twts, usrs)) => {
+                        tweets.extend(twts);
+                        users.extend(usrs);
+                    }
+                    Err(why) => {
+                        warn!(
+                            user_handle,
+                            tweet = pinned,
+                            error = %why,
+                            "Failed to get pinned tweet for user timeline. Continuing."
+                        );
+                        errors.push((format!("tweet {pinned}"), why));
+                    }
+                }
+            }
+        }
+
+        Ok(ScrapeOutcome {
+            data: UserTweetsAndReplies {
+                users,
+                tweets,
+                warnings,
+                promoted,
+                next_cursor,
+            },
+            errors,
+        })
+    }
+}
+
+// a user's tweets and retweets without their replies. cheaper than filtering
+// `UserTweetsAndReplies` as twitter leaves the replies out server side.
+#[derive(
+    Clone,
+    Debug,
+    PartialEq,
+    Eq,
+    Hash,
+    Serialize,
+    Deserialize,
+    Archive,
+    rkyv::Serialize,
+    rkyv::Deserialize,
+)]
+pub struct UserTweets(pub UserTweetsAndReplies);
+
+impl Deref for UserTweets {
+    type Target = UserTweetsAndReplies;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+#[cfg(feature = "scrape")]
+impl UserTweets {
+    // `max_tweets` and cancellation work like `UserTweetsAndReplies::scroll_user_timeline`
+    #[tracing::instrument]
+    pub async fn scroll(
+        scraper: &Scraper,
+        user_handle: String,
+        max_tweets: Option<u32>,
+    ) -> SResult<Self> {
+        UserTweetsAndReplies::scroll_timeline(
+            scraper,
+            user_handle,
+            UserTimeline::Tweets,
+            max_tweets,
+            None,
+        )
+        .await
+        .map(UserTweets)
     }
 }
 
@@ -206,71 +792,187 @@ impl UserTweetsAndReplies {
     rkyv::Serialize,
     rkyv::Deserialize,
 )]
-pub(crate) struct UserTweetAndRepliesRequest {
-    pub errors: Vec<Error>,
-    pub data: UserTARData,
+pub struct UserTweetAndRepliesRequest {
+    #[serde(default)]
+    pub(crate) errors: Vec<Error>,
+    pub(crate) data: UserTARData,
 }
 
+// the conversation a timeline tweet belongs to, the tweet itself if twitter left it out
 #[cfg(feature = "scrape")]
-impl UserTweetAndRepliesRequest {
-    pub(crate) fn json_request_filter_errors(&self) -> SResult<()> {
-        if let Some(why) = self.errors.first() {
-            if why.code != 37 {
-                return Err(TwitterJSONError(why.code, why.message.clone()));
+fn conversation_root(results: &TweetResults) -> Option<&str> {
+    let id = results.rest_id_or_skip()?;
+    results
+        .ok()
+        .map(|trr| trr.legacy.conversation_id_str.as_str())
+        .filter(|root| !root.is_empty() && *root != "0")
+        .or(Some(id))
+}
+
+// shared by every timeline made of the same entries as a user's, e.g. lists
+#[cfg(feature = "scrape")]
+pub(crate) fn bottom_cursor(instructions: &[Instruction]) -> Option<&str> {
+    for inst in instructions {
+        if let Instruction::TimelineAddEntries(add) = inst {
+            let bottom =
+                FilterCursorTweetRequest::Bottom.pick(add.entries.iter().filter_map(|entry| {
+                    match entry {
+                        Entry::Cursor(c) => Some(c),
+                        _ => None,
+                    }
+                }));
+            if bottom.is_some() {
+                return bottom;
             }
         }
-        Ok(())
+    }
+
+    None
+}
+
+#[cfg(feature = "scrape")]
+pub(crate) fn instruction_tweet_results(
+    instructions: &[Instruction],
+) -> impl Iterator<Item = (&TweetResults, bool)> {
+    instructions
+        .iter()
+        .filter_map(|inst| match inst {
+            Instruction::TimelineAddEntries(add) => Some(&add.entries),
+            _ => None,
+        })
+        .flatten()
+        .flat_map(|entry| match entry {
+            Entry::HomeConversation(homeconvo) => homeconvo
+                .content
+                .items
+                .iter()
+                .map(|item| (&item.item.tweet_results, false))
+                .collect(),
+            Entry::Tweet(tweet) => vec![(&tweet.item_content.tweet_results, false)],
+            Entry::Promoted(tweet) => vec![(&tweet.item_content.tweet_results, true)],
+            Entry::Cursor(_) | Entry::Unknown(_) => vec![],
+        })
+}
+
+#[cfg(feature = "scrape")]
+impl UserTweetAndRepliesRequest {
+    // a timeline page saved earlier, nothing is requested
+    pub fn from_json(json: &str) -> SResult<Self> {
+        Scraper::parse_body(json.as_bytes())
+    }
+
+    pub(crate) fn json_request_filter_errors(&self) -> SResult<Vec<TwitterWarning>> {
+        collect_warnings(&self.errors)
     }
 
     pub(crate) fn filter_cursor(&self) -> Option<&str> {
-        for inst in &self.data.user.result.timeline_v2.timeline.instructions {
-            if let Instruction::TimelineAddEntries(add) = inst {
-                for entry in &add.entries {
-                    if let Entry::Cursor(c) = entry {
-                        if c.content.item_content.cursor_type.starts_with("Bottom") {
-                            return Some(&c.content.item_content.value);
-                        }
-                    }
-                }
-            }
-        }
+        bottom_cursor(&self.data.user.result.timeline_v2.timeline.instructions)
+    }
+
+    // every tweet on the page with whether it is an ad, tombstones included
+    pub(crate) fn tweet_results(&self) -> impl Iterator<Item = (&TweetResults, bool)> {
+        instruction_tweet_results(&self.data.user.result.timeline_v2.timeline.instructions)
+    }
+
+    // the bottom cursor to carry on from. `None` once twitter ran out of pages, past the end it
+    // still hands out a cursor, on a page without tweets or the same one the page was fetched with
+    pub(crate) fn next_cursor(&self, fetched_with: Option<&str>) -> Option<&str> {
+        self.filter_cursor()
+            .filter(|cursor| self.tweet_count() > 0 && Some(*cursor) != fetched_with)
+    }
 
-        None
+    // timeline entries on this page, a conversation counts every tweet in it
+    pub(crate) fn tweet_count(&self) -> usize {
+        self.data
+            .user
+            .result
+            .timeline_v2
+            .timeline
+            .instructions
+            .iter()
+            .filter_map(|inst| match inst {
+                Instruction::TimelineAddEntries(add) => Some(&add.entries),
+                _ => None,
+            })
+            .flatten()
+            .map(|entry| match entry {
+                Entry::HomeConversation(homeconvo) => homeconvo.content.items.len(),
+                Entry::Tweet(_) | Entry::Promoted(_) => 1,
+                Entry::Cursor(_) | Entry::Unknown(_) => 0,
+            })
+            .sum()
     }
 
+    // stops once `max_tweets` timeline entries were fetched
     #[tracing::instrument]
     pub(crate) async fn scroll(
         scraper: &Scraper,
-        id: u64,
+        timeline: UserTimeline,
+        id: UserId,
         first_cursor: String,
+        max_tweets: usize,
     ) -> SResult<VecDeque<Self>> {
-        let mut requests = VecDeque::with_capacity(5);
-
-        let mut cursor_counter = first_cursor.to_string();
-        let mut break_on_next = false;
-        loop {
-            let scrolled_up_request = scraper
-                .api_req::<UserTweetAndRepliesRequest>(scraper.make_get_req(
-                    twitter_request_url_user_tweet_and_replies(id, Some(&cursor_counter)),
-                ))
-                .await?;
-
-            scrolled_up_request.json_request_filter_errors()?;
-
-            requests.push_front(scrolled_up_request);
-            if break_on_next {
-                break;
-            }
+        match Self::scroll_partial(scraper, timeline, id, first_cursor, max_tweets).await {
+            (_, Some((_, why))) => Err(why),
+            (requests, None) => Ok(requests),
+        }
+    }
 
-            match scrolled_up_request.filter_cursor() {
-                Some(bottom) => {
-                    cursor_counter = bottom.to_string();
-                }
-                None => break_on_next = true,
-            }
+    // like `scroll` but a failed page hands back the pages before it, and the cursor that failed
+    #[tracing::instrument]
+    pub(crate) async fn scroll_partial(
+        scraper: &Scraper,
+        timeline: UserTimeline,
+        id: UserId,
+        first_cursor: String,
+        max_tweets: usize,
+    ) -> (VecDeque<Self>, Option<(String, TwtScrapeError)>) {
+        paginate_partial(
+            scraper,
+            FilterCursorTweetRequest::Bottom,
+            first_cursor,
+            Some(max_tweets),
+            |cursor| timeline.url(id, Some(cursor), scraper.options()),
+        )
+        .await
+    }
+
+    // the pages from `cursor` on, or from the first page on `None`, handed out as they arrive.
+    // dropping the stream stops the scroll
+    pub(crate) fn scroll_stream(
+        scraper: &Scraper,
+        timeline: UserTimeline,
+        id: UserId,
+        cursor: Option<String>,
+    ) -> impl Stream<Item = SResult<Self>> + '_ {
+        let pager = Pager::new(
+            scraper,
+            FilterCursorTweetRequest::Bottom,
+            cursor,
+            move |cursor| timeline.url(id, cursor, scraper.options()),
+        );
+        stream::try_unfold(pager, |mut pager| async move {
+            let page = pager.next_page::<UserTweetAndRepliesRequest>().await?;
+            Ok(page.map(|page| (page, pager)))
+        })
+    }
+}
+
+#[cfg(feature = "scrape")]
+crate::impl_filter_json!(UserTweetAndRepliesRequest);
+
+#[cfg(feature = "scrape")]
+impl HasCursor for UserTweetAndRepliesRequest {
+    // user timelines only scroll down
+    fn filter_cursor(&self, filter: FilterCursorTweetRequest) -> Option<&str> {
+        match filter {
+            FilterCursorTweetRequest::Top => None,
+            FilterCursorTweetRequest::Bottom => UserTweetAndRepliesRequest::filter_cursor(self),
         }
+    }
 
-        Ok(requests)
+    fn entry_count(&self) -> usize {
+        self.tweet_count()
     }
 }
 
@@ -418,8 +1120,9 @@ pub(crate) struct TlPinEntryEntry {
     rkyv::Serialize,
     rkyv::Deserialize,
 )]
+#[serde(rename_all = "camelCase")]
 pub(crate) struct TlPinContent {
-    #[serde(rename = "itemContent")]
+    #[serde(alias = "item_content")]
     pub item_content: TweetItemContent,
 }
 
@@ -439,11 +1142,167 @@ pub(crate) struct TimelineAddEntry {
     pub entries: Vec<Entry>,
 }
 
-#[derive(Clone, Debug, Hash, PartialEq, Serialize, Archive, rkyv::Serialize, rkyv::Deserialize)]
+#[cfg(feature = "scrape")]
+#[test]
+fn unknown_entries_are_kept_by_id() {
+    let add = serde_json::from_str::<TimelineAddEntry>(
+        r#"{"entries":[{"entryId":"who-to-follow-1716839405628674048","sortIndex":"1716839405628674048","content":{"entryType":"TimelineTimelineModule","__typename":"TimelineTimelineModule","items":[{"entryId":"who-to-follow-1716839405628674048-user-44196397","item":{"itemContent":{"itemType":"TimelineUser","__typename":"TimelineUser","userDisplayType":"User"}}}],"displayType":"Vertical","header":{"displayType":"Classic","text":"Who to follow","sticky":false},"clientEventInfo":{"component":"suggest_who_to_follow"}}},{"entryId":"cursor-bottom-1716839405628674047","sortIndex":"1716839405628674047","content":{"entryType":"TimelineTimelineItem","__typename":"TimelineTimelineItem","itemContent":{"itemType":"TimelineTimelineCursor","__typename":"TimelineTimelineCursor","value":"HBaAwLmVh","cursorType":"Bottom"}}}]}"#,
+    )
+    .unwrap();
+    assert_eq!(add.entries.len(), 2);
+    assert_eq!(
+        add.entries[0],
+        Entry::Unknown("who-to-follow-1716839405628674048".to_string())
+    );
+    assert!(matches!(add.entries[1], Entry::Cursor(_)));
+}
+
+#[cfg(feature = "scrape")]
+#[test]
+fn tweet_entries_by_id() {
+    let add = serde_json::from_str::<TimelineAddEntry>(
+        r#"{"entries":[{"entryId":"tweet-1212092628029698048","sortIndex":"1","content":{"entryType":"TimelineTimelineItem","__typename":"TimelineTimelineItem","itemContent":{"itemType":"TimelineTweet","__typename":"TimelineTweet","tweet_results":{"__typename":"Tombstone","tombstone":{"__typename":"TextTombstone","text":{"rtl":false,"text":"This Tweet was deleted by the Tweet author."}}}}}},{"entryId":"promoted-tweet-1212092628029698049","sortIndex":"0","content":{"entryType":"TimelineTimelineItem","__typename":"TimelineTimelineItem","itemContent":{"itemType":"TimelineTweet","__typename":"TimelineTweet","tweet_results":{"__typename":"Tombstone","tombstone":{"__typename":"TextTombstone","text":{"rtl":false,"text":"This Tweet was deleted by the Tweet author."}}}}}}]}"#,
+    )
+    .unwrap();
+    assert!(matches!(add.entries[0], Entry::Tweet(_)));
+    assert!(matches!(add.entries[1], Entry::Promoted(_)));
+    assert!(serde_json::from_str::<Entry>(r#"{"content":{}}"#).is_err());
+}
+
+#[cfg(feature = "scrape")]
+#[test]
+fn content_before_entry_id() {
+    let add = serde_json::from_str::<TimelineAddEntry>(
+        r#"{"entries":[{"content":{"entryType":"TimelineTimelineItem","__typename":"TimelineTimelineItem","itemContent":{"itemType":"TimelineTweet","__typename":"TimelineTweet","tweet_results":{"__typename":"Tombstone","tombstone":{"__typename":"TextTombstone","text":{"rtl":false,"text":"This Tweet was deleted by the Tweet author."}}}}},"sortIndex":"1","entryId":"tweet-1212092628029698048"},{"content":{"entryType":"TimelineTimelineItem","__typename":"TimelineTimelineItem","itemContent":{"itemType":"TimelineTimelineCursor","__typename":"TimelineTimelineCursor","value":"HBaAwLmVh","cursorType":"Bottom"}},"entryId":"cursor-bottom-1716839405628674047"}]}"#,
+    )
+    .unwrap();
+    assert!(matches!(add.entries[0], Entry::Tweet(_)));
+    match &add.entries[1] {
+        Entry::Cursor(cursor) => {
+            assert_eq!(cursor.entry_id, "cursor-bottom-1716839405628674047")
+        }
+        other => panic!("expected a cursor, got {other:?}"),
+    }
+}
+
+// a user timeline page holding `entries`, likes come in the same shape
+#[cfg(all(test, feature = "scrape"))]
+pub(crate) fn user_timeline_page(entries: Vec<serde_json::Value>) -> String {
+    serde_json::json!({
+        "data": {"user": {"result": {"__typename": "User", "timeline_v2": {"timeline": {
+            "instructions": [{"type": "TimelineAddEntries", "entries": entries}],
+        }}}}},
+    })
+    .to_string()
+}
+
+#[cfg(feature = "scrape")]
+#[test]
+fn page_parsed_offline() {
+    use crate::tweet::{cursor_entry_fixture, tweet_entry_fixture, tweet_results_fixture};
+
+    let request = UserTweetAndRepliesRequest::from_json(
+        r#"{"data":{"user":{"result":{"__typename":"User","timeline_v2":{"timeline":{"instructions":[{"type":"TimelineClearCache"},{"type":"TimelineAddEntries","entries":[{"entryId":"cursor-bottom-1716839405628674047","sortIndex":"1716839405628674047","content":{"entryType":"TimelineTimelineItem","__typename":"TimelineTimelineItem","itemContent":{"itemType":"TimelineTimelineCursor","__typename":"TimelineTimelineCursor","value":"HBaAwLmVh","cursorType":"Bottom"}}}]}]}}}}}}"#,
+    )
+    .unwrap();
+    let page = UserTweetsAndReplies::from_request(request).unwrap();
+    assert!(page.tweets.is_empty());
+    assert_eq!(page.next_cursor.as_deref(), Some("HBaAwLmVh"));
+
+    let good = 1700000000000000001;
+    let broken = 1700000000000000002;
+    let request = UserTweetAndRepliesRequest::from_json(&user_timeline_page(vec![
+        tweet_entry_fixture(good, tweet_results_fixture(good, 12, serde_json::json!({}))),
+        tweet_entry_fixture(
+            broken,
+            tweet_results_fixture(broken, 13, serde_json::json!({"created_at": "yesterday"})),
+        ),
+        cursor_entry_fixture("Bottom", "second"),
+    ]))
+    .unwrap();
+    let page = UserTweetsAndReplies::from_request(request).unwrap();
+    assert_eq!(
+        page.tweets.iter().map(|tweet| tweet.id).collect::<Vec<_>>(),
+        vec![good]
+    );
+    // the author of a tweet that failed to parse is left out too
+    assert_eq!(page.users.len(), 1);
+    assert_eq!(page.next_cursor.as_deref(), Some("second"));
+
+    assert!(UserTweetAndRepliesRequest::from_json(r#"{"data":{}}"#).is_err());
+}
+
+#[cfg(feature = "scrape")]
+#[test]
+fn next_cursor_past_the_end() {
+    use crate::tweet::{cursor_entry_fixture, tweet_entry_fixture, tweet_results_fixture};
+
+    let tweet = 1700000000000000001;
+    let page = UserTweetAndRepliesRequest::from_json(&user_timeline_page(vec![
+        tweet_entry_fixture(
+            tweet,
+            tweet_results_fixture(tweet, 12, serde_json::json!({})),
+        ),
+        cursor_entry_fixture("Bottom", "second"),
+    ]))
+    .unwrap();
+    assert_eq!(page.next_cursor(None), Some("second"));
+    assert_eq!(page.next_cursor(Some("first")), Some("second"));
+    assert_eq!(page.next_cursor(Some("second")), None);
+
+    let empty =
+        UserTweetAndRepliesRequest::from_json(&user_timeline_page(vec![cursor_entry_fixture(
+            "Bottom", "third",
+        )]))
+        .unwrap();
+    assert_eq!(empty.next_cursor(Some("second")), None);
+}
+
+#[cfg(feature = "scrape")]
+#[test]
+fn stream_fetches_pages_as_they_are_read() {
+    use crate::scrape::dry_run;
+    use crate::tweet::{cursor_entry_fixture, tweet_entry_fixture, tweet_results_fixture};
+
+    let page = |id: u64, cursor: &str| {
+        user_timeline_page(vec![
+            tweet_entry_fixture(id, tweet_results_fixture(id, 12, serde_json::json!({}))),
+            cursor_entry_fixture("Bottom", cursor),
+        ])
+    };
+    let canned = vec![
+        page(1700000000000000001, "second"),
+        page(1700000000000000002, "third"),
+    ];
+
+    dry_run(canned, |scraper| async move {
+        let pages = UserTweetAndRepliesRequest::scroll_stream(
+            &scraper,
+            UserTimeline::TweetsAndReplies,
+            UserId(1),
+            None,
+        )
+        .take(1)
+        .collect::<Vec<_>>()
+        .await;
+        assert_eq!(pages.len(), 1);
+        assert_eq!(pages[0].as_ref().unwrap().tweet_count(), 1);
+        // the next page is only requested once the stream is read past the first
+        assert_eq!(scraper.stop_dry_run().len(), 1);
+    });
+}
+
+#[derive(
+    Clone, Debug, Hash, PartialEq, Eq, Serialize, Archive, rkyv::Serialize, rkyv::Deserialize,
+)]
 pub(crate) enum Entry {
     HomeConversation(HomeConversation),
     Tweet(TweetEnt),
+    // an ad twitter slotted into the timeline, see `ScrapeOptions::include_promoted_content`
+    Promoted(TweetEnt),
     Cursor(Cursor),
+    // modules twitter keeps adding (`who-to-follow-`, `toptabsrpusermodule-`), only the id is kept
+    Unknown(String),
 }
 
 impl<'de> Deserialize<'de> for Entry {
@@ -453,8 +1312,9 @@ impl<'de> Deserialize<'de> for Entry {
     {
         enum Field {
             EntryId,
-            SortId,
             Content,
+            // `sortIndex` and whatever else twitter sends along
+            Ignored,
         }
 
         impl<'de> Deserialize<'de> for Field {
@@ -477,12 +1337,8 @@ impl<'de> Deserialize<'de> for Entry {
                     {
                         match value {
                             "entryId" => Ok(Field::EntryId),
-                            "sortId" => Ok(Field::SortId),
                             "content" => Ok(Field::Content),
-                            _ => Err(de::Error::unknown_field(
-                                value,
-                                &["entryId", "sortId", "content"],
-                            )),
+                            _ => Ok(Field::Ignored),
                         }
                     }
                 }
@@ -491,6 +1347,46 @@ impl<'de> Deserialize<'de> for Entry {
             }
         }
 
+        // the id says what the content is
+        fn from_content<'de, D>(id: &str, content: D) -> Result<Entry, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            Ok(if id.starts_with("tweet-") {
+                Entry::Tweet(TweetEnt::deserialize(content)?)
+            } else if id.starts_with("promoted-tweet-") {
+                Entry::Promoted(TweetEnt::deserialize(content)?)
+            } else if id.starts_with("homeConversation-")
+                || id.starts_with("profile-conversation-")
+                || id.starts_with("list-conversation-")
+            {
+                Entry::HomeConversation(HomeConversation {
+                    content: Deserialize::deserialize(content)?,
+                })
+            } else if id.starts_with("cursor-") {
+                Entry::Cursor(Cursor {
+                    entry_id: id.to_string(),
+                    content: Deserialize::deserialize(content)?,
+                })
+            } else {
+                de::IgnoredAny::deserialize(content)?;
+                Entry::Unknown(id.to_string())
+            })
+        }
+
+        struct ContentSeed<'a>(&'a str);
+
+        impl<'de> DeserializeSeed<'de> for ContentSeed<'_> {
+            type Value = Entry;
+
+            fn deserialize<D>(self, deserializer: D) -> Result<Entry, D::Error>
+            where
+                D: Deserializer<'de>,
+            {
+                from_content(self.0, deserializer)
+            }
+        }
+
         struct EntryVisitor;
 
         impl<'de> Visitor<'de> for EntryVisitor {
@@ -505,44 +1401,35 @@ impl<'de> Deserialize<'de> for Entry {
                 V: MapAccess<'de>,
             {
                 let mut entry_id: Option<String> = None;
-                let mut __typename: Option<String> = None;
-                let mut sort_id: Option<String> = None;
+                let mut entry: Option<Entry> = None;
+                // content that showed up before the id, parsed once the id is known
+                let mut buffered: Option<serde_json::Value> = None;
                 while let Some(key) = map.next_key()? {
                     match key {
                         Field::EntryId => {
                             entry_id = Some(map.next_value()?);
                         }
-                        Field::SortId => {
-                            sort_id = Some(map.next_value()?);
-                        }
-                        Field::Content => {
-                            if let Some(entry) = &entry_id {
-                                if entry.starts_with("tweet-") {
-                                    Ok(Entry::Tweet(map.next_value()?))
-                                } else if entry.starts_with("homeConversation-") {
-                                    Ok(Entry::ConversationThread(map.next_value()?))
-                                } else if entry.starts_with("cursor-") {
-                                    Ok(Entry::Cursor(map.next_value()?))
-                                } else {
-                                    Err(de::Error::unknown_variant(
-                                        entry,
-                                        &["tweet", "homeConversation", "cursor"],
-                                    ))
-                                }
-                            }
-                            Err(de::Error::unknown_variant(
-                                "None",
-                                &["tweet", "homeConversation", "cursor"],
-                            ))
+                        Field::Content => match entry_id.as_deref() {
+                            Some(id) => entry = Some(map.next_value_seed(ContentSeed(id))?),
+                            None => buffered = Some(map.next_value()?),
+                        },
+                        Field::Ignored => {
+                            map.next_value::<de::IgnoredAny>()?;
                         }
                     }
                 }
-                Err(de::Error::missing_field("content"))
+                match (entry, entry_id, buffered) {
+                    (Some(entry), _, _) => Ok(entry),
+                    (None, Some(id), Some(content)) => {
+                        from_content(&id, content).map_err(de::Error::custom)
+                    }
+                    (None, None, _) => Err(de::Error::missing_field("entryId")),
+                    (None, Some(_), None) => Err(de::Error::missing_field("content")),
+                }
             }
         }
 
-        const VARIANTS: &[&str] = &["Tweet", "homeConversation", "Cursor"];
-        deserializer.deserialize_enum("Entry", VARIANTS, EntryVisitor)
+        deserializer.deserialize_map(EntryVisitor)
     }
 }
 
@@ -607,8 +1494,9 @@ pub(crate) struct HCItem {
     rkyv::Serialize,
     rkyv::Deserialize,
 )]
+#[serde(rename_all = "camelCase")]
 pub(crate) struct HCConversationMeta {
-    #[serde(rename = "allTweetIds")]
     pub all_tweet_ids: Vec<String>,
+    #[serde(rename = "enable_deduplication")]
     pub enable_deduplication: bool,
 }